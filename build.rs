@@ -0,0 +1,42 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Schemas exported by `examples/schema.rs` that `QueryMsg::Schema` serves.
+const SCHEMAS: [&str; 6] = [
+    "instantiate_msg",
+    "execute_msg",
+    "query_msg",
+    "migrate_msg",
+    "sudo_msg",
+    "receive_msg",
+];
+
+fn main() {
+    let out_dir = env::var("OUT_DIR").unwrap();
+    for name in SCHEMAS {
+        let source = format!("schema/{}.json", name);
+        println!("cargo:rerun-if-changed={}", source);
+        let json = fs::read_to_string(&source).unwrap();
+        let target = Path::new(&out_dir).join(format!("{}.json", name));
+        fs::write(target, minify_json(&json)).unwrap();
+    }
+}
+
+/// Drops the whitespace between JSON tokens, leaving strings untouched.
+fn minify_json(json: &str) -> Vec<u8> {
+    let mut minified = Vec::with_capacity(json.len());
+    let (mut in_string, mut escaped) = (false, false);
+    for byte in json.bytes() {
+        if in_string {
+            in_string = escaped || byte != b'"';
+            escaped = !escaped && byte == b'\\';
+        } else if byte.is_ascii_whitespace() {
+            continue;
+        } else {
+            in_string = byte == b'"';
+        }
+        minified.push(byte);
+    }
+    minified
+}
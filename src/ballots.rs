@@ -0,0 +1,60 @@
+use cosmwasm_std::{Addr, Order, StdResult, Storage};
+use cw_storage_plus::Bound;
+
+use crate::contract::{flag_retired, retired_options};
+use crate::state::{Ballot, BALLOTS, POLL_VOTERS, PRUNING};
+
+/// Streams a poll's ballots in voter address order, loading each one lazily so
+/// embedding contracts can walk large polls inside their own handlers without
+/// collecting them first. Resume a partial walk by passing the last voter seen
+/// as `start_after`.
+///
+/// This reads storage directly, so the poll's `privacy` level is not applied;
+/// embedding contracts are expected to enforce it themselves.
+pub fn poll_ballots<'a>(
+    storage: &'a dyn Storage,
+    poll_id: &'a str,
+    start_after: Option<&'a Addr>,
+) -> Box<dyn Iterator<Item = StdResult<(Addr, Ballot)>> + 'a> {
+    let start = start_after.map(Bound::exclusive);
+    let voters = POLL_VOTERS
+        .prefix(poll_id)
+        .keys(storage, start, None, Order::Ascending);
+
+    let mut retired = None;
+    Box::new(voters.map(move |voter| {
+        let voter = voter?;
+        let ballot = BALLOTS.load(storage, (voter.clone(), poll_id))?;
+        let retired = match &mut retired {
+            Some(retired) => retired,
+            None => retired.insert(retired_options(storage, poll_id)?),
+        };
+        Ok((voter, flag_retired(retired, ballot)))
+    }))
+}
+
+/// Streams every ballot cast by `voter`, keyed by poll id, skipping those of
+/// deleted polls still being pruned.
+pub fn voter_ballots<'a>(
+    storage: &'a dyn Storage,
+    voter: &Addr,
+    start_after: Option<&'a str>,
+) -> Box<dyn Iterator<Item = StdResult<(String, Ballot)>> + 'a> {
+    let start = start_after.map(Bound::exclusive);
+    let ballots = BALLOTS
+        .prefix(voter.clone())
+        .range(storage, start, None, Order::Ascending);
+
+    Box::new(
+        ballots
+            .filter(move |item| match item {
+                Ok((poll_id, _)) => !PRUNING.has(storage, poll_id),
+                Err(_) => true,
+            })
+            .map(move |item| {
+                let (poll_id, ballot) = item?;
+                let retired = retired_options(storage, &poll_id)?;
+                Ok((poll_id, flag_retired(&retired, ballot)))
+            }),
+    )
+}
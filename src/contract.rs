@@ -1,19 +1,105 @@
 use crate::error::ContractError;
+use crate::legacy::{split_counters, upgrade_v1};
 use crate::msg::{
-    AllPollsResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, PollResponse, QueryMsg,
-    VoteResponse,
+    AllBallotsForPollResponse, AllPollsResponse, AllVotesForAUserResponse, ArchivedPollsResponse,
+    ArchivedResultResponse, ChamberResult, ChamberResultsResponse, ClaimsResponse, ConfigResponse,
+    CreatePollResponse, CreatePollsResponse, CreditsResponse, DraftInfo, DraftsResponse,
+    ExecuteMsg, ExportTemplateResponse, FeedResponse, GatewaysResponse, InstantiateMsg,
+    LeaderboardEntry, LeaderboardResponse, Member, MembersResponse, MigrateMsg, ModeratorsResponse,
+    MutedCreatorsResponse, NoisCallback, NoisProxyExecuteMsg, PausedResponse,
+    PermittedMsgsResponse, PollDepositResponse, PollInfo, PollInput, PollResponse,
+    PollSeriesResponse, PollSummariesResponse, PollSummary, PollVoter, PollVotersResponse,
+    QueryMsg, ReadOnlyResponse, ReceiveMsg, ReputationParamsResponse, ReputationResponse,
+    ResultsResponse, SchemaKind, SchemaResponse, SignedPollPayload, SimulateVoteResponse,
+    SpaceInfoResponse, StatsResponse, StorageStatsResponse, StreakResponse, SudoMsg,
+    TemplateExport, TemplatesResponse, TopVotersResponse, TotalPowerAtHeightResponse,
+    VerifyBallotsResponse, VerifyVoteProofResponse, VoteProofResponse, VoteResponse,
+    VotingPowerResponse, WatchersResponse, WinnerResponse,
 };
-use crate::state::{Ballot, Config, Poll, BALLOTS, CONFIG, POLLS};
+use crate::state::{
+    ArchivedPoll, Ballot, BasketDenom, BlockStamp, CapturedData, Chamber, Config, ConfigChanges,
+    DataCapture, DataSource, DepositPricing, Execution, Finalizer, Label, LotteryDraw,
+    OptionDetails, PermittedMsg, Poll, PollOption, PollResult, PollRules, PollStatus, PollTotals,
+    Privacy, Reputation, ReputationParams, Resolution, RetiredOption, ScheduledConfig, SpaceInfo,
+    Stats, StoredChamber, StoredOption, StoredPoll, Streak, TieBreak, Upgrade, VotingMethod,
+    VotingStrategy, WeightCap, WeightCurve, ALLOWLIST, ALLOWLIST_TOTALS, ARCHIVE, BALLOTS,
+    CHAMBER_MEMBERS, CLAIMS, COLLECTED_CW20_FEES, COLLECTED_FEES, COMMITMENTS, CONFIG,
+    CREATION_VOLUME, CREATOR_POLLS, DEFAULT_MAX_OPTIONS, DEFAULT_MAX_OPTION_LEN,
+    DEFAULT_MAX_QUESTION_LEN, DEFAULT_MIN_OPTIONS, DEPOSIT_PRICING, DRAFTS, ELIGIBILITY, FOLLOWED,
+    GATEWAYS, GUARDIAN, LABELS, LAST_CREATED, MEMBERS, MEMBERS_TOTAL, MODERATORS, MUTED,
+    OPEN_POLLS, PAUSED, PENDING_ADMIN, PENDING_DRAWS, PERMITTED_MSGS, POLLS, POLL_COUNT,
+    POLL_SEQUENCE, POLL_TOTALS, POLL_VOTERS, PRUNING, RANDOMNESS_PROXY, READ_ONLY, REPUTATION,
+    REPUTATION_CURSORS, REPUTATION_PARAMS, SCHEDULED_CONFIG, SEQUENCE_POLLS, SERIES, SIGNED_NONCES,
+    SPACE_INFO, STATS, STORAGE_STATS, STREAKS, STREAK_RANKING, SUBJECTS, TAGS, TALLIES, TEMPLATES,
+    VOTE_COUNTS, VOTE_RANKING, WATCHERS, WATCHER_COUNTS,
+};
+use crate::tally::{viable_options, votes_on};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult,
+    coin, from_binary, from_slice, to_binary, to_vec, Addr, BankMsg, Binary, BlockInfo, Coin,
+    ContractResult, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Order,
+    QueryRequest, Response, StdError, StdResult, Storage, SystemResult, Timestamp, Uint128,
+    WasmMsg, WasmQuery,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
+use cw20::{BalanceResponse, Cw20CoinVerified, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg};
+use cw4::{Cw4QueryMsg, MemberResponse};
+use cw_storage_plus::Bound;
+use cw_utils::{must_pay, nonpayable, Expiration, PaymentError, Scheduled};
+use semver::Version;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::any::type_name;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
 
 const CONTRACT_NAME: &str = "crates.io:cw-starter";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// Maximum length in bytes of a ballot memo.
+const MAX_MEMO_LENGTH: usize = 256;
+/// Highest score a voter can give an option in `VotingMethod::Score` polls.
+pub const MAX_SCORE: u64 = 5;
+
+// Default and maximum page sizes for paginated queries.
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+const MAX_OPTION_LIMIT: u32 = 100;
+
+// Maximum number of claims checked by a single `VerifyBallots` query.
+const MAX_VERIFY_CLAIMS: usize = 500;
+
+/// Version of the `TemplateExport` format written by `ExportTemplate`.
+const TEMPLATE_FORMAT: u32 = 1;
+const MAX_TEMPLATE_NAME_LENGTH: usize = 64;
+const MAX_LABELS: usize = 8;
+const MAX_LABEL_LENGTH: usize = 32;
+const MAX_TAGS: usize = 8;
+
+// Maximum lengths in bytes of the descriptive fields of a poll.
+const MAX_DESCRIPTION_LENGTH: usize = 2048;
+const MAX_IMAGE_URL_LENGTH: usize = 512;
+const MAX_METADATA_LENGTH: usize = 4096;
+const MAX_OPTION_LABEL_LENGTH: usize = 64;
+// Maximum number of data points a poll captures when finalized.
+const MAX_CAPTURES: usize = 5;
+// Maximum number of polls created by a single `CreatePolls`.
+const MAX_BATCH_POLLS: usize = 20;
+// Maximum number of drafts saved by a single creator.
+const MAX_DRAFTS: usize = 20;
+// Voters credited with reputation by finalizing or a single `CreditReputation`.
+const REPUTATION_BATCH: usize = 50;
+// Entries removed by deleting or archiving a poll, or a single `PrunePoll`.
+const PRUNE_BATCH: usize = 50;
+// Poll sequence numbers looked up by a single `RandomPolls`.
+const RANDOM_POLLS_SCAN: u64 = 200;
+// Polls looked at for a single page of `Feed` or `FollowedPolls`.
+const FEED_SCAN: usize = 200;
+
+// Contract names whose state this code knows how to interpret.
+const MIGRATABLE_CONTRACT_NAMES: &[&str] = &[CONTRACT_NAME];
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -26,10 +112,36 @@ pub fn instantiate(
     let admin = msg.admin.unwrap_or(info.sender.to_string());
 
     let validated_admin = deps.api.addr_validate(&admin)?;
+    let treasury = match msg.treasury {
+        Some(treasury) => deps.api.addr_validate(&treasury)?,
+        None => validated_admin.clone(),
+    };
+    let cw20_poll_creation_fee = msg
+        .cw20_poll_creation_fee
+        .map(|fee| -> StdResult<_> {
+            Ok(Cw20CoinVerified {
+                address: deps.api.addr_validate(&fee.address)?,
+                amount: fee.amount,
+            })
+        })
+        .transpose()?;
 
     let config = Config {
         admin: validated_admin.clone(),
+        unique_subjects: msg.unique_subjects.unwrap_or(true),
+        admin_pubkey: msg.admin_pubkey,
+        poll_creation_fee: msg.poll_creation_fee,
+        poll_deposit: msg.poll_deposit,
+        treasury,
+        cw20_poll_creation_fee,
+        max_polls_per_creator: msg.max_polls_per_creator,
+        poll_creation_cooldown: msg.poll_creation_cooldown,
+        min_options: msg.min_options.unwrap_or(DEFAULT_MIN_OPTIONS),
+        max_options: msg.max_options.unwrap_or(DEFAULT_MAX_OPTIONS),
+        max_question_len: msg.max_question_len.unwrap_or(DEFAULT_MAX_QUESTION_LEN),
+        max_option_len: msg.max_option_len.unwrap_or(DEFAULT_MAX_OPTION_LEN),
     };
+    validate_poll_limits(&config)?;
 
     CONFIG.save(deps.storage, &config)?;
 
@@ -45,489 +157,13826 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    // Checked before anything else, as even activating a scheduled config
+    // writes to storage. The admin controls pass both, so a contract that is
+    // paused and read-only at once can still be recovered
+    let control = matches!(
+        msg,
+        ExecuteMsg::Pause {} | ExecuteMsg::Unpause {} | ExecuteMsg::SetReadOnly { .. }
+    );
+    if !control && READ_ONLY.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::ReadOnly {});
+    }
+    if !control && PAUSED.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::ContractPaused {});
+    }
+    activate_scheduled_config(deps.storage, &env.block)?;
+
+    // Only creation fees, deposits and coin votes carry funds; anything else
+    // sent along would be stranded in the contract
+    match &msg {
+        ExecuteMsg::CreatePoll { .. }
+        | ExecuteMsg::CreatePolls { .. }
+        | ExecuteMsg::CreatePollSigned { .. }
+        | ExecuteMsg::PublishPoll { .. }
+        | ExecuteMsg::FinalizePoll { .. }
+        | ExecuteMsg::Vote { .. }
+        | ExecuteMsg::VoteFor { .. }
+        | ExecuteMsg::Abstain { .. } => {}
+        _ => ensure_no_funds(&info)?,
+    }
+
     match msg {
         ExecuteMsg::CreatePoll {
             poll_id,
             question,
             options,
-        } => execute_create_poll(deps, env, info, poll_id, question, options),
-        ExecuteMsg::Vote { poll_id, vote } => execute_vote(deps, env, info, poll_id, vote),
-        //ExecuteMsg::DeletePoll { poll_id } => unimplemented!(),
-        //ExecuteMsg::RevokeVote { poll_id } => unimplemented!(),
+            series_id,
+            strategy,
+            allowlist,
+            end_time,
+            subject,
+            chambers,
+            weight_curve,
+            weight_cap,
+            basket,
+            resolver,
+            vote_token,
+            correction_window,
+            min_support,
+            group,
+            max_selections,
+            upgrade,
+            method,
+            privacy,
+            commit_deadline,
+            quorum,
+            veto,
+            finalizer,
+            execution,
+            template,
+            threshold,
+            lottery,
+            tie_break,
+            labels,
+            max_ballots,
+            description,
+            image_url,
+            metadata,
+            option_details,
+            captures,
+            tags,
+        } => execute_create_poll(
+            deps,
+            env,
+            info,
+            PollInput {
+                poll_id,
+                question,
+                options,
+                series_id,
+                strategy,
+                allowlist,
+                end_time,
+                subject,
+                chambers,
+                weight_curve,
+                weight_cap,
+                basket,
+                resolver,
+                vote_token,
+                correction_window,
+                min_support,
+                group,
+                max_selections,
+                upgrade,
+                method,
+                privacy,
+                commit_deadline,
+                quorum,
+                veto,
+                finalizer,
+                execution,
+                template,
+                threshold,
+                lottery,
+                tie_break,
+                labels,
+                max_ballots,
+                description,
+                image_url,
+                metadata,
+                option_details,
+                captures,
+                tags,
+            },
+        ),
+        ExecuteMsg::CreatePollSigned { payload, signature } => {
+            execute_create_poll_signed(deps, env, info, payload, signature)
+        }
+        ExecuteMsg::SaveDraft { draft_id, poll } => {
+            execute_save_draft(deps, env, info, draft_id, poll)
+        }
+        ExecuteMsg::DeleteDraft { draft_id } => execute_delete_draft(deps, env, info, draft_id),
+        ExecuteMsg::CreatePolls { polls } => execute_create_polls(deps, env, info, polls),
+        ExecuteMsg::PublishPoll { draft_id } => execute_publish_poll(deps, env, info, draft_id),
+        ExecuteMsg::Vote {
+            poll_id,
+            vote,
+            amounts,
+            memo,
+        } => execute_vote(
+            deps, env, info, poll_id, vote, amounts, memo, None, None, false,
+        ),
+        ExecuteMsg::Abstain { poll_id, memo } => execute_vote(
+            deps,
+            env,
+            info,
+            poll_id,
+            vec![],
+            vec![],
+            memo,
+            None,
+            None,
+            true,
+        ),
+        ExecuteMsg::VoteFor {
+            voter,
+            poll_id,
+            vote,
+            amounts,
+            memo,
+            proof,
+        } => execute_vote_for(deps, env, info, voter, poll_id, vote, amounts, memo, proof),
+        ExecuteMsg::AddGateway { address } => {
+            execute_update_gateway(deps, env, info, address, true)
+        }
+        ExecuteMsg::RemoveGateway { address } => {
+            execute_update_gateway(deps, env, info, address, false)
+        }
+        ExecuteMsg::SetSpaceInfo { info: space_info } => {
+            execute_set_space_info(deps, env, info, space_info)
+        }
+        ExecuteMsg::SetPermittedMsgs { permitted } => {
+            execute_set_permitted_msgs(deps, env, info, permitted)
+        }
+        ExecuteMsg::SetDepositPricing { pricing } => {
+            execute_set_deposit_pricing(deps, env, info, pricing)
+        }
+        ExecuteMsg::SetReputationParams { params } => {
+            execute_set_reputation_params(deps, env, info, params)
+        }
+        ExecuteMsg::SetTemplate { name, rules } => {
+            execute_set_template(deps, env, info, name, rules)
+        }
+        ExecuteMsg::ImportTemplate { export } => execute_import_template(deps, env, info, export),
+        ExecuteMsg::UpdateAllowlist {
+            poll_id,
+            add,
+            remove,
+        } => execute_update_allowlist(deps, env, info, poll_id, add, remove),
+        ExecuteMsg::PrecheckEligibility { poll_id } => {
+            execute_precheck_eligibility(deps, env, info, poll_id)
+        }
+        ExecuteMsg::WatchPoll { poll_id } => execute_watch_poll(deps, env, info, poll_id),
+        ExecuteMsg::UnwatchPoll { poll_id } => execute_unwatch_poll(deps, env, info, poll_id),
+        ExecuteMsg::MuteCreator { creator } => execute_mute_creator(deps, env, info, creator, true),
+        ExecuteMsg::UnmuteCreator { creator } => {
+            execute_mute_creator(deps, env, info, creator, false)
+        }
+        ExecuteMsg::FollowCreator { creator } => {
+            execute_follow_creator(deps, env, info, creator, true)
+        }
+        ExecuteMsg::UnfollowCreator { creator } => {
+            execute_follow_creator(deps, env, info, creator, false)
+        }
+        ExecuteMsg::UpdateConfig {
+            unique_subjects,
+            admin_pubkey,
+            poll_creation_fee,
+            poll_deposit,
+            treasury,
+            cw20_poll_creation_fee,
+            max_polls_per_creator,
+            poll_creation_cooldown,
+            min_options,
+            max_options,
+            max_question_len,
+            max_option_len,
+        } => execute_update_config(
+            deps,
+            env,
+            info,
+            ConfigChanges {
+                unique_subjects,
+                admin_pubkey,
+                poll_creation_fee,
+                poll_deposit,
+                treasury,
+                cw20_poll_creation_fee,
+                max_polls_per_creator,
+                poll_creation_cooldown,
+                min_options,
+                max_options,
+                max_question_len,
+                max_option_len,
+            },
+        ),
+        ExecuteMsg::ScheduleConfig {
+            unique_subjects,
+            admin_pubkey,
+            poll_creation_fee,
+            poll_deposit,
+            treasury,
+            cw20_poll_creation_fee,
+            max_polls_per_creator,
+            poll_creation_cooldown,
+            min_options,
+            max_options,
+            max_question_len,
+            max_option_len,
+            activate_at,
+        } => execute_schedule_config(
+            deps,
+            env,
+            info,
+            ConfigChanges {
+                unique_subjects,
+                admin_pubkey,
+                poll_creation_fee,
+                poll_deposit,
+                treasury,
+                cw20_poll_creation_fee,
+                max_polls_per_creator,
+                poll_creation_cooldown,
+                min_options,
+                max_options,
+                max_question_len,
+                max_option_len,
+            },
+            activate_at,
+        ),
+        ExecuteMsg::ProposeAdmin { new_admin } => execute_propose_admin(deps, env, info, new_admin),
+        ExecuteMsg::AcceptAdmin {} => execute_accept_admin(deps, env, info),
+        ExecuteMsg::SetGuardian { guardian } => execute_set_guardian(deps, env, info, guardian),
+        ExecuteMsg::SetReadOnly { read_only } => execute_set_read_only(deps, env, info, read_only),
+        ExecuteMsg::Pause {} => execute_set_paused(deps, env, info, true),
+        ExecuteMsg::Unpause {} => execute_set_paused(deps, env, info, false),
+        ExecuteMsg::UpdateMembers { add, remove } => {
+            execute_update_members(deps, env, info, add, remove)
+        }
+        ExecuteMsg::AddModerator { address } => {
+            execute_update_moderator(deps, env, info, address, true)
+        }
+        ExecuteMsg::RemoveModerator { address } => {
+            execute_update_moderator(deps, env, info, address, false)
+        }
+        ExecuteMsg::ClosePoll { poll_id } => {
+            execute_end_poll(deps, env, info, poll_id, PollStatus::Closed)
+        }
+        ExecuteMsg::CancelPoll { poll_id } => {
+            execute_end_poll(deps, env, info, poll_id, PollStatus::Cancelled)
+        }
+        ExecuteMsg::FreezePoll { poll_id } => execute_freeze_poll(deps, env, info, poll_id, true),
+        ExecuteMsg::UnfreezePoll { poll_id } => {
+            execute_freeze_poll(deps, env, info, poll_id, false)
+        }
+        ExecuteMsg::AnnulPoll { poll_id, reason } => {
+            execute_annul_poll(deps, env, info, poll_id, reason)
+        }
+        ExecuteMsg::ArchivePoll { poll_id } => execute_archive_poll(deps, env, info, poll_id),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, env, info, msg),
+        ExecuteMsg::WithdrawFees { amount } => execute_withdraw_fees(deps, env, info, amount),
+        ExecuteMsg::Claim {} => execute_claim(deps, env, info),
+        ExecuteMsg::DeletePoll { poll_id } => execute_delete_poll(deps, env, info, poll_id),
+        ExecuteMsg::RetireOption {
+            poll_id,
+            option,
+            reason,
+        } => execute_retire_option(deps, env, info, poll_id, option, reason),
+        ExecuteMsg::RevokeVote { poll_id } => execute_revoke_vote(deps, env, info, poll_id),
+        ExecuteMsg::ResolveOutcome { poll_id, outcome } => {
+            execute_resolve_outcome(deps, env, info, poll_id, outcome)
+        }
+        ExecuteMsg::CommitVote {
+            poll_id,
+            commitment,
+        } => execute_commit_vote(deps, env, info, poll_id, commitment),
+        ExecuteMsg::RevealVote {
+            poll_id,
+            vote,
+            amounts,
+            salt,
+        } => execute_vote(
+            deps,
+            env,
+            info,
+            poll_id,
+            vote,
+            amounts,
+            None,
+            None,
+            Some(salt),
+            false,
+        ),
+        ExecuteMsg::FinalizePoll { poll_id } => execute_finalize_poll(deps, env, info, poll_id),
+        ExecuteMsg::SettleDeposit { poll_id } => execute_settle_deposit(deps, env, info, poll_id),
+        ExecuteMsg::ReleaseEscrow { poll_id } => execute_release_escrow(deps, env, info, poll_id),
+        ExecuteMsg::CreditReputation { poll_id } => {
+            execute_credit_reputation(deps, env, info, poll_id)
+        }
+        ExecuteMsg::PrunePoll { poll_id } => execute_prune_poll(deps, env, info, poll_id),
+        ExecuteMsg::ExecuteUpgrade { poll_id } => execute_upgrade(deps, env, info, poll_id),
+        ExecuteMsg::Execute { poll_id } => execute_execute(deps, env, info, poll_id),
+        ExecuteMsg::SetRandomnessProxy { proxy } => {
+            execute_set_randomness_proxy(deps, env, info, proxy)
+        }
+        ExecuteMsg::NoisReceive { callback } => execute_nois_receive(deps, env, info, callback),
     }
 }
 
 fn execute_create_poll(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    poll_id: String,
-    question: String,
-    options: Vec<String>,
+    input: PollInput,
 ) -> Result<Response, ContractError> {
-    if options.len() > 10 {
-        return Err(ContractError::TooManyOptions {});
-    }
-
-    let mut opts: Vec<(String, u64)> = vec![];
-    for option in options {
-        opts.push((option, 0));
-    }
-
-    let poll = Poll {
-        creator: info.sender,
-        question: question.clone(),
-        options: opts,
+    let config = CONFIG.load(deps.storage)?;
+    let deposit = poll_deposit(deps.storage, &env.block, &config)?;
+    // The deposit is held back first, the rest of the funds pay the fee
+    let info = match &deposit {
+        Some(deposit) => hold_deposit(info, deposit)?,
+        None => info,
     };
+    match config.poll_creation_fee {
+        Some(fee) => collect_fee(deps.storage, &info, &fee)?,
+        None => ensure_no_funds(&info)?,
+    }
 
-    POLLS.save(deps.storage, &poll_id, &poll)?;
+    let question = input.question.clone();
+    let poll_id = create_poll(deps, &env, info.sender, input, deposit)?;
 
     Ok(Response::new()
+        .set_data(to_binary(&CreatePollResponse {
+            poll_id: poll_id.clone(),
+        })?)
         .add_attribute("action", "execute_create_poll")
         .add_attribute("poll_id", poll_id)
         .add_attribute("question", question))
 }
 
-fn execute_vote(
-    deps: DepsMut,
-    _env: Env,
+/// Creates each poll as `CreatePoll` would, paying every deposit and fee
+/// out of the funds sent. Fails as a whole, naming the first poll that
+/// could not be created, and with any funds left over.
+fn execute_create_polls(
+    mut deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    poll_id: String,
-    vote: String,
+    polls: Vec<PollInput>,
 ) -> Result<Response, ContractError> {
-    let poll = POLLS.may_load(deps.storage, &poll_id)?;
-
-    match poll {
-        Some(mut poll) => {
-            BALLOTS.update(
-                deps.storage,
-                (info.sender, &poll_id),
-                |ballot| -> StdResult<Ballot> {
-                    match ballot {
-                        Some(ballot) => {
-                            let position_of_old_vote = poll
-                                .options
-                                .iter()
-                                .position(|option| option.0 == ballot.option)
-                                .unwrap();
-
-                            poll.options[position_of_old_vote].1 -= 1;
-
-                            Ok(Ballot {
-                                option: vote.clone(),
-                            })
-                        }
-                        None => Ok(Ballot {
-                            option: vote.clone(),
-                        }),
-                    }
-                },
-            )?;
-
-            let position = poll.options.iter().position(|option| option.0 == vote);
+    if polls.len() > MAX_BATCH_POLLS {
+        return Err(ContractError::BatchTooLarge {
+            max: MAX_BATCH_POLLS,
+        });
+    }
+    let config = CONFIG.load(deps.storage)?;
 
-            if position.is_none() {
-                return Err(ContractError::OptionNotFound {});
+    let mut remaining = info.funds;
+    let mut poll_ids = vec![];
+    for (index, poll) in polls.into_iter().enumerate() {
+        // Each poll is handed its own deposit and fee, the deposit rising as
+        // the batch adds to the creation volume
+        let deposit = poll_deposit(deps.storage, &env.block, &config)?;
+        let mut funds: Vec<Coin> = vec![];
+        for price in deposit.iter().chain(config.poll_creation_fee.iter()) {
+            let available = remaining.iter_mut().find(|coin| coin.denom == price.denom);
+            let taken = match available {
+                Some(coin) => {
+                    let taken = coin.amount.min(price.amount);
+                    coin.amount -= taken;
+                    taken
+                }
+                None => continue,
+            };
+            match funds.iter_mut().find(|coin| coin.denom == price.denom) {
+                Some(coin) => coin.amount += taken,
+                None => funds.push(coin(taken.u128(), &price.denom)),
             }
+        }
+        remaining.retain(|coin| !coin.amount.is_zero());
 
-            let position = position.unwrap();
+        let info = MessageInfo {
+            sender: info.sender.clone(),
+            funds,
+        };
+        let res = execute_create_poll(deps.branch(), env.clone(), info, poll).map_err(|error| {
+            ContractError::BatchItemFailed {
+                index,
+                error: Box::new(error),
+            }
+        })?;
+        if let Some(data) = res.data {
+            let created: CreatePollResponse = from_binary(&data)?;
+            poll_ids.push(created.poll_id);
+        }
+    }
+    if !remaining.is_empty() {
+        return Err(ContractError::UnexpectedFunds {});
+    }
 
-            poll.options[position].1 += 1;
+    Ok(Response::new()
+        .set_data(to_binary(&CreatePollsResponse {
+            poll_ids: poll_ids.clone(),
+        })?)
+        .add_attribute("action", "execute_create_polls")
+        .add_attribute("poll_ids", poll_ids.join(",")))
+}
 
-            POLLS.save(deps.storage, &poll_id, &poll)?;
-            Ok(Response::new()
-                .add_attribute("action", "execute_vote")
-                .add_attribute("poll_id", poll_id)
-                .add_attribute("vote", vote))
+fn execute_save_draft(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    draft_id: String,
+    poll: PollInput,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    validate_poll_sizes(&config, &poll)?;
+    if !DRAFTS.has(deps.storage, (&info.sender, &draft_id)) {
+        let drafts = DRAFTS
+            .prefix(&info.sender)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .take(MAX_DRAFTS)
+            .count();
+        if drafts == MAX_DRAFTS {
+            return Err(ContractError::TooManyDrafts { max: MAX_DRAFTS });
         }
-        None => Err(ContractError::PollNotFound {}),
     }
+    DRAFTS.save(deps.storage, (&info.sender, &draft_id), &poll)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_save_draft")
+        .add_attribute("draft_id", draft_id)
+        .add_attribute("creator", info.sender))
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::AllPolls {} => query_all_polls(deps, env),
-        QueryMsg::Poll { poll_id } => query_poll(deps, env, poll_id),
-        QueryMsg::Vote { address, poll_id } => query_vote(deps, env, address, poll_id),
-        QueryMsg::GetConfig {} => query_config(deps, env),
-        //QueryMsg::AllVotesForAUser { user_address } => unimplemented!(),
+fn execute_delete_draft(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    draft_id: String,
+) -> Result<Response, ContractError> {
+    if !DRAFTS.has(deps.storage, (&info.sender, &draft_id)) {
+        return Err(ContractError::DraftNotFound {});
     }
+    DRAFTS.remove(deps.storage, (&info.sender, &draft_id));
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_delete_draft")
+        .add_attribute("draft_id", draft_id)
+        .add_attribute("creator", info.sender))
 }
 
-fn query_all_polls(deps: Deps, _env: Env) -> StdResult<Binary> {
-    let polls = POLLS
-        .range(deps.storage, None, None, Order::Ascending)
-        .map(|p| Ok(p?.1))
-        .collect::<StdResult<Vec<_>>>()?;
+/// Creates a poll from a draft. The template and config in force now apply,
+/// not those from when the draft was saved.
+fn execute_publish_poll(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    draft_id: String,
+) -> Result<Response, ContractError> {
+    let poll = DRAFTS
+        .may_load(deps.storage, (&info.sender, &draft_id))?
+        .ok_or(ContractError::DraftNotFound {})?;
 
-    to_binary(&AllPollsResponse { polls })
+    let creator = info.sender.clone();
+    let res = execute_create_poll(deps.branch(), env, info, poll)?;
+    DRAFTS.remove(deps.storage, (&creator, &draft_id));
+    Ok(res.add_attribute("draft_id", draft_id))
 }
 
-fn query_poll(deps: Deps, _env: Env, poll_id: String) -> StdResult<Binary> {
-    let poll = POLLS.may_load(deps.storage, &poll_id)?;
-    to_binary(&PollResponse { poll })
+/// Rejects funds sent to handlers that have no use for them.
+fn ensure_no_funds(info: &MessageInfo) -> Result<(), ContractError> {
+    nonpayable(info).map_err(|_| ContractError::UnexpectedFunds {})
 }
 
-fn query_vote(deps: Deps, _env: Env, address: String, poll_id: String) -> StdResult<Binary> {
-    let validated_address = deps.api.addr_validate(&address)?;
-    let vote = BALLOTS.may_load(deps.storage, (validated_address, &poll_id))?;
-
-    to_binary(&VoteResponse { vote })
+/// Deposit required from the next poll created: the configured one, raised
+/// by the pricing's step for each recent creation past its target.
+fn poll_deposit(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    config: &Config,
+) -> StdResult<Option<Coin>> {
+    let (base, pricing) = match (&config.poll_deposit, DEPOSIT_PRICING.may_load(storage)?) {
+        (Some(base), Some(pricing)) => (base, pricing),
+        (base, None) => return Ok(base.clone()),
+        (None, Some(_)) => return Ok(None),
+    };
+    let excess = recent_creations(storage, &pricing, block)?.saturating_sub(pricing.target);
+    let surcharge = base.amount * pricing.step * Uint128::from(excess);
+    Ok(Some(Coin {
+        denom: base.denom.clone(),
+        amount: base.amount + surcharge,
+    }))
 }
 
-fn query_config(deps: Deps, _env: Env) -> StdResult<Binary> {
-    let config = CONFIG.load(deps.storage)?;
-
-    to_binary(&ConfigResponse { config })
+/// Polls created over the last pricing window, counting the previous window
+/// in proportion to how much of it still overlaps.
+fn recent_creations(
+    storage: &dyn Storage,
+    pricing: &DepositPricing,
+    block: &BlockInfo,
+) -> StdResult<u64> {
+    let volume = CREATION_VOLUME.may_load(storage)?.unwrap_or_default();
+    let window = block.time.seconds() / pricing.window;
+    let elapsed = block.time.seconds() % pricing.window;
+    let (current, previous) = match window.saturating_sub(volume.window) {
+        0 => (volume.current, volume.previous),
+        1 => (0, volume.current),
+        _ => (0, 0),
+    };
+    let overlap = Uint128::from(previous).multiply_ratio(pricing.window - elapsed, pricing.window);
+    Ok(current + overlap.u128() as u64)
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::contract::{execute, instantiate, query};
-    use crate::msg::{
-        AllPollsResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, PollResponse, QueryMsg,
-        VoteResponse,
+/// Counts a poll creation towards deposit pricing, if the deposit is priced.
+fn record_creation(storage: &mut dyn Storage, block: &BlockInfo) -> StdResult<()> {
+    let pricing = match DEPOSIT_PRICING.may_load(storage)? {
+        Some(pricing) => pricing,
+        None => return Ok(()),
     };
-    use crate::ContractError;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{attr, from_binary};
-
-    pub const ADDR1: &str = "addr1";
-    pub const ADDR2: &str = "addr2";
-
-    #[test]
-    fn test_instantiate() {
-        let mut deps = mock_dependencies();
-        let env = mock_env();
-        let info = mock_info(ADDR1, &vec![]);
-
-        let msg = InstantiateMsg { admin: None };
-        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
-
-        assert_eq!(
-            res.attributes,
-            vec![attr("action", "instantiate"), attr("admin", ADDR1)]
-        )
+    let mut volume = CREATION_VOLUME.may_load(storage)?.unwrap_or_default();
+    let window = block.time.seconds() / pricing.window;
+    match window.saturating_sub(volume.window) {
+        0 => {}
+        1 => volume.previous = volume.current,
+        _ => volume.previous = 0,
+    }
+    if window > volume.window {
+        volume.window = window;
+        volume.current = 0;
     }
+    volume.current += 1;
+    CREATION_VOLUME.save(storage, &volume)
+}
 
-    #[test]
-    fn test_instantiate_with_admin() {
-        let mut deps = mock_dependencies();
-        let env = mock_env();
-        let info = mock_info(ADDR1, &vec![]);
+/// Removes `deposit` from the funds sent with `info`.
+fn hold_deposit(mut info: MessageInfo, deposit: &Coin) -> Result<MessageInfo, ContractError> {
+    let paid = info
+        .funds
+        .iter_mut()
+        .find(|coin| coin.denom == deposit.denom && coin.amount >= deposit.amount)
+        .ok_or_else(|| ContractError::InsufficientDeposit {
+            deposit: deposit.clone(),
+        })?;
+    paid.amount -= deposit.amount;
+    info.funds.retain(|coin| !coin.amount.is_zero());
+    Ok(info)
+}
 
-        let msg = InstantiateMsg {
-            admin: Some(ADDR2.to_string()),
-        };
-        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+/// Takes `fee` out of the funds sent with `info`, adding it to the collected
+/// fees. Anything paid on top of the fee is credited back to the sender.
+fn collect_fee(
+    storage: &mut dyn Storage,
+    info: &MessageInfo,
+    fee: &Coin,
+) -> Result<(), ContractError> {
+    let paid = must_pay(info, &fee.denom)?;
+    if paid < fee.amount {
+        return Err(ContractError::InsufficientFee { fee: fee.clone() });
+    }
 
-        assert_eq!(
-            res.attributes,
-            vec![attr("action", "instantiate"), attr("admin", ADDR2)]
-        );
+    let mut collected = COLLECTED_FEES.may_load(storage)?.unwrap_or_default();
+    match collected.iter_mut().find(|coin| coin.denom == fee.denom) {
+        Some(coin) => coin.amount += fee.amount,
+        None => collected.push(fee.clone()),
     }
+    COLLECTED_FEES.save(storage, &collected)?;
 
-    #[test]
-    fn test_execute_create_poll_valid() {
-        let mut deps = mock_dependencies();
-        let env = mock_env();
-        let info = mock_info(ADDR1, &vec![]);
+    credit_claim(
+        storage,
+        &info.sender,
+        &[coin((paid - fee.amount).u128(), &fee.denom)],
+    )?;
+    Ok(())
+}
 
-        let msg = InstantiateMsg { admin: None };
-        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+/// Creates a poll paid for in the configured cw20 fee token. `info.sender` is
+/// the token contract, the creator being the address that sent the tokens.
+/// Token callbacks carry no native funds, so no poll is created this way
+/// while a deposit is required.
+fn execute_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if let Some(deposit) = poll_deposit(deps.storage, &env.block, &config)? {
+        return Err(ContractError::DepositNotPayable { deposit });
+    }
 
-        let msg = ExecuteMsg::CreatePoll {
-            poll_id: "some_id".to_string(),
-            question: "What's your favorite Cosmos coin?".to_string(),
-            options: vec![
-                "Cosmos Hub".to_string(),
-                "Juno".to_string(),
-                "Osmosis".to_string(),
-                "Terra".to_string(),
-            ],
-        };
+    let fee = match config.cw20_poll_creation_fee {
+        Some(fee) if fee.address == info.sender => fee,
+        _ => return Err(ContractError::UnexpectedToken {}),
+    };
+    if wrapper.amount != fee.amount {
+        return Err(ContractError::IncorrectCw20Fee {
+            address: fee.address.to_string(),
+            amount: fee.amount,
+        });
+    }
 
-        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    let creator = deps.api.addr_validate(&wrapper.sender)?;
+    let ReceiveMsg::CreatePoll(input) = from_binary(&wrapper.msg)?;
 
-        assert_eq!(res.attributes[0].key, "action");
-        assert_eq!(res.attributes[0].value, "execute_create_poll");
-    }
+    COLLECTED_CW20_FEES.update(deps.storage, &fee.address, |collected| -> StdResult<_> {
+        Ok(collected.unwrap_or_default() + fee.amount)
+    })?;
 
-    #[test]
-    fn test_exeucte_create_poll_invalid() {
-        let mut deps = mock_dependencies();
-        let env = mock_env();
-        let info = mock_info(ADDR1, &vec![]);
+    let question = input.question.clone();
+    let poll_id = create_poll(deps, &env, creator.clone(), input, None)?;
 
-        let msg = InstantiateMsg { admin: None };
-        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    Ok(Response::new()
+        .set_data(to_binary(&CreatePollResponse {
+            poll_id: poll_id.clone(),
+        })?)
+        .add_attribute("action", "execute_create_poll")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("question", question)
+        .add_attribute("creator", creator))
+}
 
-        let msg = ExecuteMsg::CreatePoll {
-            poll_id: "some_id".to_string(),
-            question: "What's your favorite Cosmos coin?".to_string(),
-            options: vec![
-                "1".to_string(),
-                "2".to_string(),
-                "3".to_string(),
-                "4".to_string(),
-                "5".to_string(),
-                "6".to_string(),
-                "7".to_string(),
-                "Cosmos Hub".to_string(),
-                "Juno".to_string(),
-                "Osmosis".to_string(),
-                "Terra".to_string(),
-            ],
-        };
+/// Creates a poll authorized by the admin's signature. Any deposit required
+/// is held from the funds sent by whoever submits it, and returned to the
+/// creator like any other.
+fn execute_create_poll_signed(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    payload: Binary,
+    signature: Binary,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let pubkey = config
+        .admin_pubkey
+        .clone()
+        .ok_or(ContractError::SignedCreationDisabled {})?;
 
-        let res = execute(deps.as_mut(), env, info, msg);
+    let deposit = poll_deposit(deps.storage, &env.block, &config)?;
+    let info = match &deposit {
+        Some(deposit) => hold_deposit(info, deposit)?,
+        None => info,
+    };
+    ensure_no_funds(&info)?;
 
-        match res {
-            Err(ContractError::TooManyOptions {}) => {}
-            _ => panic!("Must return too many options error"),
-        }
+    let hash = Sha256::digest(payload.as_slice());
+    let verified = deps
+        .api
+        .secp256k1_verify(&hash, &signature, &pubkey)
+        .map_err(|_| ContractError::InvalidSignature {})?;
+    if !verified {
+        return Err(ContractError::InvalidSignature {});
     }
 
-    #[test]
-    fn test_execute_vote_valid() {
-        let mut deps = mock_dependencies();
-        let env = mock_env();
-        let info = mock_info(ADDR1, &vec![]);
+    let payload: SignedPollPayload = from_slice(&payload)?;
+    if SIGNED_NONCES.has(deps.storage, payload.nonce) {
+        return Err(ContractError::NonceUsed {
+            nonce: payload.nonce,
+        });
+    }
+    SIGNED_NONCES.save(deps.storage, payload.nonce, &Empty {})?;
 
-        let msg = InstantiateMsg { admin: None };
-        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    let creator = deps.api.addr_validate(&payload.creator)?;
+    let question = payload.poll.question.clone();
+    let poll_id = create_poll(deps, &env, creator.clone(), payload.poll, deposit)?;
 
-        let msg = ExecuteMsg::CreatePoll {
-            poll_id: "random_id".to_string(),
-            question: "What's your favorite Cosmos coin?".to_string(),
-            options: vec![
-                "Cosmos Hub".to_string(),
-                "Juno".to_string(),
-                "Osmosis".to_string(),
-            ],
-        };
+    Ok(Response::new()
+        .set_data(to_binary(&CreatePollResponse {
+            poll_id: poll_id.clone(),
+        })?)
+        .add_attribute("action", "execute_create_poll_signed")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("question", question)
+        .add_attribute("creator", creator)
+        .add_attribute("sponsor", info.sender))
+}
 
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+/// Validates and stores a new poll created by `creator`, holding `deposit`
+/// until it ends, and returns its id.
+fn create_poll(
+    deps: DepsMut,
+    env: &Env,
+    creator: Addr,
+    mut input: PollInput,
+    deposit: Option<Coin>,
+) -> Result<String, ContractError> {
+    if let Some(name) = &input.template {
+        let rules = TEMPLATES
+            .may_load(deps.storage, name)?
+            .ok_or(ContractError::TemplateNotFound {})?;
+        apply_template(&mut input, rules);
+    }
+    let config = CONFIG.load(deps.storage)?;
+    validate_poll_sizes(&config, &input)?;
+    let PollInput {
+        poll_id,
+        question,
+        options,
+        series_id,
+        strategy,
+        allowlist,
+        end_time,
+        subject,
+        chambers,
+        weight_curve,
+        weight_cap,
+        basket,
+        resolver,
+        vote_token,
+        correction_window,
+        min_support,
+        group,
+        max_selections,
+        upgrade,
+        method,
+        privacy,
+        commit_deadline,
+        quorum,
+        veto,
+        finalizer,
+        execution,
+        template,
+        threshold,
+        lottery,
+        tie_break,
+        labels,
+        max_ballots,
+        description,
+        image_url,
+        metadata,
+        option_details,
+        captures,
+        tags,
+    } = input;
 
-        let msg = ExecuteMsg::Vote {
-            poll_id: "random_id".to_string(),
-            vote: "Juno".to_string(),
-        };
+    if options.len() < config.min_options as usize {
+        return Err(ContractError::TooFewOptions {
+            min: config.min_options,
+        });
+    }
 
-        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    let method = method.unwrap_or(VotingMethod::Choice {});
+    let tie_break = tie_break.unwrap_or(TieBreak::None);
+    if (lottery || tie_break == TieBreak::Random)
+        && RANDOMNESS_PROXY.may_load(deps.storage)?.is_none()
+    {
+        return Err(ContractError::RandomnessUnavailable {});
+    }
+    let max_selections = match (&method, max_selections) {
+        // Approval, quadratic and score ballots may cover every option
+        (
+            VotingMethod::Approval {} | VotingMethod::Quadratic { .. } | VotingMethod::Score {},
+            None,
+        ) => options.len() as u32,
+        (
+            VotingMethod::Approval {} | VotingMethod::Quadratic { .. } | VotingMethod::Score {},
+            Some(_),
+        ) => return Err(ContractError::InvalidMaxSelections {}),
+        (VotingMethod::Choice {}, max_selections) => max_selections.unwrap_or(1),
+    };
+    if max_selections == 0 || max_selections as usize > options.len().max(1) {
+        return Err(ContractError::InvalidMaxSelections {});
+    }
+    if method == (VotingMethod::Quadratic { credits: 0 }) {
+        return Err(ContractError::InvalidCredits {});
+    }
+    if max_ballots == Some(0) {
+        return Err(ContractError::InvalidMaxBallots {});
+    }
+    let captures = validate_captures(deps.as_ref(), captures)?;
+    let tags = validate_tags(tags)?;
 
-        assert_eq!(res.attributes[0].value, "execute_vote");
-        assert_eq!(res.attributes[1].value, "random_id");
-        assert_eq!(res.attributes[2].value, "Juno");
+    let end_time = end_time.unwrap_or_default();
+    if end_time.is_expired(&env.block) {
+        return Err(ContractError::InvalidEndTime {});
+    }
 
-        let msg = ExecuteMsg::Vote {
-            poll_id: "random_id".to_string(),
-            vote: "Osmosis".to_string(),
-        };
+    ensure_cooled_down(deps.storage, &env.block, &creator)?;
+    count_open_poll(deps.storage, &env.block, &creator)?;
+    record_creation(deps.storage, &env.block)?;
 
-        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    let poll_id = match poll_id {
+        // Archived polls keep their id so they can't be shadowed either, nor
+        // can polls whose ballots are still being pruned
+        Some(poll_id) => {
+            if POLLS.has(deps.storage, &poll_id)
+                || ARCHIVE.has(deps.storage, &poll_id)
+                || PRUNING.has(deps.storage, &poll_id)
+            {
+                return Err(ContractError::PollAlreadyExists { poll_id });
+            }
+            poll_id
+        }
+        None => next_poll_id(deps.storage)?,
+    };
 
-        assert_eq!(res.attributes[0].value, "execute_vote");
-        assert_eq!(res.attributes[1].value, "random_id");
-        assert_eq!(res.attributes[2].value, "Osmosis");
+    if let Some(subject) = &subject {
+        let config = CONFIG.load(deps.storage)?;
+        if config.unique_subjects {
+            ensure_subject_free(deps.as_ref(), env, subject)?;
+        }
+        SUBJECTS.save(deps.storage, subject, &poll_id)?;
     }
 
-    #[test]
-    fn test_execute_vote_invalid() {
-        let mut deps = mock_dependencies();
-        let env = mock_env();
-        let info = mock_info(ADDR1, &vec![]);
+    let strategy = strategy.unwrap_or(VotingStrategy::OneAddressOneVote {});
+    if !allowlist.is_empty() && strategy != (VotingStrategy::Allowlist {}) {
+        return Err(ContractError::AllowlistNotEnabled {});
+    }
 
-        let msg = InstantiateMsg { admin: None };
-        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    if !chambers.is_empty() && strategy != (VotingStrategy::Chambers {}) {
+        return Err(ContractError::ChambersNotEnabled {});
+    }
 
-        let msg = ExecuteMsg::Vote {
-            poll_id: "random_id".to_string(),
-            vote: "Juno".to_string(),
-        };
+    if !basket.is_empty() && strategy != (VotingStrategy::Coins {}) {
+        return Err(ContractError::BasketNotEnabled {});
+    }
+    for (i, entry) in basket.iter().enumerate() {
+        if entry.multiplier == 0 || basket[..i].iter().any(|e| e.denom == entry.denom) {
+            return Err(ContractError::InvalidBasket {});
+        }
+    }
+    if strategy == (VotingStrategy::Coins {}) && basket.is_empty() {
+        return Err(ContractError::InvalidBasket {});
+    }
 
-        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    let vote_token = match vote_token {
+        Some(_) if strategy != (VotingStrategy::Cw20Balance {}) => {
+            return Err(ContractError::VoteTokenNotEnabled {})
+        }
+        Some(token) => Some(deps.api.addr_validate(&token)?),
+        None if strategy == (VotingStrategy::Cw20Balance {}) => {
+            return Err(ContractError::MissingVoteToken {})
+        }
+        None => None,
+    };
 
-        match res {
-            Err(ContractError::PollNotFound {}) => {}
-            _ => panic!("Must return poll not found error"),
+    let group = match group {
+        Some(_) if strategy != (VotingStrategy::Cw4Group {}) => {
+            return Err(ContractError::GroupNotEnabled {})
+        }
+        Some(group) => Some(deps.api.addr_validate(&group)?),
+        None if strategy == (VotingStrategy::Cw4Group {}) => {
+            return Err(ContractError::MissingGroup {})
         }
+        None => None,
+    };
 
-        let msg = ExecuteMsg::CreatePoll {
+    let upgrade = match upgrade {
+        Some(input) => {
+            if creator != CONFIG.load(deps.storage)?.admin {
+                let msg = WasmMsg::Migrate {
+                    contract_addr: env.contract.address.to_string(),
+                    new_code_id: input.code_id,
+                    msg: input.msg.clone(),
+                };
+                ensure_permitted(deps.as_ref(), env, &msg.into())?;
+            }
+            if options.len() != 2 || matches!(end_time, Expiration::Never {}) {
+                return Err(ContractError::InvalidUpgrade {});
+            }
+            let executable_at =
+                (end_time + input.timelock).map_err(|_| ContractError::InvalidUpgrade {})?;
+            Some(Upgrade {
+                code_id: input.code_id,
+                msg: input.msg,
+                executable_at,
+                executed: false,
+            })
+        }
+        None => None,
+    };
+
+    let execution = match execution {
+        Some(input) => {
+            if input.msgs.is_empty() || !options.contains(&input.pass_option) {
+                return Err(ContractError::InvalidExecution {});
+            }
+            if creator != CONFIG.load(deps.storage)?.admin {
+                for msg in &input.msgs {
+                    ensure_permitted(deps.as_ref(), env, msg)?;
+                }
+            }
+            Some(Execution {
+                pass_option: input.pass_option,
+                msgs: input.msgs,
+                executed: false,
+            })
+        }
+        None => None,
+    };
+
+    let finalizer = match finalizer {
+        Some(input) => {
+            if matches!(end_time, Expiration::Never {}) {
+                return Err(ContractError::InvalidFinalizer {});
+            }
+            let active_at =
+                (end_time + input.timeout).map_err(|_| ContractError::InvalidFinalizer {})?;
+            Some(Finalizer {
+                address: deps.api.addr_validate(&input.address)?,
+                active_at,
+            })
+        }
+        None => None,
+    };
+
+    if let Some(commit_deadline) = &commit_deadline {
+        if commit_deadline.is_expired(&env.block) || end_time <= *commit_deadline {
+            return Err(ContractError::InvalidEndTime {});
+        }
+    }
+
+    let resolution = match resolver {
+        Some(input) => {
+            if input.deadline.is_expired(&env.block) {
+                return Err(ContractError::InvalidEndTime {});
+            }
+            let arbiter = match input.arbiter {
+                Some(arbiter) => deps.api.addr_validate(&arbiter)?,
+                None => CONFIG.load(deps.storage)?.admin,
+            };
+            Some(Resolution {
+                resolver: deps.api.addr_validate(&input.resolver)?,
+                arbiter,
+                deadline: input.deadline,
+                outcome: None,
+                resolved_by: None,
+            })
+        }
+        None => None,
+    };
+
+    match &weight_cap {
+        Some(WeightCap::Absolute { max }) if *max == 0 => {
+            return Err(ContractError::InvalidWeightCap {})
+        }
+        Some(WeightCap::Percent { max }) if max.is_zero() || *max > Decimal::one() => {
+            return Err(ContractError::InvalidWeightCap {})
+        }
+        // A share needs a total to be taken of, which only these strategies track
+        Some(WeightCap::Percent { .. })
+            if !matches!(
+                strategy,
+                VotingStrategy::Allowlist {} | VotingStrategy::Registry {}
+            ) =>
+        {
+            return Err(ContractError::InvalidWeightCap {})
+        }
+        _ => {}
+    }
+
+    match min_support {
+        Some(min_support) if min_support > Decimal::one() => {
+            return Err(ContractError::InvalidThreshold {})
+        }
+        _ => {}
+    }
+    if let Some(veto) = &veto {
+        if !options.contains(&veto.option)
+            || veto.threshold.is_zero()
+            || veto.threshold > Decimal::one()
+        {
+            return Err(ContractError::InvalidVeto {});
+        }
+    }
+    if let Some(quorum) = quorum {
+        let tracked = matches!(
+            strategy,
+            VotingStrategy::Allowlist {} | VotingStrategy::Registry {}
+        );
+        if quorum > Decimal::one() || !tracked {
+            return Err(ContractError::InvalidQuorum {});
+        }
+    }
+    if let Some(threshold) = threshold {
+        if threshold.is_zero() || threshold > Decimal::one() {
+            return Err(ContractError::InvalidThreshold {});
+        }
+    }
+    validate_labels(&labels)?;
+
+    let mut details = option_details.into_iter();
+    let mut opts: Vec<PollOption> = vec![];
+    for option in options {
+        opts.push(PollOption {
+            name: option,
+            tally: Uint128::zero(),
+            details: details.next().unwrap_or_default(),
+        });
+    }
+
+    let mut poll_chambers: Vec<Chamber> = vec![];
+    for chamber in chambers.iter() {
+        if chamber.threshold > Decimal::one() {
+            return Err(ContractError::InvalidThreshold {});
+        }
+        if poll_chambers.iter().any(|c| c.name == chamber.name) {
+            return Err(ContractError::DuplicateChamber {
+                name: chamber.name.clone(),
+            });
+        }
+        poll_chambers.push(Chamber {
+            name: chamber.name.clone(),
+            threshold: chamber.threshold,
+            options: opts.iter().map(|o| (o.name.clone(), 0)).collect(),
+        });
+    }
+
+    let score_counts = match method {
+        VotingMethod::Score {} => opts
+            .iter()
+            .map(|o| (o.name.clone(), Uint128::zero()))
+            .collect(),
+        _ => vec![],
+    };
+    let sequence = POLL_SEQUENCE.may_load(deps.storage)?.unwrap_or_default() + 1;
+    POLL_SEQUENCE.save(deps.storage, &sequence)?;
+    let mut stats = STATS.may_load(deps.storage)?.unwrap_or_default();
+    stats.polls_created += 1;
+    STATS.save(deps.storage, &stats)?;
+    let poll = Poll {
+        creator,
+        question,
+        options: opts,
+        series_id: series_id.clone(),
+        strategy,
+        retired_options: vec![],
+        end_time,
+        status: PollStatus::Open,
+        annul_reason: None,
+        subject,
+        chambers: poll_chambers,
+        weight_curve: weight_curve.unwrap_or(WeightCurve::Linear),
+        weight_cap,
+        basket,
+        resolution,
+        vote_token,
+        correction_window,
+        min_support,
+        group,
+        max_selections,
+        upgrade,
+        score_counts,
+        method,
+        privacy: privacy.unwrap_or(Privacy::Public),
+        commit_deadline,
+        quorum,
+        veto,
+        deposit,
+        participation: Uint128::zero(),
+        abstain: Uint128::zero(),
+        sequence,
+        finalizer,
+        execution,
+        template,
+        threshold,
+        result: None,
+        lottery,
+        tie_break,
+        labels,
+        frozen: false,
+        max_ballots,
+        description,
+        image_url,
+        metadata,
+        captures,
+        tags,
+        created_at: Some(BlockStamp {
+            height: env.block.height,
+            time: env.block.time,
+        }),
+    };
+
+    save_poll(deps.storage, &poll_id, &poll)?;
+    track_storage(
+        deps.storage,
+        StorageSection::Polls,
+        poll_id.len(),
+        &poll,
+        true,
+    )?;
+
+    for member in allowlist {
+        if member.weight == 0 {
+            return Err(ContractError::InvalidWeight {});
+        }
+        let address = deps.api.addr_validate(&member.address)?;
+        save_allowlist_weight(
+            deps.storage,
+            env.block.height,
+            &poll_id,
+            &address,
+            Some(member.weight),
+        )?;
+    }
+
+    for chamber in chambers {
+        for member in chamber.members {
+            if member.weight == 0 {
+                return Err(ContractError::InvalidWeight {});
+            }
+            let address = deps.api.addr_validate(&member.address)?;
+            let mut memberships = CHAMBER_MEMBERS
+                .may_load(deps.storage, (&poll_id, &address))?
+                .unwrap_or_default();
+            memberships.push((chamber.name.clone(), member.weight));
+            CHAMBER_MEMBERS.save(deps.storage, (&poll_id, &address), &memberships)?;
+        }
+    }
+
+    if let Some(series_id) = series_id {
+        let position = SERIES
+            .prefix(&series_id)
+            .keys(deps.storage, None, None, Order::Descending)
+            .next()
+            .transpose()?
+            .map_or(0, |last| last + 1);
+        SERIES.save(deps.storage, (&series_id, position), &poll_id)?;
+        let key_len = series_id.len() + 8;
+        track_storage(
+            deps.storage,
+            StorageSection::Indexes,
+            key_len,
+            &poll_id,
+            true,
+        )?;
+    }
+
+    for label in poll.labels.iter() {
+        LABELS.save(
+            deps.storage,
+            (&label.key, &label.value, &poll_id),
+            &Empty {},
+        )?;
+        let key_len = label.key.len() + label.value.len() + poll_id.len();
+        track_storage(
+            deps.storage,
+            StorageSection::Indexes,
+            key_len,
+            &Empty {},
+            true,
+        )?;
+    }
+
+    SEQUENCE_POLLS.save(deps.storage, poll.sequence, &poll_id)?;
+    track_storage(deps.storage, StorageSection::Indexes, 8, &poll_id, true)?;
+    CREATOR_POLLS.save(deps.storage, (&poll.creator, &poll_id), &Empty {})?;
+    track_storage(
+        deps.storage,
+        StorageSection::Indexes,
+        poll.creator.as_str().len() + poll_id.len(),
+        &Empty {},
+        true,
+    )?;
+    for tag in poll.tags.iter() {
+        TAGS.save(deps.storage, (tag, &poll_id), &Empty {})?;
+        let key_len = tag.len() + poll_id.len();
+        track_storage(
+            deps.storage,
+            StorageSection::Indexes,
+            key_len,
+            &Empty {},
+            true,
+        )?;
+    }
+
+    Ok(poll_id)
+}
+
+/// Lowercases `tags`, failing unless they are distinct, non-empty and within
+/// `MAX_LABEL_LENGTH`.
+fn validate_tags(tags: Vec<String>) -> Result<Vec<String>, ContractError> {
+    if tags.len() > MAX_TAGS {
+        return Err(ContractError::TooManyTags { max: MAX_TAGS });
+    }
+    let mut validated: Vec<String> = vec![];
+    for tag in tags {
+        let normalized = tag.to_lowercase();
+        if normalized.is_empty()
+            || normalized.len() > MAX_LABEL_LENGTH
+            || validated.contains(&normalized)
+        {
+            return Err(ContractError::InvalidTag { tag });
+        }
+        validated.push(normalized);
+    }
+    Ok(validated)
+}
+
+/// Fails unless every label has a non-empty key and value within
+/// `MAX_LABEL_LENGTH` and no key is repeated.
+fn validate_labels(labels: &[Label]) -> Result<(), ContractError> {
+    if labels.len() > MAX_LABELS {
+        return Err(ContractError::TooManyLabels { max: MAX_LABELS });
+    }
+    for (i, label) in labels.iter().enumerate() {
+        let invalid = label.key.is_empty()
+            || label.value.is_empty()
+            || label.key.len() > MAX_LABEL_LENGTH
+            || label.value.len() > MAX_LABEL_LENGTH
+            || labels[..i].iter().any(|l| l.key == label.key);
+        if invalid {
+            return Err(ContractError::InvalidLabel {
+                key: label.key.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Fails if the poll's question, options or descriptive fields exceed their
+/// size limits. Checked on drafts too, as they are stored until published.
+fn validate_poll_sizes(config: &Config, input: &PollInput) -> Result<(), ContractError> {
+    if input.options.len() > config.max_options as usize {
+        return Err(ContractError::TooManyOptions {
+            max: config.max_options,
+        });
+    }
+    if input.question.len() > config.max_question_len as usize {
+        return Err(ContractError::QuestionTooLong {
+            max: config.max_question_len,
+        });
+    }
+    validate_options(&input.options, config.max_option_len)?;
+    if input.description.as_ref().map_or(0, String::len) > MAX_DESCRIPTION_LENGTH {
+        return Err(ContractError::DescriptionTooLong {
+            max: MAX_DESCRIPTION_LENGTH,
+        });
+    }
+    if input.image_url.as_ref().map_or(0, String::len) > MAX_IMAGE_URL_LENGTH {
+        return Err(ContractError::ImageUrlTooLong {
+            max: MAX_IMAGE_URL_LENGTH,
+        });
+    }
+    if input.metadata.as_ref().map_or(0, String::len) > MAX_METADATA_LENGTH {
+        return Err(ContractError::MetadataTooLong {
+            max: MAX_METADATA_LENGTH,
+        });
+    }
+    validate_option_details(&input.options, &input.option_details)
+}
+
+/// Fails if any of `options` is blank, longer than `max_len` bytes, or the
+/// same as another once case and surrounding whitespace are ignored, as votes
+/// could not tell them apart.
+fn validate_options(options: &[String], max_len: u32) -> Result<(), ContractError> {
+    let mut seen = BTreeSet::new();
+    for option in options {
+        if option.len() > max_len as usize {
+            return Err(ContractError::OptionTooLong {
+                option: option.clone(),
+                max: max_len,
+            });
+        }
+        let normalized = option.trim().to_lowercase();
+        if normalized.is_empty() {
+            return Err(ContractError::EmptyOption {});
+        }
+        if !seen.insert(normalized) {
+            return Err(ContractError::DuplicateOption {
+                option: option.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Fails unless `details` is empty or describes each of `options`, with
+/// labels within `MAX_OPTION_LABEL_LENGTH` and URLs within
+/// `MAX_IMAGE_URL_LENGTH`.
+fn validate_option_details(
+    options: &[String],
+    details: &[OptionDetails],
+) -> Result<(), ContractError> {
+    if details.is_empty() {
+        return Ok(());
+    }
+    if details.len() != options.len() {
+        return Err(ContractError::InvalidOptionDetails {
+            option: options.get(details.len()).cloned().unwrap_or_default(),
+        });
+    }
+    for (option, details) in options.iter().zip(details) {
+        let too_long =
+            |field: &Option<String>, max: usize| field.as_ref().map_or(0, String::len) > max;
+        if too_long(&details.label, MAX_OPTION_LABEL_LENGTH)
+            || too_long(&details.image_url, MAX_IMAGE_URL_LENGTH)
+            || too_long(&details.link, MAX_IMAGE_URL_LENGTH)
+        {
+            return Err(ContractError::InvalidOptionDetails {
+                option: option.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks captures have distinct, non-empty names, returning them with their
+/// addresses normalized.
+fn validate_captures(
+    deps: Deps,
+    captures: Vec<DataCapture>,
+) -> Result<Vec<DataCapture>, ContractError> {
+    if captures.len() > MAX_CAPTURES {
+        return Err(ContractError::TooManyCaptures { max: MAX_CAPTURES });
+    }
+    let mut validated: Vec<DataCapture> = vec![];
+    for mut capture in captures {
+        if capture.name.is_empty()
+            || capture.name.len() > MAX_LABEL_LENGTH
+            || validated.iter().any(|c| c.name == capture.name)
+        {
+            return Err(ContractError::InvalidCapture { name: capture.name });
+        }
+        let address = match &mut capture.source {
+            DataSource::TokenInfo { token } => token,
+            DataSource::GroupWeight { group } => group,
+            DataSource::Smart { contract, .. } => contract,
+        };
+        *address = deps.api.addr_validate(address)?.to_string();
+        validated.push(capture);
+    }
+    Ok(validated)
+}
+
+/// Runs the poll's data captures. A failing query is recorded rather than
+/// holding up finalization.
+fn capture_data(deps: Deps, captures: &[DataCapture]) -> StdResult<Vec<CapturedData>> {
+    captures
+        .iter()
+        .map(|capture| {
+            let (contract_addr, msg) = match &capture.source {
+                DataSource::TokenInfo { token } => {
+                    (token.clone(), to_binary(&Cw20QueryMsg::TokenInfo {})?)
+                }
+                DataSource::GroupWeight { group } => {
+                    (group.clone(), to_binary(&Cw4QueryMsg::TotalWeight {})?)
+                }
+                DataSource::Smart { contract, msg } => (contract.clone(), msg.clone()),
+            };
+            let request: QueryRequest<Empty> =
+                QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg });
+            let (value, error) = match deps.querier.raw_query(&to_vec(&request)?) {
+                SystemResult::Ok(ContractResult::Ok(value)) => (Some(value), None),
+                SystemResult::Ok(ContractResult::Err(err)) => (None, Some(err)),
+                SystemResult::Err(err) => (None, Some(err.to_string())),
+            };
+            Ok(CapturedData {
+                name: capture.name.clone(),
+                value,
+                error,
+            })
+        })
+        .collect()
+}
+
+/// Fills the settings `input` leaves unset from the template's `rules`.
+fn apply_template(input: &mut PollInput, rules: PollRules) {
+    input.strategy = input.strategy.take().or(rules.strategy);
+    input.weight_curve = input.weight_curve.take().or(rules.weight_curve);
+    input.weight_cap = input.weight_cap.take().or(rules.weight_cap);
+    input.correction_window = input.correction_window.or(rules.correction_window);
+    input.min_support = input.min_support.or(rules.min_support);
+    input.max_selections = input.max_selections.or(rules.max_selections);
+    input.method = input.method.take().or(rules.method);
+    input.privacy = input.privacy.take().or(rules.privacy);
+    input.quorum = input.quorum.or(rules.quorum);
+    input.threshold = input.threshold.or(rules.threshold);
+    input.tie_break = input.tie_break.take().or(rules.tie_break);
+}
+
+/// Assigns the next sequential numeric poll id, skipping any ids that were
+/// already taken explicitly by callers.
+fn next_poll_id(storage: &mut dyn Storage) -> StdResult<String> {
+    let mut count = POLL_COUNT.may_load(storage)?.unwrap_or_default();
+    let poll_id = loop {
+        count += 1;
+        let poll_id = count.to_string();
+        if !POLLS.has(storage, &poll_id)
+            && !ARCHIVE.has(storage, &poll_id)
+            && !PRUNING.has(storage, &poll_id)
+        {
+            break poll_id;
+        }
+    };
+    POLL_COUNT.save(storage, &count)?;
+    Ok(poll_id)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: String,
+    vote: Vec<String>,
+    amounts: Vec<u64>,
+    memo: Option<String>,
+    gateway: Option<Addr>,
+    salt: Option<String>,
+    abstain: bool,
+) -> Result<Response, ContractError> {
+    if let Some(memo) = &memo {
+        if memo.len() > MAX_MEMO_LENGTH {
+            return Err(ContractError::MemoTooLong {
+                max: MAX_MEMO_LENGTH,
+            });
+        }
+    }
+
+    let mut poll = may_load_poll(deps.storage, &poll_id)?.ok_or(ContractError::PollNotFound {})?;
+
+    let (power, ballot) = ensure_can_vote(
+        deps.as_ref(),
+        &env.block,
+        &poll_id,
+        &poll,
+        &info.sender,
+        &info.funds,
+    )?;
+    match (poll.commit_deadline, salt) {
+        (None, None) => {}
+        (None, Some(_)) => return Err(ContractError::NotCommitReveal {}),
+        (Some(_), None) => return Err(ContractError::CommitRevealRequired {}),
+        (Some(_), Some(salt)) => {
+            let commitment = COMMITMENTS
+                .may_load(deps.storage, (&poll_id, &info.sender))?
+                .ok_or(ContractError::CommitmentNotFound {})?;
+            if commitment.as_slice() != ballot_commitment(&vote, &salt).as_slice() {
+                return Err(ContractError::InvalidReveal {});
+            }
+            COMMITMENTS.remove(deps.storage, (&poll_id, &info.sender));
+            let key_len = poll_id.len() + info.sender.as_str().len();
+            track_storage(
+                deps.storage,
+                StorageSection::Ballots,
+                key_len,
+                &commitment,
+                false,
+            )?;
+        }
+    }
+    let previous = poll.clone();
+    let credits_spent = if abstain {
+        apply_abstain(&mut poll, ballot.as_ref(), &power);
+        0
+    } else {
+        apply_vote(&mut poll, ballot.as_ref(), &vote, &amounts, &power)?
+    };
+
+    // Changing a coin vote locks the new funds and frees the old ones
+    if let Some(ballot) = &ballot {
+        credit_claim(deps.storage, &info.sender, &ballot.escrow)?;
+    }
+    let escrow = match poll.strategy {
+        VotingStrategy::Coins {} => info.funds,
+        _ => vec![],
+    };
+
+    let new_ballot = Ballot {
+        options: vote.clone(),
+        amounts,
+        credits_spent,
+        weight: power.weight,
+        height: env.block.height,
+        time: env.block.time,
+        memo,
+        option_retired: false,
+        chambers: power.chambers,
+        escrow,
+        gateway,
+        // The window runs from the first cast, changes don't extend it
+        locks_at: match &ballot {
+            Some(previous) => previous.locks_at,
+            None => poll
+                .correction_window
+                .map(|window| window.after(&env.block)),
+        },
+    };
+    if ballot.is_none() {
+        count_ballot(deps.storage, &info.sender)?;
+        update_vote_count(deps.storage, &info.sender, true)?;
+        record_streak(deps.storage, &info.sender, poll.sequence)?;
+    }
+    POLL_VOTERS.save(deps.storage, (&poll_id, &info.sender), &Empty {})?;
+    BALLOTS.save(deps.storage, (info.sender.clone(), &poll_id), &new_ballot)?;
+    let key_len = poll_id.len() + info.sender.as_str().len();
+    track_storage(
+        deps.storage,
+        StorageSection::Ballots,
+        key_len,
+        &new_ballot,
+        true,
+    )?;
+    match &ballot {
+        Some(previous) => track_storage(
+            deps.storage,
+            StorageSection::Ballots,
+            key_len,
+            previous,
+            false,
+        )?,
+        None => track_storage(
+            deps.storage,
+            StorageSection::Indexes,
+            key_len,
+            &Empty {},
+            true,
+        )?,
+    }
+    save_poll_changes(deps.storage, &poll_id, &previous, &poll)?;
+
+    let vote = match poll.privacy {
+        Privacy::Public if abstain => "abstain".to_string(),
+        Privacy::Public => vote.join(","),
+        Privacy::Anonymous | Privacy::PostCloseReveal => "hidden".to_string(),
+    };
+    Ok(Response::new()
+        .add_attribute("action", "execute_vote")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("vote", vote)
+        .add_attribute("weight", power.weight.to_string()))
+}
+
+/// Counts a new ballot in the stats. Voters have a streak from their first
+/// ballot on, so one without is counted as a new voter.
+fn count_ballot(storage: &mut dyn Storage, voter: &Addr) -> StdResult<()> {
+    let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+    stats.votes_cast += 1;
+    if !STREAKS.has(storage, voter) {
+        stats.unique_voters += 1;
+    }
+    STATS.save(storage, &stats)
+}
+
+/// Moves `voter` one ballot up or down the ranking of `VOTE_COUNTS`.
+fn update_vote_count(storage: &mut dyn Storage, voter: &Addr, cast: bool) -> StdResult<()> {
+    let count = VOTE_COUNTS.may_load(storage, voter)?.unwrap_or_default();
+    VOTE_RANKING.remove(storage, (count, voter));
+    let count = if cast {
+        count + 1
+    } else {
+        count.saturating_sub(1)
+    };
+    if count == 0 {
+        VOTE_COUNTS.remove(storage, voter);
+        return Ok(());
+    }
+    VOTE_COUNTS.save(storage, voter, &count)?;
+    VOTE_RANKING.save(storage, (count, voter), &Empty {})
+}
+
+/// Counts a first ballot of `voter` on the poll numbered `sequence` towards
+/// their streak. Ballots on polls older than the last one voted on count as
+/// participation but leave the streak as is.
+fn record_streak(storage: &mut dyn Storage, voter: &Addr, sequence: u64) -> StdResult<()> {
+    let mut streak = STREAKS.may_load(storage, voter)?.unwrap_or_default();
+    STREAK_RANKING.remove(storage, streak_rank(&streak, voter));
+    streak.polls_voted += 1;
+    if sequence == streak.last_poll + 1 {
+        streak.current += 1;
+    } else if sequence > streak.last_poll {
+        streak.current = 1;
+    }
+    streak.last_poll = streak.last_poll.max(sequence);
+    streak.longest = streak.longest.max(streak.current);
+    STREAK_RANKING.save(storage, streak_rank(&streak, voter), &Empty {})?;
+    STREAKS.save(storage, voter, &streak)
+}
+
+/// Key of `voter`'s entry in `STREAK_RANKING`.
+fn streak_rank<'a>(streak: &Streak, voter: &'a Addr) -> (u64, u64, &'a Addr) {
+    (u64::MAX - streak.current, u64::MAX - streak.longest, voter)
+}
+
+/// Votes on behalf of a gateway's user. Funds sent by the gateway count as
+/// the voter's own, so coin escrows are refunded to the voter.
+#[allow(clippy::too_many_arguments)]
+fn execute_vote_for(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    voter: String,
+    poll_id: String,
+    vote: Vec<String>,
+    amounts: Vec<u64>,
+    memo: Option<String>,
+    proof: Option<Binary>,
+) -> Result<Response, ContractError> {
+    if !GATEWAYS.has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let voter_info = MessageInfo {
+        sender: deps.api.addr_validate(&voter)?,
+        funds: info.funds,
+    };
+    let res = execute_vote(
+        deps,
+        env,
+        voter_info,
+        poll_id,
+        vote,
+        amounts,
+        memo,
+        Some(info.sender.clone()),
+        None,
+        false,
+    )?;
+
+    let mut res = res
+        .add_attribute("voter", voter)
+        .add_attribute("gateway", info.sender);
+    if let Some(proof) = proof {
+        res = res.add_attribute("proof", proof.to_base64());
+    }
+    Ok(res)
+}
+
+fn execute_update_gateway(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    address: String,
+    trusted: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let validated_address = deps.api.addr_validate(&address)?;
+    let action = if trusted {
+        GATEWAYS.save(deps.storage, &validated_address, &Empty {})?;
+        "execute_add_gateway"
+    } else {
+        GATEWAYS.remove(deps.storage, &validated_address);
+        "execute_remove_gateway"
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", action)
+        .add_attribute("gateway", validated_address.to_string()))
+}
+
+fn execute_set_space_info(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    space_info: SpaceInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    validate_space_info(&space_info)?;
+
+    SPACE_INFO.save(deps.storage, &space_info)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_set_space_info")
+        .add_attribute("name", space_info.name))
+}
+
+fn execute_set_permitted_msgs(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    permitted: Vec<PermittedMsg>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    for kind in permitted.iter() {
+        if let PermittedMsg::WasmExecute { contracts } = kind {
+            for contract in contracts.iter() {
+                deps.api.addr_validate(contract.as_str())?;
+            }
+        }
+    }
+
+    PERMITTED_MSGS.save(deps.storage, &permitted)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_set_permitted_msgs")
+        .add_attribute("permitted", permitted.len().to_string()))
+}
+
+fn execute_set_reputation_params(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    params: ReputationParams,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    if params.scale == 0 {
+        return Err(ContractError::InvalidReputationParams {});
+    }
+
+    REPUTATION_PARAMS.save(deps.storage, &params)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_set_reputation_params")
+        .add_attribute("participation", params.participation.to_string())
+        .add_attribute("accuracy", params.accuracy.to_string())
+        .add_attribute("scale", params.scale.to_string()))
+}
+
+fn execute_set_deposit_pricing(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    pricing: Option<DepositPricing>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match &pricing {
+        Some(pricing) if pricing.window == 0 => {
+            return Err(ContractError::InvalidDepositPricing {})
+        }
+        Some(pricing) => DEPOSIT_PRICING.save(deps.storage, pricing)?,
+        None => DEPOSIT_PRICING.remove(deps.storage),
+    }
+    // Windows of another length can't be compared, counting starts over
+    CREATION_VOLUME.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_set_deposit_pricing")
+        .add_attribute("adaptive", pricing.is_some().to_string()))
+}
+
+fn execute_set_template(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    name: String,
+    rules: Option<PollRules>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    if name.is_empty() || name.len() > MAX_TEMPLATE_NAME_LENGTH {
+        return Err(ContractError::InvalidTemplate {});
+    }
+
+    let action = match rules {
+        Some(rules) => {
+            TEMPLATES.save(deps.storage, &name, &rules)?;
+            "set"
+        }
+        None => {
+            TEMPLATES.remove(deps.storage, &name);
+            "removed"
+        }
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_set_template")
+        .add_attribute("name", name)
+        .add_attribute("template", action))
+}
+
+fn execute_import_template(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    export: Binary,
+) -> Result<Response, ContractError> {
+    let export: TemplateExport =
+        from_binary(&export).map_err(|_| ContractError::InvalidTemplate {})?;
+    if export.contract != CONTRACT_NAME || export.format != TEMPLATE_FORMAT {
+        return Err(ContractError::InvalidTemplate {});
+    }
+    execute_set_template(deps, env, info, export.name, Some(export.rules))
+}
+
+/// Fails unless `msg` matches one of the permitted kinds, so polls opened by
+/// anyone can't carry arbitrary privileged actions.
+fn ensure_permitted(deps: Deps, env: &Env, msg: &CosmosMsg) -> Result<(), ContractError> {
+    let permitted = PERMITTED_MSGS.may_load(deps.storage)?.unwrap_or_default();
+    let allowed = permitted.iter().any(|kind| match (kind, msg) {
+        (PermittedMsg::BankSend { max }, CosmosMsg::Bank(BankMsg::Send { amount, .. })) => {
+            amount.iter().all(|coin| {
+                max.iter()
+                    .any(|cap| cap.denom == coin.denom && coin.amount <= cap.amount)
+            })
+        }
+        (
+            PermittedMsg::WasmExecute { contracts },
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }),
+        ) => contracts.iter().any(|contract| contract == contract_addr),
+        (PermittedMsg::Migrate {}, CosmosMsg::Wasm(WasmMsg::Migrate { contract_addr, .. })) => {
+            *contract_addr == env.contract.address
+        }
+        _ => false,
+    });
+    if !allowed {
+        return Err(ContractError::MsgNotPermitted {});
+    }
+    Ok(())
+}
+
+/// Keeps branding small enough to be cheap to store and safe to render.
+fn validate_space_info(space_info: &SpaceInfo) -> Result<(), ContractError> {
+    let bounded = |text: &str, max: usize| !text.is_empty() && text.len() <= max;
+    let valid = bounded(&space_info.name, 64)
+        && space_info.logo_uri.iter().all(|uri| bounded(uri, 256))
+        && space_info.colors.len() <= 4
+        && space_info.colors.iter().all(|color| bounded(color, 16))
+        && space_info.links.len() <= 5
+        && space_info
+            .links
+            .iter()
+            .all(|link| bounded(&link.label, 32) && bounded(&link.url, 256));
+    if !valid {
+        return Err(ContractError::InvalidSpaceInfo {});
+    }
+    Ok(())
+}
+
+fn execute_retire_option(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: String,
+    option: String,
+    reason: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut poll = may_load_poll(deps.storage, &poll_id)?.ok_or(ContractError::PollNotFound {})?;
+
+    if info.sender != poll.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let tally = poll
+        .options
+        .iter()
+        .find(|o| o.name == option)
+        .map(|o| o.tally)
+        .ok_or(ContractError::OptionNotFound {})?;
+    if poll
+        .retired_options
+        .iter()
+        .any(|retired| retired.option == option)
+    {
+        return Err(ContractError::OptionRetired {});
+    }
+
+    poll.retired_options.push(RetiredOption {
+        option: option.clone(),
+        reason,
+        height: env.block.height,
+        tally_at_retirement: tally,
+    });
+    // Affected ballots are flagged as they are read, see `flag_retired`
+    save_poll(deps.storage, &poll_id, &poll)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_retire_option")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("option", option)
+        .add_attribute("tally_at_retirement", tally.to_string()))
+}
+
+/// Sets `option_retired` on a ballot of a poll with the given retired
+/// options. Votes for retired options are refused, so a ballot backing one
+/// was cast before it was retired.
+pub(crate) fn flag_retired(retired: &[RetiredOption], mut ballot: Ballot) -> Ballot {
+    ballot.option_retired = retired
+        .iter()
+        .any(|retired| ballot.options.contains(&retired.option));
+    ballot
+}
+
+fn execute_revoke_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    let mut poll = may_load_poll(deps.storage, &poll_id)?.ok_or(ContractError::PollNotFound {})?;
+    ensure_open(&poll, &env.block)?;
+
+    let ballot = BALLOTS
+        .may_load(deps.storage, (info.sender.clone(), &poll_id))?
+        .map(|ballot| flag_retired(&poll.retired_options, ballot))
+        .ok_or(ContractError::BallotNotFound {})?;
+    ensure_unlocked(Some(&ballot), &env.block)?;
+
+    remove_ballot_from_tally(&mut poll, &ballot);
+    credit_claim(deps.storage, &info.sender, &ballot.escrow)?;
+
+    BALLOTS.remove(deps.storage, (info.sender.clone(), &poll_id));
+    POLL_VOTERS.remove(deps.storage, (&poll_id, &info.sender));
+    untrack_ballot(deps.storage, &poll_id, &info.sender, &ballot)?;
+    update_vote_count(deps.storage, &info.sender, false)?;
+    save_poll(deps.storage, &poll_id, &poll)?;
+
+    let revoked = match poll.privacy {
+        Privacy::Public => ballot.options.join(","),
+        Privacy::Anonymous | Privacy::PostCloseReveal => "hidden".to_string(),
+    };
+    Ok(Response::new()
+        .add_attribute("action", "execute_revoke_vote")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("voter", info.sender)
+        .add_attribute("revoked_vote", revoked)
+        .add_attribute("weight", ballot.weight.to_string()))
+}
+
+fn execute_resolve_outcome(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: String,
+    outcome: String,
+) -> Result<Response, ContractError> {
+    let mut poll = may_load_poll(deps.storage, &poll_id)?.ok_or(ContractError::PollNotFound {})?;
+    if poll_status(&poll, &env.block) == PollStatus::Open {
+        return Err(ContractError::PollStillOpen {});
+    }
+    if !poll.options.iter().any(|option| option.name == outcome) {
+        return Err(ContractError::OptionNotFound {});
+    }
+
+    let resolution = poll
+        .resolution
+        .as_mut()
+        .ok_or(ContractError::ResolverNotSet {})?;
+    if resolution.outcome.is_some() {
+        return Err(ContractError::AlreadyResolved {});
+    }
+
+    // The arbiter only steps in once the resolver has gone silent
+    let authorized = if resolution.deadline.is_expired(&env.block) {
+        info.sender == resolution.arbiter
+    } else {
+        info.sender == resolution.resolver
+    };
+    if !authorized && !is_active_finalizer(&poll.finalizer, &info.sender, &env.block) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    resolution.outcome = Some(outcome.clone());
+    resolution.resolved_by = Some(info.sender.clone());
+    save_poll(deps.storage, &poll_id, &poll)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_resolve_outcome")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("outcome", outcome)
+        .add_attribute("resolved_by", info.sender))
+}
+
+fn execute_execute(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    let mut poll = may_load_poll(deps.storage, &poll_id)?.ok_or(ContractError::PollNotFound {})?;
+    let mut execution = poll.execution.clone().ok_or(ContractError::NotBinding {})?;
+    if execution.executed {
+        return Err(ContractError::PollExecuted {});
+    }
+
+    if poll_status(&poll, &env.block) == PollStatus::Open {
+        return Err(ContractError::PollStillOpen {});
+    }
+    // Only the result frozen at finalization decides, never the live tallies
+    let result = poll.result.as_ref().ok_or(ContractError::NotFinalized {})?;
+    if result.status != PollStatus::Closed
+        || !result.valid
+        || result.winner.as_ref() != Some(&execution.pass_option)
+    {
+        return Err(ContractError::PollNotPassed {});
+    }
+
+    execution.executed = true;
+    let msgs = execution.msgs.clone();
+    poll.execution = Some(execution);
+    save_poll(deps.storage, &poll_id, &poll)?;
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "execute_execute")
+        .add_attribute("poll_id", poll_id))
+}
+
+/// Whether `sender` is the poll's backup finalizer and its timeout has run out.
+fn is_active_finalizer(finalizer: &Option<Finalizer>, sender: &Addr, block: &BlockInfo) -> bool {
+    match finalizer {
+        Some(finalizer) => finalizer.address == *sender && finalizer.active_at.is_expired(block),
+        None => false,
+    }
+}
+
+fn execute_upgrade(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    let mut poll = may_load_poll(deps.storage, &poll_id)?.ok_or(ContractError::PollNotFound {})?;
+    let mut upgrade = poll.upgrade.clone().ok_or(ContractError::NotAnUpgrade {})?;
+    if upgrade.executed {
+        return Err(ContractError::UpgradeExecuted {});
+    }
+
+    if poll_status(&poll, &env.block) == PollStatus::Open {
+        return Err(ContractError::PollStillOpen {});
+    }
+    let result = poll.result.as_ref().ok_or(ContractError::NotFinalized {})?;
+    if result.status != PollStatus::Closed
+        || !result.valid
+        || result.winner.as_ref() != Some(&poll.options[0].name)
+    {
+        return Err(ContractError::UpgradeRejected {});
+    }
+    if !upgrade.executable_at.is_expired(&env.block) {
+        return Err(ContractError::UpgradeTimelocked {});
+    }
+
+    upgrade.executed = true;
+    let msg = WasmMsg::Migrate {
+        contract_addr: env.contract.address.to_string(),
+        new_code_id: upgrade.code_id,
+        msg: upgrade.msg.clone(),
+    };
+    poll.upgrade = Some(upgrade);
+    save_poll(deps.storage, &poll_id, &poll)?;
+
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("action", "execute_upgrade")
+        .add_attribute("poll_id", poll_id))
+}
+
+fn execute_commit_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: String,
+    commitment: Binary,
+) -> Result<Response, ContractError> {
+    let poll = may_load_poll(deps.storage, &poll_id)?.ok_or(ContractError::PollNotFound {})?;
+    ensure_open(&poll, &env.block)?;
+    ensure_no_funds(&info)?;
+    let deadline = poll
+        .commit_deadline
+        .ok_or(ContractError::NotCommitReveal {})?;
+    if deadline.is_expired(&env.block) {
+        return Err(ContractError::CommitPhaseOver {});
+    }
+
+    // Commitments can be replaced until the deadline, they all have the same size
+    if !COMMITMENTS.has(deps.storage, (&poll_id, &info.sender)) {
+        let key_len = poll_id.len() + info.sender.as_str().len();
+        track_storage(
+            deps.storage,
+            StorageSection::Ballots,
+            key_len,
+            &commitment,
+            true,
+        )?;
+    }
+    COMMITMENTS.save(deps.storage, (&poll_id, &info.sender), &commitment)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_commit_vote")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("voter", info.sender))
+}
+
+/// Hash a commit-reveal ballot is committed as: sha256 of the comma-joined
+/// options followed by the salt.
+pub fn ballot_commitment(vote: &[String], salt: &str) -> Binary {
+    let mut hasher = Sha256::new();
+    hasher.update(vote.join(",").as_bytes());
+    hasher.update(salt.as_bytes());
+    Binary::from(hasher.finalize().as_slice())
+}
+
+fn execute_finalize_poll(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    let poll = may_load_poll(deps.storage, &poll_id)?.ok_or(ContractError::PollNotFound {})?;
+    if poll.result.is_some() {
+        return Err(ContractError::AlreadyFinalized {});
+    }
+    if PENDING_DRAWS.has(deps.storage, &poll_id) {
+        return Err(ContractError::DrawPending {});
+    }
+    let status = poll_status(&poll, &env.block);
+    if status == PollStatus::Open {
+        return Err(ContractError::PollStillOpen {});
+    }
+
+    // Draws wait for a beacon round published after this request, so neither
+    // the finalizer nor the block proposer can pick the outcome
+    if status == PollStatus::Closed && needs_draw(deps.storage, &poll_id, &poll)? {
+        let proxy = RANDOMNESS_PROXY
+            .may_load(deps.storage)?
+            .ok_or(ContractError::RandomnessUnavailable {})?;
+        PENDING_DRAWS.save(deps.storage, &poll_id, &Empty {})?;
+        let msg = WasmMsg::Execute {
+            contract_addr: proxy.to_string(),
+            msg: to_binary(&NoisProxyExecuteMsg::GetNextRandomness {
+                job_id: poll_id.clone(),
+            })?,
+            funds: info.funds,
+        };
+        return Ok(Response::new()
+            .add_message(msg)
+            .add_attribute("action", "execute_finalize_poll")
+            .add_attribute("poll_id", poll_id)
+            .add_attribute("draw_requested", proxy));
+    }
+    ensure_no_funds(&info)?;
+
+    finalize_poll(deps, &env, poll_id, poll, status, None)
+}
+
+fn execute_set_randomness_proxy(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    proxy: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let proxy = proxy
+        .map(|proxy| deps.api.addr_validate(&proxy))
+        .transpose()?;
+    match &proxy {
+        Some(proxy) => RANDOMNESS_PROXY.save(deps.storage, proxy)?,
+        None => RANDOMNESS_PROXY.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_set_randomness_proxy")
+        .add_attribute("proxy", proxy.map(String::from).unwrap_or_default()))
+}
+
+fn execute_nois_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    callback: NoisCallback,
+) -> Result<Response, ContractError> {
+    if RANDOMNESS_PROXY.may_load(deps.storage)?.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let poll_id = callback.job_id;
+    if !PENDING_DRAWS.has(deps.storage, &poll_id) {
+        return Err(ContractError::NoPendingDraw {});
+    }
+    PENDING_DRAWS.remove(deps.storage, &poll_id);
+    let randomness =
+        hex::decode(&callback.randomness).map_err(|_| ContractError::InvalidRandomness {})?;
+    if randomness.len() < 16 {
+        return Err(ContractError::InvalidRandomness {});
+    }
+    let beacon = Beacon {
+        randomness,
+        published: callback.published,
+    };
+
+    let poll = may_load_poll(deps.storage, &poll_id)?.ok_or(ContractError::PollNotFound {})?;
+    if poll.result.is_some() {
+        return Err(ContractError::AlreadyFinalized {});
+    }
+    let status = poll_status(&poll, &env.block);
+    finalize_poll(deps, &env, poll_id, poll, status, Some(&beacon))
+}
+
+/// A round of the randomness beacon, as delivered by the proxy.
+struct Beacon {
+    randomness: Vec<u8>,
+    published: Timestamp,
+}
+
+/// Whether finalizing `poll` takes a random draw, for its lottery or to
+/// break a tie for the lead.
+fn needs_draw(storage: &dyn Storage, poll_id: &str, poll: &Poll) -> StdResult<bool> {
+    if poll.lottery {
+        let (_, valid) = quorum_status(storage, poll_id, poll)?;
+        if valid && poll.options.iter().any(|option| !option.tally.is_zero()) {
+            return Ok(true);
+        }
+    }
+    if poll.tie_break == TieBreak::Random {
+        return Ok(leading_options(storage, poll_id, poll)?.len() > 1);
+    }
+    Ok(false)
+}
+
+/// Fixes the result of an ended poll, drawing from `beacon` where the poll
+/// needs a draw.
+fn finalize_poll(
+    deps: DepsMut,
+    env: &Env,
+    poll_id: String,
+    mut poll: Poll,
+    status: PollStatus,
+    beacon: Option<&Beacon>,
+) -> Result<Response, ContractError> {
+    let (total_power, valid) = quorum_status(deps.storage, &poll_id, &poll)?;
+    let (winner, draw) = match (&status, beacon) {
+        (PollStatus::Closed, Some(beacon)) if poll.lottery => draw_lottery(&poll, beacon),
+        (PollStatus::Closed, Some(beacon)) if poll.tie_break == TieBreak::Random => {
+            let leaders = leading_options(deps.storage, &poll_id, &poll)?;
+            if leaders.len() > 1 {
+                let (point, draw) = draw_point(beacon, leaders.len() as u128);
+                (Some(leaders[point as usize].name.clone()), Some(draw))
+            } else {
+                (leaders.first().map(|leader| leader.name.clone()), None)
+            }
+        }
+        (PollStatus::Closed, _) => (winning_option(deps.storage, &poll_id, &poll)?, None),
+        _ => (None, None),
+    };
+    let reputation_pending = status == PollStatus::Closed
+        && credit_reputation(deps.storage, &poll_id, winner.as_ref(), None)?;
+    let forfeited = settle_deposit(deps.storage, &mut poll)?;
+    poll.status = status.clone();
+    poll.result = Some(PollResult {
+        status: status.clone(),
+        options: poll.tallies(),
+        abstain: poll.abstain,
+        participation: poll.participation,
+        total_power,
+        valid,
+        winner: winner.clone(),
+        finalized_at: env.block.height,
+        draw,
+        captured: capture_data(deps.as_ref(), &poll.captures)?,
+    });
+    save_poll(deps.storage, &poll_id, &poll)?;
+
+    let mut res = Response::new()
+        .add_attribute("action", "execute_finalize_poll")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("valid", valid.to_string())
+        .add_attribute("winner", winner.unwrap_or_default());
+    if let Some(forfeited) = forfeited {
+        res = res.add_attribute("deposit_forfeited", forfeited.to_string());
+    }
+    if reputation_pending {
+        res = res.add_attribute("reputation_pending", "true");
+    }
+    Ok(res)
+}
+
+/// Credits up to `REPUTATION_BATCH` voters on the poll after `start_after`
+/// with taking part, and those whose ballot included `winner` with backing
+/// it. Returns whether voters remain, remembering the last one credited.
+fn credit_reputation(
+    storage: &mut dyn Storage,
+    poll_id: &str,
+    winner: Option<&String>,
+    start_after: Option<Addr>,
+) -> StdResult<bool> {
+    let voters = POLL_VOTERS
+        .prefix(poll_id)
+        .keys(
+            storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(REPUTATION_BATCH + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+    let pending = voters.len() > REPUTATION_BATCH;
+    for voter in voters.iter().take(REPUTATION_BATCH) {
+        let ballot = BALLOTS.load(storage, (voter.clone(), poll_id))?;
+        let mut reputation = REPUTATION.may_load(storage, voter)?.unwrap_or_default();
+        reputation.polls_voted += 1;
+        if let Some(winner) = winner {
+            if ballot.options.contains(winner) {
+                reputation.polls_won += 1;
+            }
+        }
+        REPUTATION.save(storage, voter, &reputation)?;
+    }
+    if pending {
+        REPUTATION_CURSORS.save(storage, poll_id, &voters[REPUTATION_BATCH - 1])?;
+    } else {
+        REPUTATION_CURSORS.remove(storage, poll_id);
+    }
+    Ok(pending)
+}
+
+fn execute_credit_reputation(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    let start_after = REPUTATION_CURSORS
+        .may_load(deps.storage, &poll_id)?
+        .ok_or(ContractError::NothingToCredit {})?;
+    let poll = POLLS.load(deps.storage, &poll_id)?;
+    let winner = poll.result.and_then(|result| result.winner);
+    let pending = credit_reputation(deps.storage, &poll_id, winner.as_ref(), Some(start_after))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_credit_reputation")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("reputation_pending", pending.to_string()))
+}
+
+/// Draws the winner of a lottery poll, each option's chance proportional to
+/// its tally, from the beacon round delivered for it.
+fn draw_lottery(poll: &Poll, beacon: &Beacon) -> (Option<String>, Option<LotteryDraw>) {
+    let total: Uint128 = poll.options.iter().map(|option| option.tally).sum();
+    if total.is_zero() {
+        return (None, None);
+    }
+    let (point, draw) = draw_point(beacon, total.u128());
+
+    let mut cumulative = Uint128::zero();
+    let winner = poll.options.iter().find(|option| {
+        cumulative += option.tally;
+        Uint128::new(point) < cumulative
+    });
+    (winner.map(|option| option.name.clone()), Some(draw))
+}
+
+/// Draws a point below `range` from a beacon round, see `LotteryDraw`.
+fn draw_point(beacon: &Beacon, range: u128) -> (u128, LotteryDraw) {
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&beacon.randomness[..16]);
+    let point = u128::from_be_bytes(bytes) % range;
+    let draw = LotteryDraw {
+        randomness: Binary::from(beacon.randomness.as_slice()),
+        point: Uint128::new(point),
+        published: Some(beacon.published),
+    };
+    (point, draw)
+}
+
+fn execute_settle_deposit(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    let mut poll = may_load_poll(deps.storage, &poll_id)?.ok_or(ContractError::PollNotFound {})?;
+    if poll_status(&poll, &env.block) == PollStatus::Open {
+        return Err(ContractError::PollStillOpen {});
+    }
+    let forfeited = settle_deposit(deps.storage, &mut poll)?.ok_or(ContractError::NoDeposit {})?;
+    save_poll(deps.storage, &poll_id, &poll)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_settle_deposit")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("forfeited", forfeited.to_string()))
+}
+
+/// Hands the poll's deposit back to its creator, or to the collected fees if
+/// the poll is vetoed. Annulled polls always refund it. Returns whether it
+/// was forfeited, `None` if there was no deposit left to settle.
+fn settle_deposit(storage: &mut dyn Storage, poll: &mut Poll) -> StdResult<Option<bool>> {
+    let deposit = match poll.deposit.take() {
+        Some(deposit) => deposit,
+        None => return Ok(None),
+    };
+    let forfeited = poll.status != PollStatus::Annulled && vetoed(poll);
+    if forfeited {
+        let mut collected = COLLECTED_FEES.may_load(storage)?.unwrap_or_default();
+        match collected
+            .iter_mut()
+            .find(|coin| coin.denom == deposit.denom)
+        {
+            Some(coin) => coin.amount += deposit.amount,
+            None => collected.push(deposit),
+        }
+        COLLECTED_FEES.save(storage, &collected)?;
+    } else {
+        credit_claim(storage, &poll.creator, &[deposit])?;
+    }
+    Ok(Some(forfeited))
+}
+
+fn execute_release_escrow(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    let poll = may_load_poll(deps.storage, &poll_id)?.ok_or(ContractError::PollNotFound {})?;
+    if poll_status(&poll, &env.block) == PollStatus::Open {
+        return Err(ContractError::PollStillOpen {});
+    }
+
+    let mut ballot = BALLOTS
+        .may_load(deps.storage, (info.sender.clone(), &poll_id))?
+        .ok_or(ContractError::BallotNotFound {})?;
+    if ballot.escrow.is_empty() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    credit_claim(deps.storage, &info.sender, &ballot.escrow)?;
+    ballot.escrow = vec![];
+    BALLOTS.save(deps.storage, (info.sender.clone(), &poll_id), &ballot)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_release_escrow")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("voter", info.sender))
+}
+
+/// Fails if another poll on `subject` is still open. The registered poll is
+/// checked lazily, so polls that expired or were closed free their subject
+/// without any bookkeeping.
+fn ensure_subject_free(deps: Deps, env: &Env, subject: &str) -> Result<(), ContractError> {
+    if let Some(poll_id) = SUBJECTS.may_load(deps.storage, subject)? {
+        if let Some(poll) = may_load_poll(deps.storage, &poll_id)? {
+            if poll_status(&poll, &env.block) == PollStatus::Open {
+                return Err(ContractError::SubjectInUse { poll_id });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the poll's status as of `block`; an open poll whose `end_time`
+/// has passed is reported as closed even before anyone closes it.
+fn poll_status(poll: &Poll, block: &BlockInfo) -> PollStatus {
+    let status = if poll.status == PollStatus::Open && poll.end_time.is_expired(block) {
+        PollStatus::Closed
+    } else {
+        poll.status.clone()
+    };
+    // Once finalized, the veto is decided by the stored status
+    if status == PollStatus::Closed && poll.result.is_none() && vetoed(poll) {
+        return PollStatus::Vetoed;
+    }
+    status
+}
+
+/// Whether the poll's veto option has more than its threshold of the
+/// participation.
+fn vetoed(poll: &Poll) -> bool {
+    match &poll.veto {
+        Some(veto) => poll.options.iter().any(|option| {
+            option.name == veto.option && option.tally > poll.participation * veto.threshold
+        }),
+        None => false,
+    }
+}
+
+/// Ballots `poll` still accepts if it caps their number, counting every
+/// voter holding one.
+fn remaining_ballots(storage: &dyn Storage, poll_id: &str, poll: &Poll) -> StdResult<Option<u64>> {
+    match poll.max_ballots {
+        Some(max) => {
+            let cast = POLL_VOTERS
+                .prefix(poll_id)
+                .keys_raw(storage, None, None, Order::Ascending)
+                .count() as u64;
+            Ok(Some(max.saturating_sub(cast)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Checks whether `voter` may cast a ballot on `poll` now, sending `funds`,
+/// returning their voting power and the ballot they already hold. Shared by
+/// `Vote` and `SimulateVote` so the preview accepts exactly what voting does,
+/// short of the choices themselves and a reveal's salt.
+fn ensure_can_vote(
+    deps: Deps,
+    block: &BlockInfo,
+    poll_id: &str,
+    poll: &Poll,
+    voter: &Addr,
+    funds: &[Coin],
+) -> Result<(VotingPower, Option<Ballot>), ContractError> {
+    ensure_open(poll, block)?;
+    if poll.strategy != (VotingStrategy::Coins {}) && !funds.is_empty() {
+        return Err(ContractError::UnexpectedFunds {});
+    }
+    if let Some(deadline) = poll.commit_deadline {
+        if !deadline.is_expired(block) {
+            return Err(ContractError::CommitPhaseActive {});
+        }
+    }
+    let power = voting_power(deps, poll_id, poll, voter, funds)?;
+    let ballot = BALLOTS
+        .may_load(deps.storage, (voter.clone(), poll_id))?
+        .map(|ballot| flag_retired(&poll.retired_options, ballot));
+    ensure_unlocked(ballot.as_ref(), block)?;
+    if ballot.is_none() && remaining_ballots(deps.storage, poll_id, poll)? == Some(0) {
+        return Err(ContractError::PollFull {});
+    }
+    Ok((power, ballot))
+}
+
+/// Fails once a ballot's correction window has passed, unless its option was
+/// retired and the voter must pick another one.
+fn ensure_unlocked(ballot: Option<&Ballot>, block: &BlockInfo) -> Result<(), ContractError> {
+    match ballot {
+        Some(ballot) if !ballot.option_retired => match ballot.locks_at {
+            Some(locks_at) if locks_at.is_expired(block) => Err(ContractError::BallotLocked {}),
+            _ => Ok(()),
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Fails unless `poll` is still accepting votes and revocations.
+fn ensure_open(poll: &Poll, block: &BlockInfo) -> Result<(), ContractError> {
+    if poll_status(poll, block) != PollStatus::Open {
+        return Err(ContractError::PollClosed {});
+    }
+    if poll.frozen {
+        return Err(ContractError::PollFrozen {});
+    }
+    Ok(())
+}
+
+/// Weight a voter is counted with, overall and in each chamber they sit in.
+struct VotingPower {
+    weight: Uint128,
+    chambers: Vec<(String, u64)>,
+}
+
+/// Resolves the power `voter` would vote with on `poll` under its strategy,
+/// failing if they are not eligible to vote at all.
+fn voting_power(
+    deps: Deps,
+    poll_id: &str,
+    poll: &Poll,
+    voter: &Addr,
+    funds: &[Coin],
+) -> Result<VotingPower, ContractError> {
+    let raw_weight = match poll.strategy {
+        VotingStrategy::OneAddressOneVote {} => Uint128::new(1),
+        VotingStrategy::Allowlist {} => ALLOWLIST
+            .may_load(deps.storage, (poll_id, voter))?
+            .map(Uint128::from)
+            .ok_or(ContractError::NotEligible {})?,
+        VotingStrategy::Registry {} => MEMBERS
+            .may_load(deps.storage, voter)?
+            .map(Uint128::from)
+            .ok_or(ContractError::NotEligible {})?,
+        VotingStrategy::Coins {} => basket_weight(&poll.basket, funds)?,
+        VotingStrategy::Reputation {} => {
+            let params = REPUTATION_PARAMS
+                .may_load(deps.storage)?
+                .unwrap_or_default();
+            let reputation = REPUTATION
+                .may_load(deps.storage, voter)?
+                .unwrap_or_default();
+            reputation_weight(&params, &reputation)
+        }
+        VotingStrategy::Cw20Balance {}
+        | VotingStrategy::Staked {}
+        | VotingStrategy::Cw4Group {} => {
+            match ELIGIBILITY.may_load(deps.storage, (poll_id, voter))? {
+                Some(weight) => weight,
+                None => queried_weight(deps, poll, voter)?,
+            }
+        }
+        VotingStrategy::Chambers {} => {
+            let chambers = CHAMBER_MEMBERS
+                .may_load(deps.storage, (poll_id, voter))?
+                .ok_or(ContractError::NotEligible {})?;
+            return Ok(VotingPower {
+                weight: Uint128::new(1),
+                chambers,
+            });
+        }
+    };
+
+    Ok(VotingPower {
+        weight: effective_weight(deps.storage, poll_id, poll, raw_weight)?,
+        chambers: vec![],
+    })
+}
+
+/// Reads `voter`'s raw weight from the contract the poll's strategy relies
+/// on. Other strategies have nothing to query.
+fn queried_weight(deps: Deps, poll: &Poll, voter: &Addr) -> Result<Uint128, ContractError> {
+    match poll.strategy {
+        VotingStrategy::Cw20Balance {} => vote_token_balance(deps, poll, voter),
+        VotingStrategy::Staked {} => bonded_amount(deps, voter),
+        VotingStrategy::Cw4Group {} => group_weight(deps, poll, voter),
+        _ => Err(ContractError::PrecheckNotNeeded {}),
+    }
+}
+
+fn reputation_score(params: &ReputationParams, reputation: &Reputation) -> u64 {
+    params.participation * reputation.polls_voted + params.accuracy * reputation.polls_won
+}
+
+fn reputation_weight(params: &ReputationParams, reputation: &Reputation) -> Uint128 {
+    Uint128::from(params.scale) + Uint128::from(reputation_score(params, reputation))
+}
+
+/// Values `funds` against a poll's basket, rejecting denoms it doesn't accept.
+fn basket_weight(basket: &[BasketDenom], funds: &[Coin]) -> Result<Uint128, ContractError> {
+    let mut weight = Uint128::zero();
+    for coin in funds.iter() {
+        let entry = basket
+            .iter()
+            .find(|entry| entry.denom == coin.denom)
+            .ok_or_else(|| ContractError::DenomNotAccepted {
+                denom: coin.denom.clone(),
+            })?;
+        let value = coin
+            .amount
+            .checked_mul(Uint128::from(entry.multiplier))
+            .map_err(StdError::from)?;
+        weight = weight.checked_add(value).map_err(StdError::from)?;
+    }
+
+    if weight.is_zero() {
+        return Err(PaymentError::NoFunds {}.into());
+    }
+    Ok(weight)
+}
+
+/// Reads `voter`'s current balance of the poll's vote token. Holders of none
+/// can't vote.
+fn vote_token_balance(deps: Deps, poll: &Poll, voter: &Addr) -> Result<Uint128, ContractError> {
+    let token = poll
+        .vote_token
+        .as_ref()
+        .ok_or(ContractError::MissingVoteToken {})?;
+    let res: BalanceResponse = deps.querier.query_wasm_smart(
+        token,
+        &Cw20QueryMsg::Balance {
+            address: voter.to_string(),
+        },
+    )?;
+    if res.balance.is_zero() {
+        return Err(ContractError::NotEligible {});
+    }
+    Ok(res.balance)
+}
+
+/// Reads `voter`'s weight in the poll's cw4 group. Non-members, and members
+/// without weight, can't vote.
+fn group_weight(deps: Deps, poll: &Poll, voter: &Addr) -> Result<Uint128, ContractError> {
+    let group = poll.group.as_ref().ok_or(ContractError::MissingGroup {})?;
+    let res: MemberResponse = deps.querier.query_wasm_smart(
+        group,
+        &Cw4QueryMsg::Member {
+            addr: voter.to_string(),
+            at_height: None,
+        },
+    )?;
+    match res.weight {
+        Some(weight) if weight > 0 => Ok(weight.into()),
+        _ => Err(ContractError::NotEligible {}),
+    }
+}
+
+/// Sums what `voter` has delegated in the staking denom. Addresses with
+/// nothing bonded can't vote.
+fn bonded_amount(deps: Deps, voter: &Addr) -> Result<Uint128, ContractError> {
+    let denom = deps.querier.query_bonded_denom()?;
+    let bonded = deps
+        .querier
+        .query_all_delegations(voter)?
+        .into_iter()
+        .filter(|delegation| delegation.amount.denom == denom)
+        .try_fold(Uint128::zero(), |total, delegation| {
+            total.checked_add(delegation.amount.amount)
+        })
+        .map_err(StdError::from)?;
+    if bonded.is_zero() {
+        return Err(ContractError::NotEligible {});
+    }
+    Ok(bonded)
+}
+
+/// Applies the poll's weight cap and curve to a voter's raw weight. Eligible
+/// voters always keep a weight of at least one.
+fn effective_weight(
+    storage: &dyn Storage,
+    poll_id: &str,
+    poll: &Poll,
+    raw_weight: Uint128,
+) -> StdResult<Uint128> {
+    let capped = match &poll.weight_cap {
+        None => raw_weight,
+        Some(WeightCap::Absolute { max }) => raw_weight.min(Uint128::from(*max)),
+        Some(WeightCap::Percent { max }) => {
+            let total_power = match poll.strategy {
+                VotingStrategy::Allowlist {} => ALLOWLIST_TOTALS
+                    .may_load(storage, poll_id)?
+                    .unwrap_or_default()
+                    .into(),
+                VotingStrategy::Registry {} => {
+                    MEMBERS_TOTAL.may_load(storage)?.unwrap_or_default().into()
+                }
+                VotingStrategy::OneAddressOneVote {}
+                | VotingStrategy::Chambers {}
+                | VotingStrategy::Coins {}
+                | VotingStrategy::Cw20Balance {}
+                | VotingStrategy::Staked {}
+                // Refused at creation, as no total is tracked
+                | VotingStrategy::Cw4Group {}
+                | VotingStrategy::Reputation {} => raw_weight,
+            };
+            raw_weight.min(total_power * *max)
+        }
+    };
+
+    let weight = match poll.weight_curve {
+        WeightCurve::Linear => capped,
+        WeightCurve::Sqrt => Uint128::new(integer_sqrt(capped.u128())),
+    };
+
+    Ok(weight.max(Uint128::new(1)))
+}
+
+/// Largest integer whose square does not exceed `n`.
+fn integer_sqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = n / 2;
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// Sets (or with `None`, removes) an address' allowlist weight, keeping the
+/// poll's allowlist total in step.
+fn save_allowlist_weight(
+    storage: &mut dyn Storage,
+    height: u64,
+    poll_id: &str,
+    address: &Addr,
+    weight: Option<u64>,
+) -> StdResult<()> {
+    let previous = ALLOWLIST
+        .may_load(storage, (poll_id, address))?
+        .unwrap_or_default();
+    let total = ALLOWLIST_TOTALS
+        .may_load(storage, poll_id)?
+        .unwrap_or_default();
+
+    match weight {
+        Some(weight) => ALLOWLIST.save(storage, (poll_id, address), &weight)?,
+        None => ALLOWLIST.remove(storage, (poll_id, address)),
+    }
+    ALLOWLIST_TOTALS.save(
+        storage,
+        poll_id,
+        &(total - previous + weight.unwrap_or_default()),
+        height,
+    )
+}
+
+/// Moves a voter's weight from their previous ballot (if any) onto `vote`,
+/// updating the poll's tallies in place and returning the credits the new
+/// ballot costs in quadratic polls. Shared by `Vote` and `SimulateVote`
+/// so the preview can never disagree with the real thing.
+fn apply_vote(
+    poll: &mut Poll,
+    previous: Option<&Ballot>,
+    vote: &[String],
+    amounts: &[u64],
+    power: &VotingPower,
+) -> Result<u64, ContractError> {
+    if vote.is_empty() {
+        return Err(ContractError::EmptySelection {});
+    }
+    if vote.len() > poll.max_selections as usize {
+        return Err(ContractError::TooManySelections {
+            max: poll.max_selections,
+        });
+    }
+
+    for (i, selected) in vote.iter().enumerate() {
+        if vote[..i].contains(selected) {
+            return Err(ContractError::DuplicateSelection {
+                option: selected.clone(),
+            });
+        }
+        if !poll.options.iter().any(|option| &option.name == selected) {
+            return Err(ContractError::OptionNotFound {});
+        }
+        if poll
+            .retired_options
+            .iter()
+            .any(|retired| &retired.option == selected)
+        {
+            return Err(ContractError::OptionRetired {});
+        }
+    }
+
+    let credits_spent = match poll.method {
+        VotingMethod::Quadratic { credits } => {
+            if amounts.len() != vote.len() || amounts.contains(&0) {
+                return Err(ContractError::InvalidVoteAmounts {});
+            }
+            let required = amounts.iter().try_fold(0u64, |sum, n| {
+                n.checked_mul(*n).and_then(|cost| sum.checked_add(cost))
+            });
+            match required {
+                Some(required) if required <= credits => required,
+                _ => return Err(ContractError::InsufficientCredits { credits }),
+            }
+        }
+        VotingMethod::Score {} => {
+            let remaining = poll.options.len() - poll.retired_options.len();
+            if vote.len() != remaining
+                || amounts.len() != vote.len()
+                || amounts.iter().any(|score| *score > MAX_SCORE)
+            {
+                return Err(ContractError::InvalidScores { max: MAX_SCORE });
+            }
+            0
+        }
+        VotingMethod::Choice {} | VotingMethod::Approval {} => {
+            if !amounts.is_empty() {
+                return Err(ContractError::InvalidVoteAmounts {});
+            }
+            0
+        }
+    };
+
+    // Diff the previous votes against the new ones: each option loses what the
+    // old ballot placed on it and gains what the new one does.
+    let votes_before =
+        |option: &str| previous.map_or(0, |b| votes_on(&b.options, &b.amounts, option));
+    for option in poll.options.iter_mut() {
+        let before = previous.map_or(Uint128::zero(), |b| b.weight)
+            * Uint128::from(votes_before(&option.name));
+        let after = power.weight * Uint128::from(votes_on(vote, amounts, &option.name));
+        option.tally = option.tally + after - before;
+    }
+    for count in poll.score_counts.iter_mut() {
+        if let Some(previous) = previous.filter(|b| b.options.contains(&count.0)) {
+            count.1 -= previous.weight;
+        }
+        if vote.contains(&count.0) {
+            count.1 += power.weight;
+        }
+    }
+    if let Some(previous) = previous {
+        poll.participation -= previous.weight;
+        if previous.options.is_empty() {
+            poll.abstain -= previous.weight;
+        }
+    }
+    poll.participation += power.weight;
+    for chamber in poll.chambers.iter_mut() {
+        let chamber_weight = |chambers: &[(String, u64)]| {
+            chambers
+                .iter()
+                .find(|c| c.0 == chamber.name)
+                .map_or(0, |c| c.1)
+        };
+        let before = previous.map_or(0, |b| chamber_weight(&b.chambers));
+        let after = chamber_weight(&power.chambers);
+        for option in chamber.options.iter_mut() {
+            option.1 = option.1 + after * votes_on(vote, amounts, &option.0)
+                - before * votes_before(&option.0);
+        }
+    }
+
+    Ok(credits_spent)
+}
+
+/// Replaces the voter's previous ballot (if any) with an abstention, which
+/// counts towards participation but no option.
+fn apply_abstain(poll: &mut Poll, previous: Option<&Ballot>, power: &VotingPower) {
+    if let Some(previous) = previous {
+        remove_ballot_from_tally(poll, previous);
+    }
+    poll.participation += power.weight;
+    poll.abstain += power.weight;
+}
+
+/// Takes a previously counted ballot back out of the poll's tallies.
+fn remove_ballot_from_tally(poll: &mut Poll, ballot: &Ballot) {
+    poll.participation -= ballot.weight;
+    if ballot.options.is_empty() {
+        poll.abstain -= ballot.weight;
+    }
+    for option in poll.options.iter_mut() {
+        let votes = votes_on(&ballot.options, &ballot.amounts, &option.name);
+        option.tally -= ballot.weight * Uint128::from(votes);
+    }
+    for count in poll.score_counts.iter_mut() {
+        if ballot.options.contains(&count.0) {
+            count.1 -= ballot.weight;
+        }
+    }
+    for (name, weight) in ballot.chambers.iter() {
+        if let Some(chamber) = poll.chambers.iter_mut().find(|c| &c.name == name) {
+            for option in chamber.options.iter_mut() {
+                option.1 -= weight * votes_on(&ballot.options, &ballot.amounts, &option.0);
+            }
+        }
+    }
+}
+
+fn execute_update_allowlist(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: String,
+    add: Vec<Member>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    let mut poll = may_load_poll(deps.storage, &poll_id)?.ok_or(ContractError::PollNotFound {})?;
+
+    if info.sender != poll.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+    if poll.strategy != (VotingStrategy::Allowlist {}) {
+        return Err(ContractError::AllowlistNotEnabled {});
+    }
+    // Closed polls keep the tallies their outcome is decided on
+    ensure_open(&poll, &env.block)?;
+
+    // Ballots already cast by touched addresses are re-weighted (or dropped)
+    // so the tallies always match the current allowlist.
+    for member in add.iter() {
+        if member.weight == 0 {
+            return Err(ContractError::InvalidWeight {});
+        }
+        let address = deps.api.addr_validate(&member.address)?;
+        save_allowlist_weight(
+            deps.storage,
+            env.block.height,
+            &poll_id,
+            &address,
+            Some(member.weight),
+        )?;
+    }
+
+    for address in remove.iter() {
+        let address = deps.api.addr_validate(address)?;
+        save_allowlist_weight(deps.storage, env.block.height, &poll_id, &address, None)?;
+
+        if let Some(ballot) = BALLOTS.may_load(deps.storage, (address.clone(), &poll_id))? {
+            remove_ballot_from_tally(&mut poll, &ballot);
+            BALLOTS.remove(deps.storage, (address.clone(), &poll_id));
+            POLL_VOTERS.remove(deps.storage, (&poll_id, &address));
+            untrack_ballot(deps.storage, &poll_id, &address, &ballot)?;
+            update_vote_count(deps.storage, &address, false)?;
+        }
+    }
+
+    // Re-weighting happens once the allowlist total is final, as percentage
+    // caps depend on it. A change of total moves the cap of every ballot cast
+    // so far, otherwise only the added members' ballots change.
+    let voters: Vec<Addr> = match poll.weight_cap {
+        Some(WeightCap::Percent { .. }) => POLL_VOTERS
+            .prefix(&poll_id)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<_>>()?,
+        _ => add
+            .iter()
+            .map(|member| deps.api.addr_validate(&member.address))
+            .collect::<StdResult<_>>()?,
+    };
+    for address in voters {
+        if let Some(mut ballot) = BALLOTS.may_load(deps.storage, (address.clone(), &poll_id))? {
+            let raw_weight = ALLOWLIST
+                .may_load(deps.storage, (&poll_id, &address))?
+                .unwrap_or_default();
+            let weight = effective_weight(deps.storage, &poll_id, &poll, raw_weight.into())?;
+            for option in poll.options.iter_mut() {
+                let votes = Uint128::from(votes_on(&ballot.options, &ballot.amounts, &option.name));
+                option.tally = option.tally - ballot.weight * votes + weight * votes;
+            }
+            for count in poll.score_counts.iter_mut() {
+                if ballot.options.contains(&count.0) {
+                    count.1 = count.1 - ballot.weight + weight;
+                }
+            }
+            poll.participation = poll.participation - ballot.weight + weight;
+            if ballot.options.is_empty() {
+                poll.abstain = poll.abstain - ballot.weight + weight;
+            }
+            ballot.weight = weight;
+            BALLOTS.save(deps.storage, (address, &poll_id), &ballot)?;
+        }
+    }
+
+    save_poll(deps.storage, &poll_id, &poll)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_update_allowlist")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("added", add.len().to_string())
+        .add_attribute("removed", remove.len().to_string()))
+}
+
+/// Caches the sender's weight on the poll for the rest of its run, so a
+/// change in their balance or membership afterwards no longer counts.
+fn execute_precheck_eligibility(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    let poll = may_load_poll(deps.storage, &poll_id)?.ok_or(ContractError::PollNotFound {})?;
+    ensure_open(&poll, &env.block)?;
+
+    let weight = queried_weight(deps.as_ref(), &poll, &info.sender)?;
+    ELIGIBILITY.save(deps.storage, (&poll_id, &info.sender), &weight)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_precheck_eligibility")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("weight", weight.to_string()))
+}
+
+fn execute_watch_poll(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    if !POLLS.has(deps.storage, &poll_id) {
+        return Err(ContractError::PollNotFound {});
+    }
+
+    if !WATCHERS.has(deps.storage, (&poll_id, &info.sender)) {
+        WATCHERS.save(deps.storage, (&poll_id, &info.sender), &Empty {})?;
+        WATCHER_COUNTS.update(deps.storage, &poll_id, |count| -> StdResult<u64> {
+            Ok(count.unwrap_or_default() + 1)
+        })?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_watch_poll")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("watcher", info.sender))
+}
+
+fn execute_unwatch_poll(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    if WATCHERS.has(deps.storage, (&poll_id, &info.sender)) {
+        WATCHERS.remove(deps.storage, (&poll_id, &info.sender));
+        WATCHER_COUNTS.update(deps.storage, &poll_id, |count| -> StdResult<u64> {
+            Ok(count.unwrap_or_default().saturating_sub(1))
+        })?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_unwatch_poll")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("watcher", info.sender))
+}
+
+fn execute_mute_creator(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    creator: String,
+    muted: bool,
+) -> Result<Response, ContractError> {
+    let creator = deps.api.addr_validate(&creator)?;
+    let action = if muted {
+        MUTED.save(deps.storage, (&info.sender, &creator), &Empty {})?;
+        "execute_mute_creator"
+    } else {
+        MUTED.remove(deps.storage, (&info.sender, &creator));
+        "execute_unmute_creator"
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", action)
+        .add_attribute("creator", creator)
+        .add_attribute("address", info.sender))
+}
+
+fn execute_follow_creator(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    creator: String,
+    followed: bool,
+) -> Result<Response, ContractError> {
+    let creator = deps.api.addr_validate(&creator)?;
+    let action = if followed {
+        FOLLOWED.save(deps.storage, (&info.sender, &creator), &Empty {})?;
+        "execute_follow_creator"
+    } else {
+        FOLLOWED.remove(deps.storage, (&info.sender, &creator));
+        "execute_unfollow_creator"
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", action)
+        .add_attribute("creator", creator)
+        .add_attribute("address", info.sender))
+}
+
+fn execute_delete_poll(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    let mut poll = may_load_poll(deps.storage, &poll_id)?.ok_or(ContractError::PollNotFound {})?;
+
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != poll.creator
+        && info.sender != config.admin
+        && !MODERATORS.has(deps.storage, &info.sender)
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Deleting a poll settles its deposit, so a veto can't be dodged
+    settle_deposit(deps.storage, &mut poll)?;
+    remove_poll(deps.storage, env.block.height, &poll_id, &poll)?;
+    let (ballots_removed, pending) = prune_poll(deps.storage, &poll_id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_delete_poll")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("ballots_removed", ballots_removed.to_string())
+        .add_attribute("prune_pending", pending.to_string()))
+}
+
+fn execute_prune_poll(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    if !PRUNING.has(deps.storage, &poll_id) {
+        return Err(ContractError::NothingToPrune {});
+    }
+    let (ballots_removed, pending) = prune_poll(deps.storage, &poll_id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_prune_poll")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("ballots_removed", ballots_removed.to_string())
+        .add_attribute("prune_pending", pending.to_string()))
+}
+
+#[derive(Clone, Copy)]
+enum StorageSection {
+    Polls,
+    Ballots,
+    Indexes,
+    Archive,
+}
+
+/// Records an entry of `section` being written (`added`) or removed, sized as
+/// its key plus its serialized value.
+fn track_storage<T: Serialize>(
+    storage: &mut dyn Storage,
+    section: StorageSection,
+    key_len: usize,
+    value: &T,
+    added: bool,
+) -> StdResult<()> {
+    let bytes = (key_len + to_vec(value)?.len()) as u64;
+    let mut stats = STORAGE_STATS.may_load(storage)?.unwrap_or_default();
+    let entry = match section {
+        StorageSection::Polls => &mut stats.polls,
+        StorageSection::Ballots => &mut stats.ballots,
+        StorageSection::Indexes => &mut stats.indexes,
+        StorageSection::Archive => &mut stats.archive,
+    };
+    if added {
+        entry.count += 1;
+        entry.bytes += bytes;
+    } else {
+        entry.count = entry.count.saturating_sub(1);
+        entry.bytes = entry.bytes.saturating_sub(bytes);
+    }
+    STORAGE_STATS.save(storage, &stats)
+}
+
+/// Drops a removed ballot and its voter index entry from the storage stats.
+fn untrack_ballot(
+    storage: &mut dyn Storage,
+    poll_id: &str,
+    voter: &Addr,
+    ballot: &Ballot,
+) -> StdResult<()> {
+    let key_len = poll_id.len() + voter.as_str().len();
+    track_storage(storage, StorageSection::Ballots, key_len, ballot, false)?;
+    track_storage(storage, StorageSection::Indexes, key_len, &Empty {}, false)
+}
+
+/// Loads a poll with its tallies and totals filled in from their own maps.
+fn may_load_poll(storage: &dyn Storage, poll_id: &str) -> StdResult<Option<Poll>> {
+    POLLS
+        .may_load(storage, poll_id)?
+        .map(|poll| with_tallies(storage, poll_id, poll))
+        .transpose()
+}
+
+pub(crate) fn load_poll(storage: &dyn Storage, poll_id: &str) -> StdResult<Poll> {
+    may_load_poll(storage, poll_id)?.ok_or_else(|| StdError::not_found(type_name::<Poll>()))
+}
+
+fn with_tallies(storage: &dyn Storage, poll_id: &str, poll: StoredPoll) -> StdResult<Poll> {
+    let tallies = (0..poll.options.len() as u32)
+        .map(|idx| {
+            Ok(TALLIES
+                .may_load(storage, (poll_id, idx))?
+                .unwrap_or_default())
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    let totals = POLL_TOTALS.may_load(storage, poll_id)?.unwrap_or_default();
+    Ok(with_counters(poll, &tallies, totals))
+}
+
+/// Fills `poll` in with the given counters, zero wherever they run short.
+fn with_counters(poll: StoredPoll, tallies: &[Uint128], totals: PollTotals) -> Poll {
+    let score_counts = match poll.method {
+        VotingMethod::Score {} => by_option(&poll.options, &totals.score_counts),
+        _ => vec![],
+    };
+    let chambers = poll
+        .chambers
+        .iter()
+        .enumerate()
+        .map(|(idx, chamber)| Chamber {
+            name: chamber.name.clone(),
+            threshold: chamber.threshold,
+            options: by_option(
+                &poll.options,
+                totals
+                    .chambers
+                    .get(idx)
+                    .map_or(&[][..], |counts| &counts[..]),
+            ),
+        })
+        .collect();
+    let options = poll
+        .options
+        .into_iter()
+        .enumerate()
+        .map(|(idx, option)| PollOption {
+            name: option.name,
+            tally: tallies.get(idx).copied().unwrap_or_default(),
+            details: option.details,
+        })
+        .collect();
+    Poll {
+        creator: poll.creator,
+        question: poll.question,
+        options,
+        series_id: poll.series_id,
+        strategy: poll.strategy,
+        retired_options: poll.retired_options,
+        end_time: poll.end_time,
+        status: poll.status,
+        annul_reason: poll.annul_reason,
+        subject: poll.subject,
+        chambers,
+        weight_curve: poll.weight_curve,
+        weight_cap: poll.weight_cap,
+        basket: poll.basket,
+        resolution: poll.resolution,
+        finalizer: poll.finalizer,
+        vote_token: poll.vote_token,
+        correction_window: poll.correction_window,
+        min_support: poll.min_support,
+        group: poll.group,
+        max_selections: poll.max_selections,
+        upgrade: poll.upgrade,
+        execution: poll.execution,
+        template: poll.template,
+        lottery: poll.lottery,
+        tie_break: poll.tie_break,
+        result: poll.result,
+        method: poll.method,
+        score_counts,
+        privacy: poll.privacy,
+        commit_deadline: poll.commit_deadline,
+        quorum: poll.quorum,
+        threshold: poll.threshold,
+        veto: poll.veto,
+        deposit: poll.deposit,
+        participation: totals.participation,
+        abstain: totals.abstain,
+        sequence: poll.sequence,
+        labels: poll.labels,
+        frozen: poll.frozen,
+        max_ballots: poll.max_ballots,
+        description: poll.description,
+        image_url: poll.image_url,
+        metadata: poll.metadata,
+        captures: poll.captures,
+        tags: poll.tags,
+        created_at: poll.created_at,
+    }
+}
+
+/// Pairs each option with its count, zero for those without one.
+fn by_option<T: Copy + Default>(options: &[StoredOption], counts: &[T]) -> Vec<(String, T)> {
+    options
+        .iter()
+        .enumerate()
+        .map(|(idx, option)| {
+            let count = counts.get(idx).copied().unwrap_or_default();
+            (option.name.clone(), count)
+        })
+        .collect()
+}
+
+/// `poll` as kept in `POLLS`, without the counters stored apart.
+fn static_part(poll: &Poll) -> StoredPoll {
+    let poll = poll.clone();
+    StoredPoll {
+        creator: poll.creator,
+        question: poll.question,
+        options: poll
+            .options
+            .into_iter()
+            .map(|option| StoredOption {
+                name: option.name,
+                details: option.details,
+            })
+            .collect(),
+        series_id: poll.series_id,
+        strategy: poll.strategy,
+        retired_options: poll.retired_options,
+        end_time: poll.end_time,
+        status: poll.status,
+        annul_reason: poll.annul_reason,
+        subject: poll.subject,
+        chambers: poll
+            .chambers
+            .into_iter()
+            .map(|chamber| StoredChamber {
+                name: chamber.name,
+                threshold: chamber.threshold,
+            })
+            .collect(),
+        weight_curve: poll.weight_curve,
+        weight_cap: poll.weight_cap,
+        basket: poll.basket,
+        resolution: poll.resolution,
+        finalizer: poll.finalizer,
+        vote_token: poll.vote_token,
+        correction_window: poll.correction_window,
+        min_support: poll.min_support,
+        group: poll.group,
+        max_selections: poll.max_selections,
+        upgrade: poll.upgrade,
+        execution: poll.execution,
+        template: poll.template,
+        lottery: poll.lottery,
+        tie_break: poll.tie_break,
+        result: poll.result,
+        method: poll.method,
+        privacy: poll.privacy,
+        commit_deadline: poll.commit_deadline,
+        quorum: poll.quorum,
+        threshold: poll.threshold,
+        veto: poll.veto,
+        deposit: poll.deposit,
+        sequence: poll.sequence,
+        labels: poll.labels,
+        frozen: poll.frozen,
+        max_ballots: poll.max_ballots,
+        description: poll.description,
+        image_url: poll.image_url,
+        metadata: poll.metadata,
+        captures: poll.captures,
+        tags: poll.tags,
+        created_at: poll.created_at,
+    }
+}
+
+pub(crate) fn save_poll(storage: &mut dyn Storage, poll_id: &str, poll: &Poll) -> StdResult<()> {
+    POLLS.save(storage, poll_id, &static_part(poll))?;
+    save_tallies(storage, poll_id, None, poll)
+}
+
+/// Persists what a ballot changed in `poll`, rewriting the poll itself only
+/// when more than its counters moved.
+fn save_poll_changes(
+    storage: &mut dyn Storage,
+    poll_id: &str,
+    previous: &Poll,
+    poll: &Poll,
+) -> StdResult<()> {
+    let stored = static_part(poll);
+    if static_part(previous) != stored {
+        POLLS.save(storage, poll_id, &stored)?;
+    }
+    save_tallies(storage, poll_id, Some(previous), poll)
+}
+
+/// Writes the tallies and totals of `poll` that differ from `previous`, or
+/// all of them without one.
+fn save_tallies(
+    storage: &mut dyn Storage,
+    poll_id: &str,
+    previous: Option<&Poll>,
+    poll: &Poll,
+) -> StdResult<()> {
+    for (idx, option) in poll.options.iter().enumerate() {
+        let unchanged = match previous.and_then(|previous| previous.options.get(idx)) {
+            Some(old) => old.tally == option.tally,
+            None => false,
+        };
+        if !unchanged {
+            TALLIES.save(storage, (poll_id, idx as u32), &option.tally)?;
+        }
+    }
+    let totals = totals_of(poll);
+    if previous.map(totals_of) != Some(totals.clone()) {
+        POLL_TOTALS.save(storage, poll_id, &totals)?;
+    }
+    Ok(())
+}
+
+fn totals_of(poll: &Poll) -> PollTotals {
+    PollTotals {
+        participation: poll.participation,
+        abstain: poll.abstain,
+        score_counts: poll.score_counts.iter().map(|count| count.1).collect(),
+        chambers: poll
+            .chambers
+            .iter()
+            .map(|chamber| chamber.options.iter().map(|option| option.1).collect())
+            .collect(),
+    }
+}
+
+/// Indexes every poll under its creator and sequence number. Returns the
+/// number of polls indexed.
+fn index_polls(storage: &mut dyn Storage, block: &BlockInfo) -> StdResult<u64> {
+    let polls = POLLS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut open: BTreeMap<&Addr, u64> = BTreeMap::new();
+    for (poll_id, poll) in polls.iter() {
+        CREATOR_POLLS.save(storage, (&poll.creator, poll_id), &Empty {})?;
+        SEQUENCE_POLLS.save(storage, poll.sequence, poll_id)?;
+        if poll_status(&with_tallies(storage, poll_id, poll.clone())?, block) == PollStatus::Open {
+            *open.entry(&poll.creator).or_default() += 1;
+        }
+    }
+    for (creator, count) in open {
+        OPEN_POLLS.save(storage, creator, &count)?;
+    }
+    Ok(polls.len() as u64)
+}
+
+/// Fails if `creator` created a poll less than the configured cooldown ago,
+/// otherwise records `block` as their latest creation. Nothing is recorded
+/// while no cooldown is configured.
+fn ensure_cooled_down(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    creator: &Addr,
+) -> Result<(), ContractError> {
+    let cooldown = match CONFIG.load(storage)?.poll_creation_cooldown {
+        Some(cooldown) => cooldown,
+        None => return Ok(()),
+    };
+    if let Some(last) = LAST_CREATED.may_load(storage, creator)? {
+        let available_at = last.plus_seconds(cooldown);
+        if block.time < available_at {
+            return Err(ContractError::CreationCooldown { available_at });
+        }
+    }
+    LAST_CREATED.save(storage, creator, &block.time)?;
+    Ok(())
+}
+
+/// Counts a new open poll against `creator`, failing if they already hold
+/// the most the config allows. The count only ever grows here, it is
+/// brought back down to the polls still open once it reaches the limit.
+fn count_open_poll(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    creator: &Addr,
+) -> Result<(), ContractError> {
+    let mut open = OPEN_POLLS.may_load(storage, creator)?.unwrap_or_default();
+    if let Some(max) = CONFIG.load(storage)?.max_polls_per_creator {
+        if open >= max {
+            open = 0;
+            let poll_ids = CREATOR_POLLS
+                .prefix(creator)
+                .keys(storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?;
+            for poll_id in poll_ids {
+                let poll = load_poll(storage, &poll_id)?;
+                if poll_status(&poll, block) == PollStatus::Open {
+                    open += 1;
+                }
+            }
+            if open >= max {
+                return Err(ContractError::TooManyPolls { max });
+            }
+        }
+    }
+    OPEN_POLLS.save(storage, creator, &(open + 1))?;
+    Ok(())
+}
+
+/// Seeds the stats counters from the poll sequence and voting streaks.
+fn count_stats(storage: &mut dyn Storage) -> StdResult<()> {
+    let mut stats = Stats {
+        polls_created: POLL_SEQUENCE.may_load(storage)?.unwrap_or_default(),
+        ..Stats::default()
+    };
+    for streak in STREAKS.range(storage, None, None, Order::Ascending) {
+        let (_, streak) = streak?;
+        stats.votes_cast += streak.polls_voted;
+        stats.unique_voters += 1;
+    }
+    STATS.save(storage, &stats)
+}
+
+/// Ranks voters on the ballots they currently hold and on their streaks.
+fn rank_voters(storage: &mut dyn Storage) -> StdResult<()> {
+    let mut counts: BTreeMap<Addr, u64> = BTreeMap::new();
+    for key in POLL_VOTERS.keys(storage, None, None, Order::Ascending) {
+        let (_, voter) = key?;
+        *counts.entry(voter).or_default() += 1;
+    }
+    for (voter, count) in counts.iter() {
+        VOTE_COUNTS.save(storage, voter, count)?;
+        VOTE_RANKING.save(storage, (*count, voter), &Empty {})?;
+    }
+    let streaks = STREAKS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (voter, streak) in streaks.iter() {
+        STREAK_RANKING.save(storage, streak_rank(streak, voter), &Empty {})?;
+    }
+    Ok(())
+}
+
+/// Deletes a poll and its indexes, leaving a tombstone in `PRUNING` for the
+/// entries keyed by voter or address, which `prune_poll` removes in batches.
+fn remove_poll(
+    storage: &mut dyn Storage,
+    height: u64,
+    poll_id: &str,
+    poll: &Poll,
+) -> StdResult<()> {
+    PRUNING.save(storage, poll_id, &Empty {})?;
+    ALLOWLIST_TOTALS.remove(storage, poll_id, height)?;
+    WATCHER_COUNTS.remove(storage, poll_id);
+    REPUTATION_CURSORS.remove(storage, poll_id);
+
+    if let Some(series_id) = &poll.series_id {
+        let positions = SERIES
+            .prefix(series_id)
+            .range(storage, None, None, Order::Ascending)
+            .filter(|item| matches!(item, Ok((_, id)) if id == poll_id))
+            .map(|item| item.map(|(position, _)| position))
+            .collect::<StdResult<Vec<_>>>()?;
+        for position in positions {
+            SERIES.remove(storage, (series_id, position));
+            track_storage(
+                storage,
+                StorageSection::Indexes,
+                series_id.len() + 8,
+                &poll_id,
+                false,
+            )?;
+        }
+    }
+
+    for label in poll.labels.iter() {
+        LABELS.remove(storage, (&label.key, &label.value, poll_id));
+        let key_len = label.key.len() + label.value.len() + poll_id.len();
+        track_storage(storage, StorageSection::Indexes, key_len, &Empty {}, false)?;
+    }
+
+    SEQUENCE_POLLS.remove(storage, poll.sequence);
+    track_storage(storage, StorageSection::Indexes, 8, &poll_id, false)?;
+    CREATOR_POLLS.remove(storage, (&poll.creator, poll_id));
+    let key_len = poll.creator.as_str().len() + poll_id.len();
+    track_storage(storage, StorageSection::Indexes, key_len, &Empty {}, false)?;
+    for tag in poll.tags.iter() {
+        TAGS.remove(storage, (tag, poll_id));
+        let key_len = tag.len() + poll_id.len();
+        track_storage(storage, StorageSection::Indexes, key_len, &Empty {}, false)?;
+    }
+
+    if let Some(subject) = &poll.subject {
+        if SUBJECTS.may_load(storage, subject)?.as_deref() == Some(poll_id) {
+            SUBJECTS.remove(storage, subject);
+        }
+    }
+
+    POLLS.remove(storage, poll_id);
+    for idx in 0..poll.options.len() {
+        TALLIES.remove(storage, (poll_id, idx as u32));
+    }
+    POLL_TOTALS.remove(storage, poll_id);
+    track_storage(storage, StorageSection::Polls, poll_id.len(), poll, false)
+}
+
+/// Removes up to `PRUNE_BATCH` of the entries a deleted or archived poll left
+/// keyed by voter or address, counting the ballots among them on its archived
+/// record if any. Returns the number of ballots removed and whether entries
+/// may remain; the tombstone is cleared once a batch comes up short.
+fn prune_poll(storage: &mut dyn Storage, poll_id: &str) -> StdResult<(u64, bool)> {
+    let mut budget = PRUNE_BATCH;
+
+    let voters = POLL_VOTERS
+        .prefix(poll_id)
+        .keys(storage, None, None, Order::Ascending)
+        .take(budget)
+        .collect::<StdResult<Vec<_>>>()?;
+    budget -= voters.len();
+    for voter in voters.iter() {
+        // Coins still locked with a ballot go back to the voter
+        let ballot = BALLOTS.load(storage, (voter.clone(), poll_id))?;
+        credit_claim(storage, voter, &ballot.escrow)?;
+        BALLOTS.remove(storage, (voter.clone(), poll_id));
+        POLL_VOTERS.remove(storage, (poll_id, voter));
+        untrack_ballot(storage, poll_id, voter, &ballot)?;
+        update_vote_count(storage, voter, false)?;
+    }
+
+    let allowlisted = ALLOWLIST
+        .prefix(poll_id)
+        .keys(storage, None, None, Order::Ascending)
+        .take(budget)
+        .collect::<StdResult<Vec<_>>>()?;
+    budget -= allowlisted.len();
+    for address in allowlisted.iter() {
+        ALLOWLIST.remove(storage, (poll_id, address));
+    }
+    let checkpoints = ALLOWLIST_TOTALS
+        .changelog()
+        .prefix(poll_id)
+        .keys(storage, None, None, Order::Ascending)
+        .take(budget)
+        .collect::<StdResult<Vec<_>>>()?;
+    budget -= checkpoints.len();
+    for checkpoint in checkpoints {
+        ALLOWLIST_TOTALS
+            .changelog()
+            .remove(storage, (poll_id, checkpoint));
+    }
+
+    let chamber_members = CHAMBER_MEMBERS
+        .prefix(poll_id)
+        .keys(storage, None, None, Order::Ascending)
+        .take(budget)
+        .collect::<StdResult<Vec<_>>>()?;
+    budget -= chamber_members.len();
+    for address in chamber_members.iter() {
+        CHAMBER_MEMBERS.remove(storage, (poll_id, address));
+    }
+
+    let prechecked = ELIGIBILITY
+        .prefix(poll_id)
+        .keys(storage, None, None, Order::Ascending)
+        .take(budget)
+        .collect::<StdResult<Vec<_>>>()?;
+    budget -= prechecked.len();
+    for address in prechecked.iter() {
+        ELIGIBILITY.remove(storage, (poll_id, address));
+    }
+
+    let watchers = WATCHERS
+        .prefix(poll_id)
+        .keys(storage, None, None, Order::Ascending)
+        .take(budget)
+        .collect::<StdResult<Vec<_>>>()?;
+    budget -= watchers.len();
+    for watcher in watchers.iter() {
+        WATCHERS.remove(storage, (poll_id, watcher));
+    }
+
+    // Commitments that were never revealed
+    let commitments = COMMITMENTS
+        .prefix(poll_id)
+        .range(storage, None, None, Order::Ascending)
+        .take(budget)
+        .collect::<StdResult<Vec<_>>>()?;
+    budget -= commitments.len();
+    for (voter, commitment) in commitments.iter() {
+        COMMITMENTS.remove(storage, (poll_id, voter));
+        let key_len = poll_id.len() + voter.as_str().len();
+        track_storage(storage, StorageSection::Ballots, key_len, commitment, false)?;
+    }
+
+    let ballots = voters.len() as u64;
+    if let Some(mut archived) = ARCHIVE.may_load(storage, poll_id)? {
+        let key_len = poll_id.len();
+        track_storage(storage, StorageSection::Archive, key_len, &archived, false)?;
+        archived.ballots += ballots;
+        ARCHIVE.save(storage, poll_id, &archived)?;
+        track_storage(storage, StorageSection::Archive, key_len, &archived, true)?;
+    }
+    let pending = budget == 0;
+    if !pending {
+        PRUNING.remove(storage, poll_id);
+    }
+    Ok((ballots, pending))
+}
+
+fn execute_update_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    changes: ConfigChanges,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let changes = validate_config_changes(deps.as_ref(), &config, changes)?;
+    apply_config_changes(&mut config, changes);
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "execute_update_config"))
+}
+
+fn execute_schedule_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    changes: ConfigChanges,
+    activate_at: Scheduled,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    if activate_at.is_triggered(&env.block) {
+        return Err(ContractError::InvalidSchedule {});
+    }
+
+    let changes = validate_config_changes(deps.as_ref(), &config, changes)?;
+    SCHEDULED_CONFIG.save(
+        deps.storage,
+        &ScheduledConfig {
+            changes,
+            activate_at,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_schedule_config")
+        .add_attribute("activate_at", activate_at.to_string()))
+}
+
+/// Validates `changes` on their own and as applied to `config`.
+fn validate_config_changes(
+    deps: Deps,
+    config: &Config,
+    mut changes: ConfigChanges,
+) -> Result<ConfigChanges, ContractError> {
+    if let Some(treasury) = &changes.treasury {
+        changes.treasury = Some(deps.api.addr_validate(treasury)?.to_string());
+    }
+    if let Some(fee) = &mut changes.cw20_poll_creation_fee {
+        fee.address = deps.api.addr_validate(&fee.address)?.to_string();
+    }
+    let mut changed = config.clone();
+    apply_config_changes(&mut changed, changes.clone());
+    validate_poll_limits(&changed)?;
+    Ok(changes)
+}
+
+fn validate_poll_limits(config: &Config) -> Result<(), ContractError> {
+    if config.min_options == 0 || config.min_options > config.max_options {
+        return Err(ContractError::InvalidOptionLimits {});
+    }
+    if config.max_question_len == 0 || config.max_option_len == 0 {
+        return Err(ContractError::InvalidLengthLimits {});
+    }
+    Ok(())
+}
+
+fn apply_config_changes(config: &mut Config, changes: ConfigChanges) {
+    if let Some(unique_subjects) = changes.unique_subjects {
+        config.unique_subjects = unique_subjects;
+    }
+    if changes.admin_pubkey.is_some() {
+        config.admin_pubkey = changes.admin_pubkey;
+    }
+    if changes.poll_creation_fee.is_some() {
+        config.poll_creation_fee = changes.poll_creation_fee;
+    }
+    if changes.poll_deposit.is_some() {
+        config.poll_deposit = changes.poll_deposit;
+    }
+    // Addresses were validated when the changes were submitted
+    if let Some(treasury) = changes.treasury {
+        config.treasury = Addr::unchecked(treasury);
+    }
+    if let Some(fee) = changes.cw20_poll_creation_fee {
+        config.cw20_poll_creation_fee = Some(Cw20CoinVerified {
+            address: Addr::unchecked(fee.address),
+            amount: fee.amount,
+        });
+    }
+    if changes.max_polls_per_creator.is_some() {
+        config.max_polls_per_creator = changes.max_polls_per_creator;
+    }
+    if changes.poll_creation_cooldown.is_some() {
+        config.poll_creation_cooldown = changes.poll_creation_cooldown;
+    }
+    if let Some(min_options) = changes.min_options {
+        config.min_options = min_options;
+    }
+    if let Some(max_options) = changes.max_options {
+        config.max_options = max_options;
+    }
+    if let Some(max_question_len) = changes.max_question_len {
+        config.max_question_len = max_question_len;
+    }
+    if let Some(max_option_len) = changes.max_option_len {
+        config.max_option_len = max_option_len;
+    }
+}
+
+/// Loads the config as of `block`, with any scheduled change that has come
+/// due applied. Returns whether the scheduled change was applied.
+fn load_config(storage: &dyn Storage, block: &BlockInfo) -> StdResult<(Config, bool)> {
+    let mut config = CONFIG.load(storage)?;
+    match SCHEDULED_CONFIG.may_load(storage)? {
+        Some(scheduled) if scheduled.activate_at.is_triggered(block) => {
+            apply_config_changes(&mut config, scheduled.changes);
+            Ok((config, true))
+        }
+        _ => Ok((config, false)),
+    }
+}
+
+/// Persists a scheduled config change once its activation point has passed.
+/// Runs ahead of every execute so handlers always see the current config.
+fn activate_scheduled_config(storage: &mut dyn Storage, block: &BlockInfo) -> StdResult<()> {
+    let (config, activated) = load_config(storage, block)?;
+    if activated {
+        CONFIG.save(storage, &config)?;
+        SCHEDULED_CONFIG.remove(storage);
+    }
+    Ok(())
+}
+
+fn execute_propose_admin(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    new_admin: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let validated_admin = deps.api.addr_validate(&new_admin)?;
+    PENDING_ADMIN.save(deps.storage, &validated_admin)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_propose_admin")
+        .add_attribute("pending_admin", validated_admin.to_string()))
+}
+
+fn execute_accept_admin(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let pending_admin = PENDING_ADMIN
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingAdmin {})?;
+    if info.sender != pending_admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.admin = pending_admin.clone();
+        Ok(config)
+    })?;
+    PENDING_ADMIN.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_accept_admin")
+        .add_attribute("admin", pending_admin.to_string()))
+}
+
+fn execute_set_guardian(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    guardian: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let guardian = guardian
+        .map(|guardian| deps.api.addr_validate(&guardian))
+        .transpose()?;
+    match &guardian {
+        Some(guardian) => GUARDIAN.save(deps.storage, guardian)?,
+        None => GUARDIAN.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_set_guardian")
+        .add_attribute("guardian", guardian.map(String::from).unwrap_or_default()))
+}
+
+fn execute_set_read_only(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    read_only: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let guardian = GUARDIAN.may_load(deps.storage)?;
+    if info.sender != config.admin && Some(&info.sender) != guardian.as_ref() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    READ_ONLY.save(deps.storage, &read_only)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_set_read_only")
+        .add_attribute("read_only", read_only.to_string()))
+}
+
+fn execute_set_paused(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    PAUSED.save(deps.storage, &paused)?;
+
+    let action = if paused {
+        "execute_pause"
+    } else {
+        "execute_unpause"
+    };
+    Ok(Response::new().add_attribute("action", action))
+}
+
+fn execute_update_moderator(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    address: String,
+    moderator: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let validated_address = deps.api.addr_validate(&address)?;
+    let action = if moderator {
+        MODERATORS.save(deps.storage, &validated_address, &Empty {})?;
+        "execute_add_moderator"
+    } else {
+        MODERATORS.remove(deps.storage, &validated_address);
+        "execute_remove_moderator"
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", action)
+        .add_attribute("moderator", validated_address.to_string()))
+}
+
+fn execute_update_members(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    add: Vec<Member>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Ballots keep the weight they were cast with; registry changes only
+    // affect votes cast afterwards.
+    let mut total = MEMBERS_TOTAL.may_load(deps.storage)?.unwrap_or_default();
+    for member in add.iter() {
+        if member.weight == 0 {
+            return Err(ContractError::InvalidWeight {});
+        }
+        let address = deps.api.addr_validate(&member.address)?;
+        let previous = MEMBERS
+            .may_load(deps.storage, &address)?
+            .unwrap_or_default();
+        MEMBERS.save(deps.storage, &address, &member.weight)?;
+        total = total - previous + member.weight;
+    }
+
+    for address in remove.iter() {
+        let address = deps.api.addr_validate(address)?;
+        total -= MEMBERS
+            .may_load(deps.storage, &address)?
+            .unwrap_or_default();
+        MEMBERS.remove(deps.storage, &address);
+    }
+
+    MEMBERS_TOTAL.save(deps.storage, &total, env.block.height)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_update_members")
+        .add_attribute("added", add.len().to_string())
+        .add_attribute("removed", remove.len().to_string()))
+}
+
+/// Moves an open poll to `status` (closed or cancelled) on behalf of its
+/// creator or the admin.
+fn execute_end_poll(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: String,
+    status: PollStatus,
+) -> Result<Response, ContractError> {
+    let mut poll = may_load_poll(deps.storage, &poll_id)?.ok_or(ContractError::PollNotFound {})?;
+
+    // Moderators may close abusive polls, but only creators and the admin
+    // can cancel them
+    let config = CONFIG.load(deps.storage)?;
+    let moderator = status == PollStatus::Closed && MODERATORS.has(deps.storage, &info.sender);
+    if info.sender != poll.creator && info.sender != config.admin && !moderator {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if poll.status != PollStatus::Open {
+        return Err(ContractError::PollNotOpen {});
+    }
+    // Past its end time a poll can still be closed, but no longer cancelled
+    if status == PollStatus::Cancelled && poll_status(&poll, &env.block) != PollStatus::Open {
+        return Err(ContractError::PollNotOpen {});
+    }
+
+    poll.status = status;
+    save_poll(deps.storage, &poll_id, &poll)?;
+
+    let action = match poll.status {
+        PollStatus::Cancelled => "execute_cancel_poll",
+        _ => "execute_close_poll",
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", action)
+        .add_attribute("poll_id", poll_id))
+}
+
+fn execute_freeze_poll(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    poll_id: String,
+    frozen: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut poll = may_load_poll(deps.storage, &poll_id)?.ok_or(ContractError::PollNotFound {})?;
+
+    poll.frozen = frozen;
+    save_poll(deps.storage, &poll_id, &poll)?;
+
+    let action = if frozen {
+        "execute_freeze_poll"
+    } else {
+        "execute_unfreeze_poll"
+    };
+    Ok(Response::new()
+        .add_attribute("action", action)
+        .add_attribute("poll_id", poll_id))
+}
+
+fn execute_annul_poll(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    poll_id: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut poll = may_load_poll(deps.storage, &poll_id)?.ok_or(ContractError::PollNotFound {})?;
+    if poll.status == PollStatus::Annulled {
+        return Err(ContractError::PollAnnulled {});
+    }
+
+    poll.status = PollStatus::Annulled;
+    poll.annul_reason = Some(reason.clone());
+    // Nothing the poll decided stands, whatever was staked on it is returned
+    if let Some(result) = &mut poll.result {
+        result.status = PollStatus::Annulled;
+        result.winner = None;
+    }
+    // Escrowed coins are left for each voter to release with `ReleaseEscrow`
+    settle_deposit(deps.storage, &mut poll)?;
+    save_poll(deps.storage, &poll_id, &poll)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_annul_poll")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("reason", reason))
+}
+
+fn execute_archive_poll(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: String,
+) -> Result<Response, ContractError> {
+    let mut poll = may_load_poll(deps.storage, &poll_id)?.ok_or(ContractError::PollNotFound {})?;
+
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != poll.creator
+        && info.sender != config.admin
+        && !is_active_finalizer(&poll.finalizer, &info.sender, &env.block)
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let status = poll_status(&poll, &env.block);
+    if status == PollStatus::Open {
+        return Err(ContractError::PollStillOpen {});
+    }
+
+    settle_deposit(deps.storage, &mut poll)?;
+    remove_poll(deps.storage, env.block.height, &poll_id, &poll)?;
+    let options = poll.tallies();
+    let archived = ArchivedPoll {
+        creator: poll.creator,
+        question: poll.question,
+        options,
+        status,
+        ballots: 0,
+        archived_at: env.block.height,
+    };
+    ARCHIVE.save(deps.storage, &poll_id, &archived)?;
+    track_storage(
+        deps.storage,
+        StorageSection::Archive,
+        poll_id.len(),
+        &archived,
+        true,
+    )?;
+    // Pruned after archiving, so the first batch is counted in the record
+    let (ballots, pending) = prune_poll(deps.storage, &poll_id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_archive_poll")
+        .add_attribute("poll_id", poll_id)
+        .add_attribute("ballots_removed", ballots.to_string())
+        .add_attribute("prune_pending", pending.to_string()))
+}
+
+fn execute_withdraw_fees(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    amount: Option<Coin>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let withdraw_all = amount.is_none();
+    let mut collected = COLLECTED_FEES.may_load(deps.storage)?.unwrap_or_default();
+    let withdrawn = match amount {
+        Some(amount) => {
+            let coin = collected
+                .iter_mut()
+                .find(|coin| coin.denom == amount.denom)
+                .ok_or(ContractError::InsufficientCollectedFees {})?;
+            coin.amount = coin
+                .amount
+                .checked_sub(amount.amount)
+                .map_err(|_| ContractError::InsufficientCollectedFees {})?;
+            vec![amount]
+        }
+        None => std::mem::take(&mut collected),
+    };
+    collected.retain(|coin| !coin.amount.is_zero());
+
+    let withdrawn: Vec<Coin> = withdrawn
+        .into_iter()
+        .filter(|coin| !coin.amount.is_zero())
+        .collect();
+    COLLECTED_FEES.save(deps.storage, &collected)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !withdrawn.is_empty() {
+        messages.push(
+            BankMsg::Send {
+                to_address: config.treasury.to_string(),
+                amount: withdrawn,
+            }
+            .into(),
+        );
+    }
+
+    if withdraw_all {
+        let tokens = COLLECTED_CW20_FEES
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for (token, amount) in tokens {
+            COLLECTED_CW20_FEES.remove(deps.storage, &token);
+            messages.push(
+                WasmMsg::Execute {
+                    contract_addr: token.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: config.treasury.to_string(),
+                        amount,
+                    })?,
+                    funds: vec![],
+                }
+                .into(),
+            );
+        }
+    }
+
+    if messages.is_empty() {
+        return Err(ContractError::InsufficientCollectedFees {});
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "execute_withdraw_fees")
+        .add_attribute("treasury", config.treasury))
+}
+
+fn execute_claim(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let claims = CLAIMS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+
+    if claims.is_empty() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    CLAIMS.remove(deps.storage, &info.sender);
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: claims,
+        })
+        .add_attribute("action", "execute_claim")
+        .add_attribute("address", info.sender))
+}
+
+/// Records `amount` as owed to `address`, to be collected through `Claim`.
+/// Every payout path goes through here so users have a single place to withdraw.
+pub fn credit_claim(storage: &mut dyn Storage, address: &Addr, amount: &[Coin]) -> StdResult<()> {
+    let mut claims = CLAIMS.may_load(storage, address)?.unwrap_or_default();
+
+    for coin in amount.iter().filter(|coin| !coin.amount.is_zero()) {
+        match claims.iter_mut().find(|claim| claim.denom == coin.denom) {
+            Some(claim) => claim.amount += coin.amount,
+            None => claims.push(coin.clone()),
+        }
+    }
+
+    CLAIMS.save(storage, address, &claims)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+
+    if !MIGRATABLE_CONTRACT_NAMES.contains(&stored.contract.as_str()) {
+        return Err(ContractError::CannotMigrate {
+            previous_contract: stored.contract,
+        });
+    }
+
+    let stored_version: Version = stored.version.parse()?;
+    let new_version: Version = CONTRACT_VERSION.parse()?;
+
+    if stored_version > new_version {
+        return Err(ContractError::CannotMigrateVersion {
+            previous_version: stored.version,
+            new_version: CONTRACT_VERSION.to_string(),
+        });
+    }
+
+    // Counters were kept inside each poll until 0.3.0, tallies and totals
+    // until 0.2.0, while 0.1 releases stored polls in a narrower layout still
+    let split = if stored_version < Version::new(0, 3, 0) {
+        split_counters(deps.storage, stored_version < Version::new(0, 2, 0))?
+    } else {
+        0
+    };
+    let upgraded = if stored_version < Version::new(0, 2, 0) {
+        upgrade_v1(deps.storage, &env.block)?
+    } else {
+        0
+    };
+    // Polls have been indexed by creator and sequence, and activity counted
+    // and ranked, since 0.3.0
+    let indexed = if stored_version < Version::new(0, 3, 0) {
+        count_stats(deps.storage)?;
+        rank_voters(deps.storage)?;
+        index_polls(deps.storage, &env.block)?
+    } else {
+        0
+    };
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("previous_contract", stored.contract)
+        .add_attribute("previous_version", stored.version)
+        .add_attribute("new_version", CONTRACT_VERSION)
+        .add_attribute("polls_migrated", split.to_string())
+        .add_attribute("polls_upgraded", upgraded.to_string())
+        .add_attribute("creators_indexed", indexed.to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::SetReadOnly { read_only } => {
+            READ_ONLY.save(deps.storage, &read_only)?;
+            Ok(Response::new()
+                .add_attribute("action", "sudo_set_read_only")
+                .add_attribute("read_only", read_only.to_string()))
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::AllPolls { start_after, limit } => query_all_polls(deps, env, start_after, limit),
+        QueryMsg::LatestPolls { start_after, limit } => {
+            query_latest_polls(deps, env, start_after, limit)
+        }
+        QueryMsg::PollSummaries { start_after, limit } => {
+            query_poll_summaries(deps, env, start_after, limit)
+        }
+        QueryMsg::RandomPolls { seed, limit } => query_random_polls(deps, env, seed, limit),
+        QueryMsg::Poll {
+            poll_id,
+            option_offset,
+            option_limit,
+        } => query_poll(deps, env, poll_id, option_offset, option_limit),
+        QueryMsg::Drafts {
+            creator,
+            start_after,
+            limit,
+        } => query_drafts(deps, env, creator, start_after, limit),
+        QueryMsg::Vote { address, poll_id } => query_vote(deps, env, address, poll_id),
+        QueryMsg::GetConfig {} => query_config(deps, env),
+        QueryMsg::ReadOnly {} => query_read_only(deps, env),
+        QueryMsg::Paused {} => query_paused(deps, env),
+        QueryMsg::Schema { kind } => query_schema(deps, env, kind),
+        QueryMsg::Claims { address } => query_claims(deps, env, address),
+        QueryMsg::ArchivedPolls { start_after, limit } => {
+            query_archived_polls(deps, env, start_after, limit)
+        }
+        QueryMsg::ArchivedResult { poll_id } => query_archived_result(deps, env, poll_id),
+        QueryMsg::Members { start_after, limit } => query_members(deps, env, start_after, limit),
+        QueryMsg::Gateways { start_after, limit } => query_gateways(deps, env, start_after, limit),
+        QueryMsg::Moderators { start_after, limit } => {
+            query_moderators(deps, env, start_after, limit)
+        }
+        QueryMsg::Feed {
+            address,
+            start_after,
+            limit,
+        } => query_feed(deps, env, address, start_after, limit),
+        QueryMsg::FollowedPolls {
+            address,
+            start_after,
+            limit,
+        } => query_followed_polls(deps, env, address, start_after, limit),
+        QueryMsg::MutedCreators {
+            address,
+            start_after,
+            limit,
+        } => query_muted_creators(deps, env, address, start_after, limit),
+        QueryMsg::Watchers {
+            poll_id,
+            start_after,
+            limit,
+        } => query_watchers(deps, env, poll_id, start_after, limit),
+        QueryMsg::ChamberResults { poll_id } => query_chamber_results(deps, env, poll_id),
+        QueryMsg::PollSeries { series_id } => query_poll_series(deps, env, series_id),
+        QueryMsg::PollsByLabel {
+            key,
+            value,
+            start_after,
+            limit,
+        } => query_polls_by_label(deps, env, key, value, start_after, limit),
+        QueryMsg::PollsByCreator {
+            creator,
+            start_after,
+            limit,
+        } => query_polls_by_creator(deps, env, creator, start_after, limit),
+        QueryMsg::PollsByTag {
+            tag,
+            start_after,
+            limit,
+        } => query_polls_by_tag(deps, env, tag, start_after, limit),
+        QueryMsg::VotingPower { poll_id, address } => {
+            query_voting_power(deps, env, poll_id, address)
+        }
+        QueryMsg::SimulateVote {
+            poll_id,
+            address,
+            vote,
+            amounts,
+            funds,
+        } => query_simulate_vote(deps, env, poll_id, address, vote, amounts, funds),
+        QueryMsg::AllVotesForAUser {
+            user_address,
+            start_after,
+            limit,
+        } => query_all_votes_for_a_user(deps, env, user_address, start_after, limit),
+        QueryMsg::StorageStats {} => query_storage_stats(deps, env),
+        QueryMsg::Stats {} => query_stats(deps, env),
+        QueryMsg::Credits { poll_id, address } => query_credits(deps, env, poll_id, address),
+        QueryMsg::Results {
+            poll_id,
+            option_offset,
+            option_limit,
+        } => query_results(deps, env, poll_id, option_offset, option_limit),
+        QueryMsg::Winner { poll_id } => query_winner(deps, env, poll_id),
+        QueryMsg::Reputation { address } => query_reputation(deps, env, address),
+        QueryMsg::ReputationParams {} => query_reputation_params(deps, env),
+        QueryMsg::PollDeposit {} => query_poll_deposit(deps, env),
+        QueryMsg::TotalPowerAtHeight { poll_id, height } => {
+            query_total_power_at_height(deps, env, poll_id, height)
+        }
+        QueryMsg::SpaceInfo {} => query_space_info(deps, env),
+        QueryMsg::Streak { address } => query_streak(deps, env, address),
+        QueryMsg::TopVoters { limit } => query_top_voters(deps, env, limit),
+        QueryMsg::Leaderboard { limit } => query_leaderboard(deps, env, limit),
+        QueryMsg::PermittedMsgs {} => query_permitted_msgs(deps, env),
+        QueryMsg::Templates { start_after, limit } => {
+            query_templates(deps, env, start_after, limit)
+        }
+        QueryMsg::ExportTemplate { name } => query_export_template(deps, env, name),
+        QueryMsg::AllBallotsForPoll {
+            poll_id,
+            start_after,
+            limit,
+        } => query_all_ballots_for_poll(deps, env, poll_id, start_after, limit),
+        QueryMsg::PollVoters {
+            poll_id,
+            start_after,
+            limit,
+        } => query_poll_voters(deps, env, poll_id, start_after, limit),
+        QueryMsg::VerifyBallots { poll_id, claims } => {
+            query_verify_ballots(deps, env, poll_id, claims)
+        }
+        QueryMsg::VoteProof { poll_id, voter } => query_vote_proof(deps, env, poll_id, voter),
+        QueryMsg::VerifyVoteProof {
+            poll_id,
+            voter,
+            proof,
+        } => query_verify_vote_proof(deps, env, poll_id, voter, proof),
+    }
+}
+
+fn query_all_polls(
+    deps: Deps,
+    _env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let polls = POLLS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (poll_id, poll) = item?;
+            let poll = with_tallies(deps.storage, &poll_id, poll)?;
+            Ok(PollInfo { poll_id, poll })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&AllPollsResponse { polls })
+}
+
+fn query_poll_summaries(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let polls = POLLS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (poll_id, poll) = item?;
+            let totals = POLL_TOTALS
+                .may_load(deps.storage, &poll_id)?
+                .unwrap_or_default();
+            // Only a veto needs the tallies to tell the status
+            let poll = if poll.veto.is_some() {
+                with_tallies(deps.storage, &poll_id, poll)?
+            } else {
+                with_counters(poll, &[], totals.clone())
+            };
+            Ok(PollSummary {
+                status: poll_status(&poll, &env.block),
+                remaining_ballots: remaining_ballots(deps.storage, &poll_id, &poll)?,
+                poll_id,
+                question: poll.question,
+                total_votes: totals.participation,
+                end_time: poll.end_time,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&PollSummariesResponse { polls })
+}
+
+fn query_latest_polls(
+    deps: Deps,
+    _env: Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let end = start_after.map(Bound::exclusive);
+
+    let polls = SEQUENCE_POLLS
+        .range(deps.storage, None, end, Order::Descending)
+        .take(limit)
+        .map(|item| {
+            let (_, poll_id) = item?;
+            let poll = load_poll(deps.storage, &poll_id)?;
+            Ok(PollInfo { poll_id, poll })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&AllPollsResponse { polls })
+}
+
+/// Samples open polls in an order drawn from the seed mixed with the block,
+/// so the same request in the same block always returns the same sample.
+fn query_random_polls(deps: Deps, env: Env, seed: u64, limit: Option<u32>) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let mut hasher = Sha256::new();
+    hasher.update(seed.to_be_bytes());
+    hasher.update(env.block.height.to_be_bytes());
+    hasher.update(env.block.time.nanos().to_be_bytes());
+    let round = hasher.finalize();
+
+    // Walks the sequence numbers in the order of a random affine permutation,
+    // so every poll is visited at most once without loading them all
+    let sequence = POLL_SEQUENCE.may_load(deps.storage)?.unwrap_or_default();
+    if sequence == 0 {
+        return to_binary(&AllPollsResponse { polls: vec![] });
+    }
+    let word = |at: usize| {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&round[at..at + 8]);
+        u64::from_be_bytes(bytes) % sequence
+    };
+    let mut stride = word(0).max(1);
+    while gcd(stride, sequence) != 1 {
+        stride += 1;
+    }
+    let offset = word(8);
+
+    let mut polls = vec![];
+    for step in 0..sequence.min(RANDOM_POLLS_SCAN) {
+        if polls.len() == limit {
+            break;
+        }
+        let position = (stride as u128 * step as u128 + offset as u128) % sequence as u128;
+        let poll_id = match SEQUENCE_POLLS.may_load(deps.storage, position as u64 + 1)? {
+            Some(poll_id) => poll_id,
+            None => continue,
+        };
+        let poll = match may_load_poll(deps.storage, &poll_id)? {
+            Some(poll) if poll_status(&poll, &env.block) == PollStatus::Open => poll,
+            _ => continue,
+        };
+        polls.push(PollInfo { poll_id, poll });
+    }
+    to_binary(&AllPollsResponse { polls })
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn query_drafts(
+    deps: Deps,
+    _env: Env,
+    creator: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let creator = deps.api.addr_validate(&creator)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let drafts = DRAFTS
+        .prefix(&creator)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (draft_id, poll) = item?;
+            Ok(DraftInfo { draft_id, poll })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&DraftsResponse { drafts })
+}
+
+fn query_poll(
+    deps: Deps,
+    env: Env,
+    poll_id: String,
+    option_offset: Option<u32>,
+    option_limit: Option<u32>,
+) -> StdResult<Binary> {
+    let mut poll = may_load_poll(deps.storage, &poll_id)?;
+    let status = poll.as_ref().map(|poll| poll_status(poll, &env.block));
+    let is_open = status == Some(PollStatus::Open);
+    let option_count = poll.as_ref().map_or(0, |poll| poll.options.len() as u32);
+    let remaining_ballots = match &poll {
+        Some(poll) => remaining_ballots(deps.storage, &poll_id, poll)?,
+        None => None,
+    };
+    // Viability depends on every tally, only the response is paged
+    let mut viable = poll
+        .as_ref()
+        .map(|poll| viable_options(&poll.tallies(), poll.min_support))
+        .unwrap_or_default();
+    if let Some(poll) = poll.as_mut() {
+        let page = option_page(poll.options.len(), option_offset, option_limit);
+        poll.options = poll.options[page.clone()].to_vec();
+        if !poll.score_counts.is_empty() {
+            poll.score_counts = poll.score_counts[page].to_vec();
+        }
+        viable.retain(|option| poll.options.iter().any(|o| &o.name == option));
+    }
+    let average_scores = poll
+        .as_ref()
+        .map(|poll| {
+            poll.options
+                .iter()
+                .zip(poll.score_counts.iter())
+                .map(|(option, count)| {
+                    let average = if count.1.is_zero() {
+                        Decimal::zero()
+                    } else {
+                        Decimal::from_ratio(option.tally, count.1)
+                    };
+                    (option.name.clone(), average)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    to_binary(&PollResponse {
+        poll,
+        status,
+        is_open,
+        viable_options: viable,
+        average_scores,
+        option_count,
+        remaining_ballots,
+    })
+}
+
+/// Positions of the options a query returns: `limit` of them, capped at
+/// `MAX_OPTION_LIMIT`, from `offset` on, or all of them without a limit.
+fn option_page(count: usize, offset: Option<u32>, limit: Option<u32>) -> Range<usize> {
+    let start = (offset.unwrap_or_default() as usize).min(count);
+    let end = match limit {
+        Some(limit) => start + limit.min(MAX_OPTION_LIMIT) as usize,
+        None => count,
+    };
+    start..end.min(count)
+}
+
+/// Whether individual ballots of `poll` may be disclosed right now.
+fn ballots_visible(poll: &Poll, block: &BlockInfo) -> bool {
+    match poll.privacy {
+        Privacy::Public => true,
+        Privacy::Anonymous => false,
+        Privacy::PostCloseReveal => poll_status(poll, block) != PollStatus::Open,
+    }
+}
+
+fn ensure_ballots_visible(deps: Deps, poll_id: &str, block: &BlockInfo) -> StdResult<()> {
+    match may_load_poll(deps.storage, poll_id)? {
+        Some(poll) if !ballots_visible(&poll, block) => Err(StdError::generic_err(format!(
+            "Ballots of poll {} are private",
+            poll_id
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Options retired from `poll_id`, none if it doesn't exist.
+pub(crate) fn retired_options(
+    storage: &dyn Storage,
+    poll_id: &str,
+) -> StdResult<Vec<RetiredOption>> {
+    Ok(POLLS
+        .may_load(storage, poll_id)?
+        .map(|poll| poll.retired_options)
+        .unwrap_or_default())
+}
+
+fn query_vote(deps: Deps, env: Env, address: String, poll_id: String) -> StdResult<Binary> {
+    let validated_address = deps.api.addr_validate(&address)?;
+    ensure_ballots_visible(deps, &poll_id, &env.block)?;
+    let retired = retired_options(deps.storage, &poll_id)?;
+    let vote = BALLOTS
+        .may_load(deps.storage, (validated_address, &poll_id))?
+        .map(|ballot| flag_retired(&retired, ballot));
+
+    to_binary(&VoteResponse { vote })
+}
+
+fn query_poll_voters(
+    deps: Deps,
+    env: Env,
+    poll_id: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    ensure_ballots_visible(deps, &poll_id, &env.block)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+
+    let voters = POLL_VOTERS
+        .prefix(&poll_id)
+        .keys(
+            deps.storage,
+            start.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|address| {
+            let address = address?;
+            let ballot = BALLOTS.load(deps.storage, (address.clone(), &poll_id))?;
+            Ok(PollVoter {
+                address: address.to_string(),
+                options: ballot.options,
+                weight: ballot.weight,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&PollVotersResponse { voters })
+}
+
+fn query_all_ballots_for_poll(
+    deps: Deps,
+    env: Env,
+    poll_id: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    ensure_ballots_visible(deps, &poll_id, &env.block)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let retired = retired_options(deps.storage, &poll_id)?;
+
+    let ballots = POLL_VOTERS
+        .prefix(&poll_id)
+        .keys(
+            deps.storage,
+            start.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|voter| {
+            let voter = voter?;
+            let ballot = BALLOTS.load(deps.storage, (voter.clone(), &poll_id))?;
+            Ok((voter, flag_retired(&retired, ballot)))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&AllBallotsForPollResponse { ballots })
+}
+
+fn query_all_votes_for_a_user(
+    deps: Deps,
+    env: Env,
+    user_address: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let validated_address = deps.api.addr_validate(&user_address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    // BALLOTS is keyed address first, so a user's ballots share a prefix
+    let votes = BALLOTS
+        .prefix(validated_address)
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((poll_id, _)) => match may_load_poll(deps.storage, poll_id) {
+                Ok(Some(poll)) => ballots_visible(&poll, &env.block),
+                // Ballots of a deleted poll linger until pruned
+                Ok(None) => !PRUNING.has(deps.storage, poll_id),
+                Err(_) => true,
+            },
+            Err(_) => true,
+        })
+        .take(limit)
+        .map(|item| {
+            let (poll_id, ballot) = item?;
+            let retired = retired_options(deps.storage, &poll_id)?;
+            Ok((poll_id, flag_retired(&retired, ballot)))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&AllVotesForAUserResponse { votes })
+}
+
+fn query_schema(_deps: Deps, _env: Env, kind: SchemaKind) -> StdResult<Binary> {
+    // Exported by `examples/schema.rs`, minified by `build.rs` and embedded
+    let schema: &[u8] = match kind {
+        SchemaKind::Instantiate => {
+            include_bytes!(concat!(env!("OUT_DIR"), "/instantiate_msg.json"))
+        }
+        SchemaKind::Execute => include_bytes!(concat!(env!("OUT_DIR"), "/execute_msg.json")),
+        SchemaKind::Query => include_bytes!(concat!(env!("OUT_DIR"), "/query_msg.json")),
+        SchemaKind::Migrate => include_bytes!(concat!(env!("OUT_DIR"), "/migrate_msg.json")),
+        SchemaKind::Sudo => include_bytes!(concat!(env!("OUT_DIR"), "/sudo_msg.json")),
+        SchemaKind::Receive => include_bytes!(concat!(env!("OUT_DIR"), "/receive_msg.json")),
+    };
+    to_binary(&SchemaResponse {
+        kind,
+        version: CONTRACT_VERSION.to_string(),
+        schema: Binary::from(schema),
+    })
+}
+
+fn query_paused(deps: Deps, _env: Env) -> StdResult<Binary> {
+    let paused = PAUSED.may_load(deps.storage)?.unwrap_or_default();
+    to_binary(&PausedResponse { paused })
+}
+
+fn query_read_only(deps: Deps, _env: Env) -> StdResult<Binary> {
+    let read_only = READ_ONLY.may_load(deps.storage)?.unwrap_or_default();
+    let guardian = GUARDIAN.may_load(deps.storage)?;
+    to_binary(&ReadOnlyResponse {
+        read_only,
+        guardian,
+    })
+}
+
+fn query_config(deps: Deps, env: Env) -> StdResult<Binary> {
+    let (config, activated) = load_config(deps.storage, &env.block)?;
+
+    let pending_admin = PENDING_ADMIN.may_load(deps.storage)?;
+    let scheduled = if activated {
+        None
+    } else {
+        SCHEDULED_CONFIG.may_load(deps.storage)?
+    };
+
+    to_binary(&ConfigResponse {
+        config,
+        pending_admin,
+        scheduled,
+    })
+}
+
+fn query_claims(deps: Deps, _env: Env, address: String) -> StdResult<Binary> {
+    let validated_address = deps.api.addr_validate(&address)?;
+    let claims = CLAIMS
+        .may_load(deps.storage, &validated_address)?
+        .unwrap_or_default();
+
+    to_binary(&ClaimsResponse { claims })
+}
+
+fn query_archived_polls(
+    deps: Deps,
+    _env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let polls = ARCHIVE
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&ArchivedPollsResponse { polls })
+}
+
+fn query_archived_result(deps: Deps, _env: Env, poll_id: String) -> StdResult<Binary> {
+    let result = ARCHIVE.may_load(deps.storage, &poll_id)?;
+    to_binary(&ArchivedResultResponse { result })
+}
+
+fn query_gateways(
+    deps: Deps,
+    _env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let gateways = GATEWAYS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&GatewaysResponse { gateways })
+}
+
+fn query_moderators(
+    deps: Deps,
+    _env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let moderators = MODERATORS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&ModeratorsResponse { moderators })
+}
+
+fn query_members(
+    deps: Deps,
+    _env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let members = MEMBERS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (address, weight) = item?;
+            Ok(Member {
+                address: address.to_string(),
+                weight,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&MembersResponse { members })
+}
+
+fn query_feed(
+    deps: Deps,
+    _env: Env,
+    address: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let address = deps.api.addr_validate(&address)?;
+    let page = personal_polls(deps, start_after, limit, |poll| {
+        !MUTED.has(deps.storage, (&address, &poll.creator))
+    })?;
+    to_binary(&page)
+}
+
+fn query_followed_polls(
+    deps: Deps,
+    _env: Env,
+    address: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let address = deps.api.addr_validate(&address)?;
+    let page = personal_polls(deps, start_after, limit, |poll| {
+        FOLLOWED.has(deps.storage, (&address, &poll.creator))
+            && !MUTED.has(deps.storage, (&address, &poll.creator))
+    })?;
+    to_binary(&page)
+}
+
+/// Pages through polls by id, keeping those `keep` accepts until `limit` are
+/// found or `FEED_SCAN` polls have been looked at, whichever comes first.
+fn personal_polls(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    keep: impl Fn(&StoredPoll) -> bool,
+) -> StdResult<FeedResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let mut range = POLLS.range(deps.storage, start, None, Order::Ascending);
+    let mut polls = vec![];
+    let mut last = None;
+    let mut scanned = 0;
+    while polls.len() < limit && scanned < FEED_SCAN {
+        let (poll_id, poll) = match range.next() {
+            Some(item) => item?,
+            None => break,
+        };
+        scanned += 1;
+        if keep(&poll) {
+            let poll = with_tallies(deps.storage, &poll_id, poll)?;
+            polls.push(PollInfo {
+                poll_id: poll_id.clone(),
+                poll,
+            });
+        }
+        last = Some(poll_id);
+    }
+    let next_start_after = range.next().and(last);
+
+    Ok(FeedResponse {
+        polls,
+        next_start_after,
+    })
+}
+
+fn query_muted_creators(
+    deps: Deps,
+    _env: Env,
+    address: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let address = deps.api.addr_validate(&address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|creator| deps.api.addr_validate(&creator))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let creators = MUTED
+        .prefix(&address)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&MutedCreatorsResponse { creators })
+}
+
+fn query_watchers(
+    deps: Deps,
+    _env: Env,
+    poll_id: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let watchers = WATCHERS
+        .prefix(&poll_id)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    let count = WATCHER_COUNTS
+        .may_load(deps.storage, &poll_id)?
+        .unwrap_or_default();
+
+    to_binary(&WatchersResponse { watchers, count })
+}
+
+fn query_chamber_results(deps: Deps, _env: Env, poll_id: String) -> StdResult<Binary> {
+    let poll = load_poll(deps.storage, &poll_id)?;
+
+    let chambers: Vec<ChamberResult> = poll
+        .chambers
+        .into_iter()
+        .map(|chamber| {
+            let total_weight: u64 = chamber.options.iter().map(|o| o.1).sum();
+            let passing_options = chamber
+                .options
+                .iter()
+                .filter(|o| {
+                    total_weight > 0 && Decimal::from_ratio(o.1, total_weight) >= chamber.threshold
+                })
+                .map(|o| o.0.clone())
+                .collect();
+            ChamberResult {
+                name: chamber.name,
+                threshold: chamber.threshold,
+                options: chamber.options,
+                total_weight,
+                passing_options,
+            }
+        })
+        .collect();
+
+    // A poll without chambers has no bicameral outcome
+    let passing_options = match chambers.first() {
+        Some(first) => first
+            .passing_options
+            .iter()
+            .filter(|option| chambers.iter().all(|c| c.passing_options.contains(option)))
+            .cloned()
+            .collect(),
+        None => vec![],
+    };
+
+    to_binary(&ChamberResultsResponse {
+        chambers,
+        passing_options,
+    })
+}
+
+fn query_poll_series(deps: Deps, _env: Env, series_id: String) -> StdResult<Binary> {
+    let polls = SERIES
+        .prefix(&series_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (_, poll_id) = item?;
+            let poll = load_poll(deps.storage, &poll_id)?;
+            Ok(PollInfo { poll_id, poll })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&PollSeriesResponse { series_id, polls })
+}
+
+fn query_polls_by_label(
+    deps: Deps,
+    _env: Env,
+    key: String,
+    value: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let polls = LABELS
+        .prefix((&key, &value))
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|poll_id| {
+            let poll_id = poll_id?;
+            let poll = load_poll(deps.storage, &poll_id)?;
+            Ok(PollInfo { poll_id, poll })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&AllPollsResponse { polls })
+}
+
+fn query_polls_by_creator(
+    deps: Deps,
+    _env: Env,
+    creator: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let creator = deps.api.addr_validate(&creator)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let polls = CREATOR_POLLS
+        .prefix(&creator)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|poll_id| {
+            let poll_id = poll_id?;
+            let poll = load_poll(deps.storage, &poll_id)?;
+            Ok(PollInfo { poll_id, poll })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&AllPollsResponse { polls })
+}
+
+fn query_polls_by_tag(
+    deps: Deps,
+    _env: Env,
+    tag: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let tag = tag.to_lowercase();
+
+    let polls = TAGS
+        .prefix(&tag)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|poll_id| {
+            let poll_id = poll_id?;
+            let poll = load_poll(deps.storage, &poll_id)?;
+            Ok(PollInfo { poll_id, poll })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&AllPollsResponse { polls })
+}
+
+fn query_verify_ballots(
+    deps: Deps,
+    env: Env,
+    poll_id: String,
+    claims: Vec<(String, String)>,
+) -> StdResult<Binary> {
+    ensure_ballots_visible(deps, &poll_id, &env.block)?;
+    if claims.len() > MAX_VERIFY_CLAIMS {
+        return Err(StdError::generic_err(format!(
+            "At most {} claims can be verified at once",
+            MAX_VERIFY_CLAIMS
+        )));
+    }
+
+    // Malformed addresses can't have voted, so they fail the claim
+    let results = claims
+        .iter()
+        .map(|(address, option)| match deps.api.addr_validate(address) {
+            Ok(address) => {
+                let ballot = BALLOTS.may_load(deps.storage, (address, &poll_id))?;
+                Ok(matches!(ballot, Some(ballot) if ballot.options.contains(option)))
+            }
+            Err(_) => Ok(false),
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&VerifyBallotsResponse { results })
+}
+
+/// Proof of `voter`'s ballot on the finalized poll, binding it to the
+/// contract, the poll and its result record. Fails if the poll isn't
+/// finalized, its ballots are private or `voter` didn't vote.
+fn vote_proof(deps: Deps, env: &Env, poll_id: String, voter: &str) -> StdResult<VoteProofResponse> {
+    ensure_ballots_visible(deps, &poll_id, &env.block)?;
+    let voter = deps.api.addr_validate(voter)?;
+    let poll = load_poll(deps.storage, &poll_id)?;
+    let result = poll
+        .result
+        .ok_or_else(|| StdError::generic_err(format!("Poll {} is not finalized", poll_id)))?;
+    let ballot = BALLOTS
+        .may_load(deps.storage, (voter.clone(), &poll_id))?
+        .ok_or_else(|| {
+            StdError::generic_err(format!("{} did not vote on poll {}", voter, poll_id))
+        })?;
+
+    let result_hash = Binary::from(Sha256::digest(&to_vec(&result)?).as_slice());
+    let contract = env.contract.address.clone();
+    let record = (
+        &contract,
+        &poll_id,
+        &voter,
+        ballot.weight,
+        &ballot.options,
+        &result_hash,
+    );
+    let proof = Binary::from(Sha256::digest(&to_vec(&record)?).as_slice());
+    Ok(VoteProofResponse {
+        contract,
+        poll_id,
+        voter,
+        weight: ballot.weight,
+        options: ballot.options,
+        result_hash,
+        proof,
+    })
+}
+
+fn query_vote_proof(deps: Deps, env: Env, poll_id: String, voter: String) -> StdResult<Binary> {
+    to_binary(&vote_proof(deps, &env, poll_id, &voter)?)
+}
+
+/// A proof is valid while it matches the one the poll's stored ballot and
+/// result produce.
+fn query_verify_vote_proof(
+    deps: Deps,
+    env: Env,
+    poll_id: String,
+    voter: String,
+    proof: Binary,
+) -> StdResult<Binary> {
+    let valid = match vote_proof(deps, &env, poll_id, &voter) {
+        Ok(expected) => expected.proof == proof,
+        Err(_) => false,
+    };
+    to_binary(&VerifyVoteProofResponse { valid })
+}
+
+fn query_permitted_msgs(deps: Deps, _env: Env) -> StdResult<Binary> {
+    let permitted = PERMITTED_MSGS.may_load(deps.storage)?.unwrap_or_default();
+    to_binary(&PermittedMsgsResponse { permitted })
+}
+
+fn query_templates(
+    deps: Deps,
+    _env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let templates = TEMPLATES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&TemplatesResponse { templates })
+}
+
+fn query_export_template(deps: Deps, _env: Env, name: String) -> StdResult<Binary> {
+    let rules = TEMPLATES.load(deps.storage, &name)?;
+    let export = to_binary(&TemplateExport {
+        contract: CONTRACT_NAME.to_string(),
+        format: TEMPLATE_FORMAT,
+        name,
+        rules,
+    })?;
+
+    to_binary(&ExportTemplateResponse { export })
+}
+
+fn query_space_info(deps: Deps, _env: Env) -> StdResult<Binary> {
+    let info = SPACE_INFO.may_load(deps.storage)?;
+    to_binary(&SpaceInfoResponse { info })
+}
+
+fn query_storage_stats(deps: Deps, _env: Env) -> StdResult<Binary> {
+    let stats = STORAGE_STATS.may_load(deps.storage)?.unwrap_or_default();
+    to_binary(&StorageStatsResponse { stats })
+}
+
+fn query_stats(deps: Deps, _env: Env) -> StdResult<Binary> {
+    let stats = STATS.may_load(deps.storage)?.unwrap_or_default();
+    to_binary(&StatsResponse { stats })
+}
+
+fn query_voting_power(
+    deps: Deps,
+    _env: Env,
+    poll_id: String,
+    address: String,
+) -> StdResult<Binary> {
+    let validated_address = deps.api.addr_validate(&address)?;
+    let poll = load_poll(deps.storage, &poll_id)?;
+
+    let res = match voting_power(deps, &poll_id, &poll, &validated_address, &[]) {
+        Ok(power) => VotingPowerResponse {
+            weight: power.weight,
+            chambers: power.chambers,
+        },
+        Err(ContractError::Std(err)) => return Err(err),
+        Err(_) => VotingPowerResponse {
+            weight: Uint128::zero(),
+            chambers: vec![],
+        },
+    };
+
+    to_binary(&res)
+}
+
+fn query_total_power_at_height(
+    deps: Deps,
+    env: Env,
+    poll_id: String,
+    height: Option<u64>,
+) -> StdResult<Binary> {
+    let poll = load_poll(deps.storage, &poll_id)?;
+    let power = total_power(deps.storage, &poll_id, &poll, height)?.ok_or_else(|| {
+        StdError::generic_err(format!("Total power of poll {} is not tracked", poll_id))
+    })?;
+
+    to_binary(&TotalPowerAtHeightResponse {
+        power,
+        height: height.unwrap_or(env.block.height),
+    })
+}
+
+/// Raw voting power of every eligible voter of an allowlist or registry poll
+/// as of the start of `height`, or now. `None` for strategies without a total.
+fn total_power(
+    storage: &dyn Storage,
+    poll_id: &str,
+    poll: &Poll,
+    height: Option<u64>,
+) -> StdResult<Option<Uint128>> {
+    // Checkpoints make past totals a single lookup rather than a replay
+    let power = match (&poll.strategy, height) {
+        (VotingStrategy::Allowlist {}, None) => ALLOWLIST_TOTALS.may_load(storage, poll_id)?,
+        (VotingStrategy::Allowlist {}, Some(height)) => {
+            ALLOWLIST_TOTALS.may_load_at_height(storage, poll_id, height)?
+        }
+        (VotingStrategy::Registry {}, None) => MEMBERS_TOTAL.may_load(storage)?,
+        (VotingStrategy::Registry {}, Some(height)) => {
+            MEMBERS_TOTAL.may_load_at_height(storage, height)?
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(power.unwrap_or_default().into()))
+}
+
+/// Returns the total power the poll's quorum is measured against, if it sets
+/// one, and whether its participation reaches it.
+fn quorum_status(
+    storage: &dyn Storage,
+    poll_id: &str,
+    poll: &Poll,
+) -> StdResult<(Option<Uint128>, bool)> {
+    match poll.quorum {
+        Some(quorum) => {
+            let total = total_power(storage, poll_id, poll, None)?.unwrap_or_default();
+            Ok((Some(total), poll.participation >= total * quorum))
+        }
+        None => Ok((None, true)),
+    }
+}
+
+/// Returns the option the poll's tallies settle on: the leading one, as long
+/// as it leads outright, the poll reached its quorum and the option holds more
+/// than the poll's threshold of the non-abstaining weight. Annulled polls
+/// have no winner.
+fn winning_option(storage: &dyn Storage, poll_id: &str, poll: &Poll) -> StdResult<Option<String>> {
+    if poll.status == PollStatus::Annulled {
+        return Ok(None);
+    }
+    if let Some(result) = &poll.result {
+        return Ok(result.winner.clone());
+    }
+    // Lottery winners only exist once drawn
+    if poll.lottery {
+        return Ok(None);
+    }
+    let leaders = leading_options(storage, poll_id, poll)?;
+    Ok(match (leaders.len(), &poll.tie_break) {
+        (0, _) => None,
+        (1, _) | (_, TieBreak::FirstListed) => Some(leaders[0].name.clone()),
+        // Random tie-breaks are drawn at finalization
+        (_, TieBreak::None) | (_, TieBreak::Random) => None,
+    })
+}
+
+/// Returns the options sharing the lead, in option order, or none if nobody
+/// voted, the poll missed its quorum or the lead falls short of its threshold.
+fn leading_options<'a>(
+    storage: &dyn Storage,
+    poll_id: &str,
+    poll: &'a Poll,
+) -> StdResult<Vec<&'a PollOption>> {
+    let (_, valid) = quorum_status(storage, poll_id, poll)?;
+    let lead = poll
+        .options
+        .iter()
+        .map(|option| option.tally)
+        .max()
+        .unwrap_or_default();
+    let cleared = match poll.threshold {
+        Some(threshold) => lead > (poll.participation - poll.abstain) * threshold,
+        None => true,
+    };
+    if !valid || !cleared || lead.is_zero() {
+        return Ok(vec![]);
+    }
+    Ok(poll
+        .options
+        .iter()
+        .filter(|option| option.tally == lead)
+        .collect())
+}
+
+fn query_reputation(deps: Deps, _env: Env, address: String) -> StdResult<Binary> {
+    let address = deps.api.addr_validate(&address)?;
+    let params = REPUTATION_PARAMS
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let reputation = REPUTATION
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+
+    to_binary(&ReputationResponse {
+        score: reputation_score(&params, &reputation),
+        weight: reputation_weight(&params, &reputation),
+        reputation,
+    })
+}
+
+fn query_poll_deposit(deps: Deps, env: Env) -> StdResult<Binary> {
+    let (config, _) = load_config(deps.storage, &env.block)?;
+    let pricing = DEPOSIT_PRICING.may_load(deps.storage)?;
+    let recent_polls = match &pricing {
+        Some(pricing) => recent_creations(deps.storage, pricing, &env.block)?,
+        None => 0,
+    };
+    to_binary(&PollDepositResponse {
+        deposit: poll_deposit(deps.storage, &env.block, &config)?,
+        pricing,
+        recent_polls,
+    })
+}
+
+fn query_reputation_params(deps: Deps, _env: Env) -> StdResult<Binary> {
+    let params = REPUTATION_PARAMS
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    to_binary(&ReputationParamsResponse { params })
+}
+
+fn query_winner(deps: Deps, env: Env, poll_id: String) -> StdResult<Binary> {
+    let poll = load_poll(deps.storage, &poll_id)?;
+    let winner = winning_option(deps.storage, &poll_id, &poll)?;
+    let decided = winner.is_some() && poll_status(&poll, &env.block) == PollStatus::Closed;
+
+    // Undecided polls report the first option sharing the lead
+    let lead = poll
+        .options
+        .iter()
+        .map(|option| option.tally)
+        .max()
+        .unwrap_or_default();
+    let annulled = poll.status == PollStatus::Annulled;
+    let leader = poll.options.iter().find(|option| match &winner {
+        Some(winner) => option.name == *winner,
+        None => !annulled && !lead.is_zero() && option.tally == lead,
+    });
+
+    to_binary(&WinnerResponse {
+        option: leader.map(|option| option.name.clone()),
+        count: leader.map(|option| option.tally).unwrap_or_default(),
+        decided,
+    })
+}
+
+fn query_results(
+    deps: Deps,
+    _env: Env,
+    poll_id: String,
+    option_offset: Option<u32>,
+    option_limit: Option<u32>,
+) -> StdResult<Binary> {
+    let poll = load_poll(deps.storage, &poll_id)?;
+    let option_count = poll.options.len() as u32;
+    let page = option_page(poll.options.len(), option_offset, option_limit);
+    if let Some(result) = poll.result {
+        return to_binary(&ResultsResponse {
+            options: result.options[page].to_vec(),
+            option_count,
+            abstain: result.abstain,
+            participation: result.participation,
+            total_power: result.total_power,
+            valid: result.valid,
+            winner: result.winner,
+            finalized_at: Some(result.finalized_at),
+        });
+    }
+    let (total_power, valid) = quorum_status(deps.storage, &poll_id, &poll)?;
+    let winner = winning_option(deps.storage, &poll_id, &poll)?;
+
+    to_binary(&ResultsResponse {
+        options: poll.tallies()[page].to_vec(),
+        option_count,
+        abstain: poll.abstain,
+        participation: poll.participation,
+        total_power,
+        valid,
+        winner,
+        finalized_at: None,
+    })
+}
+
+fn query_credits(deps: Deps, _env: Env, poll_id: String, address: String) -> StdResult<Binary> {
+    let address = deps.api.addr_validate(&address)?;
+    let poll = load_poll(deps.storage, &poll_id)?;
+    let budget = match poll.method {
+        VotingMethod::Quadratic { credits } => credits,
+        VotingMethod::Choice {} | VotingMethod::Approval {} | VotingMethod::Score {} => {
+            return Err(StdError::generic_err(format!(
+                "Poll {} does not use credits",
+                poll_id
+            )))
+        }
+    };
+    let spent = BALLOTS
+        .may_load(deps.storage, (address, &poll_id))?
+        .map_or(0, |ballot| ballot.credits_spent);
+
+    to_binary(&CreditsResponse {
+        budget,
+        spent,
+        remaining: budget - spent,
+    })
+}
+
+fn query_streak(deps: Deps, _env: Env, address: String) -> StdResult<Binary> {
+    let address = deps.api.addr_validate(&address)?;
+    let streak = STREAKS
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+
+    to_binary(&StreakResponse { streak })
+}
+
+/// Ranks every address that ever voted, so the cost grows with the number
+/// of voters rather than `limit`.
+fn query_leaderboard(deps: Deps, _env: Env, limit: Option<u32>) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let entries = STREAK_RANKING
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(limit)
+        .map(|key| {
+            let (_, _, address) = key?;
+            let streak = STREAKS.load(deps.storage, &address)?;
+            Ok(LeaderboardEntry {
+                address: address.to_string(),
+                streak,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&LeaderboardResponse { entries })
+}
+
+fn query_top_voters(deps: Deps, _env: Env, limit: Option<u32>) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let voters = VOTE_RANKING
+        .keys(deps.storage, None, None, Order::Descending)
+        .take(limit)
+        .map(|key| key.map(|(count, address)| (address, count)))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&TopVotersResponse { voters })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn query_simulate_vote(
+    deps: Deps,
+    env: Env,
+    poll_id: String,
+    address: String,
+    vote: Vec<String>,
+    amounts: Vec<u64>,
+    funds: Vec<Coin>,
+) -> StdResult<Binary> {
+    let validated_address = deps.api.addr_validate(&address)?;
+
+    let poll = match may_load_poll(deps.storage, &poll_id)? {
+        Some(poll) => poll,
+        None => {
+            return to_binary(&SimulateVoteResponse {
+                accepted: false,
+                error: Some(ContractError::PollNotFound {}.to_string()),
+                weight: Uint128::zero(),
+                required_funds: vec![],
+                options: vec![],
+            })
+        }
+    };
+
+    let mut simulated = poll.clone();
+    let outcome = ensure_can_vote(
+        deps,
+        &env.block,
+        &poll_id,
+        &poll,
+        &validated_address,
+        &funds,
+    )
+    .and_then(|(power, ballot)| {
+        apply_vote(&mut simulated, ballot.as_ref(), &vote, &amounts, &power)?;
+        Ok(power.weight)
+    });
+
+    // How the tallies move would give away the ballot being replaced
+    let hidden = !ballots_visible(&poll, &env.block);
+    let mut res = match outcome {
+        Ok(weight) => SimulateVoteResponse {
+            accepted: true,
+            error: None,
+            weight,
+            // Coin votes lock what they are cast with
+            required_funds: match poll.strategy {
+                VotingStrategy::Coins {} => funds,
+                _ => vec![],
+            },
+            options: simulated.tallies(),
+        },
+        Err(err) => SimulateVoteResponse {
+            accepted: false,
+            error: Some(err.to_string()),
+            weight: Uint128::zero(),
+            required_funds: vec![],
+            options: poll.tallies(),
+        },
+    };
+    if hidden {
+        res.options = vec![];
+    }
+
+    to_binary(&res)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ballots::{poll_ballots, voter_ballots};
+    use crate::contract::{
+        ballot_commitment, create_poll, credit_claim, ensure_permitted, execute, instantiate,
+        load_poll, migrate, query, static_part, sudo, CONTRACT_NAME, MAX_DRAFTS, MAX_MEMO_LENGTH,
+        MAX_METADATA_LENGTH, MAX_OPTION_LABEL_LENGTH, MAX_SCORE, PRUNE_BATCH, REPUTATION_BATCH,
+    };
+    use crate::msg::{
+        AllBallotsForPollResponse, AllPollsResponse, AllVotesForAUserResponse,
+        ArchivedPollsResponse, ArchivedResultResponse, ChamberInput, ChamberResultsResponse,
+        ClaimsResponse, ConfigResponse, CreatePollResponse, CreatePollsResponse, CreditsResponse,
+        DraftsResponse, ExecuteMsg, ExecutionInput, ExportTemplateResponse, FeedResponse,
+        FinalizerInput, GatewaysResponse, InstantiateMsg, LeaderboardResponse, Member,
+        MembersResponse, MigrateMsg, ModeratorsResponse, MutedCreatorsResponse, NoisCallback,
+        NoisProxyExecuteMsg, PausedResponse, PermittedMsgsResponse, PollDepositResponse, PollInput,
+        PollResponse, PollSeriesResponse, PollSummariesResponse, PollSummary, PollVoter,
+        PollVotersResponse, QueryMsg, ReadOnlyResponse, ReceiveMsg, ReputationResponse,
+        ResolverInput, ResultsResponse, SchemaKind, SchemaResponse, SignedPollPayload,
+        SimulateVoteResponse, SpaceInfoResponse, StatsResponse, StorageStatsResponse,
+        StreakResponse, SudoMsg, TemplateExport, TemplatesResponse, TopVotersResponse,
+        TotalPowerAtHeightResponse, UpgradeInput, VerifyBallotsResponse, VerifyVoteProofResponse,
+        VoteProofResponse, VoteResponse, VotingPowerResponse, WatchersResponse, WinnerResponse,
+    };
+    use crate::scenarios::{
+        self, commit_reveal_vote, prize_poll, weighted_election, CommitRevealVote, PrizePoll,
+        WeightedElection,
+    };
+    use crate::state::{
+        Ballot, BasketDenom, BlockStamp, DataCapture, DataSource, DepositPricing, Label,
+        OptionDetails, PermittedMsg, Poll, PollOption, PollRules, PollStatus, PollTotals, Privacy,
+        Reputation, ReputationParams, SectionStats, SpaceInfo, SpaceLink, Stats, StorageStats,
+        Streak, TieBreak, Veto, VotingMethod, VotingStrategy, WeightCap, WeightCurve, BALLOTS,
+        COLLECTED_FEES, CONFIG, CREATOR_POLLS, LAST_CREATED, POLLS, POLL_TOTALS, POLL_VOTERS,
+        REPUTATION, STATS, STREAK_RANKING, TALLIES, VOTE_COUNTS, VOTE_RANKING,
+    };
+    use crate::tally::compute_tally;
+    use crate::ContractError;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{
+        attr, coin, coins, from_binary, to_binary, to_vec, Addr, BankMsg, Binary, Coin,
+        ContractResult, CosmosMsg, Decimal, Deps, Env, FullDelegation, StdResult, Storage,
+        SystemError, SystemResult, Uint128, WasmMsg, WasmQuery,
+    };
+    use cw2::{get_contract_version, set_contract_version};
+    use cw20::{
+        BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg, TokenInfoResponse,
+    };
+    use cw4::{Cw4QueryMsg, MemberResponse};
+    use cw_multi_test::App;
+    use cw_utils::{Duration, Expiration, PaymentError, Scheduled};
+    use k256::ecdsa::signature::Signer;
+    use k256::ecdsa::{Signature, SigningKey};
+    use sha2::{Digest, Sha256};
+
+    pub const ADDR1: &str = "addr1";
+    pub const ADDR2: &str = "addr2";
+
+    fn default_instantiate_msg() -> InstantiateMsg {
+        InstantiateMsg {
+            admin: None,
+            unique_subjects: None,
+            admin_pubkey: None,
+            poll_creation_fee: None,
+            poll_deposit: None,
+            treasury: None,
+            cw20_poll_creation_fee: None,
+            max_polls_per_creator: None,
+            poll_creation_cooldown: None,
+            min_options: None,
+            max_options: None,
+            max_question_len: None,
+            max_option_len: None,
+        }
+    }
+
+    fn vote_msg(poll_id: &str, vote: &str) -> ExecuteMsg {
+        ExecuteMsg::Vote {
+            poll_id: poll_id.to_string(),
+            vote: vec![vote.to_string()],
+            memo: None,
+            amounts: vec![],
+        }
+    }
+
+    fn create_poll_msg(poll_id: &str, question: &str, options: &[&str]) -> ExecuteMsg {
+        ExecuteMsg::CreatePoll {
+            poll_id: Some(poll_id.to_string()),
+            question: question.to_string(),
+            options: options.iter().map(|option| option.to_string()).collect(),
+            series_id: None,
+            strategy: None,
+            allowlist: vec![],
+            end_time: None,
+            subject: None,
+            chambers: vec![],
+            weight_curve: None,
+            weight_cap: None,
+            basket: vec![],
+            resolver: None,
+            vote_token: None,
+            correction_window: None,
+            min_support: None,
+            group: None,
+            max_selections: None,
+            upgrade: None,
+            method: None,
+            privacy: None,
+            commit_deadline: None,
+            quorum: None,
+            veto: None,
+            finalizer: None,
+            execution: None,
+            template: None,
+            threshold: None,
+            lottery: false,
+            tie_break: None,
+            labels: vec![],
+            max_ballots: None,
+            description: None,
+            image_url: None,
+            metadata: None,
+            option_details: vec![],
+            captures: vec![],
+            tags: vec![],
+        }
+    }
+
+    fn poll_input(poll_id: &str, question: &str, options: &[&str]) -> PollInput {
+        PollInput {
+            poll_id: Some(poll_id.to_string()),
+            question: question.to_string(),
+            options: options.iter().map(|option| option.to_string()).collect(),
+            series_id: None,
+            strategy: None,
+            allowlist: vec![],
+            end_time: None,
+            subject: None,
+            chambers: vec![],
+            weight_curve: None,
+            weight_cap: None,
+            basket: vec![],
+            resolver: None,
+            vote_token: None,
+            correction_window: None,
+            min_support: None,
+            group: None,
+            max_selections: None,
+            upgrade: None,
+            method: None,
+            privacy: None,
+            commit_deadline: None,
+            quorum: None,
+            veto: None,
+            finalizer: None,
+            execution: None,
+            template: None,
+            threshold: None,
+            lottery: false,
+            tie_break: None,
+            labels: vec![],
+            max_ballots: None,
+            description: None,
+            image_url: None,
+            metadata: None,
+            option_details: vec![],
+            captures: vec![],
+            tags: vec![],
+        }
+    }
+
+    #[test]
+    fn test_instantiate() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "instantiate"), attr("admin", ADDR1)]
+        )
+    }
+
+    #[test]
+    fn test_instantiate_with_admin() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = InstantiateMsg {
+            admin: Some(ADDR2.to_string()),
+            unique_subjects: None,
+            admin_pubkey: None,
+            poll_creation_fee: None,
+            poll_deposit: None,
+            treasury: None,
+            cw20_poll_creation_fee: None,
+            max_polls_per_creator: None,
+            poll_creation_cooldown: None,
+            min_options: None,
+            max_options: None,
+            max_question_len: None,
+            max_option_len: None,
+        };
+        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "instantiate"), attr("admin", ADDR2)]
+        );
+    }
+
+    #[test]
+    fn test_execute_create_poll_valid() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg(
+            "some_id",
+            "What's your favorite Cosmos coin?",
+            &["Cosmos Hub", "Juno", "Osmosis", "Terra"],
+        );
+
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        assert_eq!(res.attributes[0].key, "action");
+        assert_eq!(res.attributes[0].value, "execute_create_poll");
+    }
+
+    #[test]
+    fn test_exeucte_create_poll_invalid() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg(
+            "some_id",
+            "What's your favorite Cosmos coin?",
+            &[
+                "1",
+                "2",
+                "3",
+                "4",
+                "5",
+                "6",
+                "7",
+                "Cosmos Hub",
+                "Juno",
+                "Osmosis",
+                "Terra",
+            ],
+        );
+
+        let res = execute(deps.as_mut(), env, info, msg);
+
+        match res {
+            Err(ContractError::TooManyOptions { max }) => assert_eq!(max, 10),
+            _ => panic!("Must return too many options error"),
+        }
+    }
+
+    #[test]
+    fn test_execute_vote_valid() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg(
+            "random_id",
+            "What's your favorite Cosmos coin?",
+            &["Cosmos Hub", "Juno", "Osmosis"],
+        );
+
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+
+        let msg = vote_msg("random_id", "Juno");
+
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        assert_eq!(res.attributes[0].value, "execute_vote");
+        assert_eq!(res.attributes[1].value, "random_id");
+        assert_eq!(res.attributes[2].value, "Juno");
+
+        let msg = vote_msg("random_id", "Osmosis");
+
+        let mut env = env;
+        env.block.height += 10;
+        env.block.time = env.block.time.plus_seconds(60);
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        assert_eq!(res.attributes[0].value, "execute_vote");
+        assert_eq!(res.attributes[1].value, "random_id");
+        assert_eq!(res.attributes[2].value, "Osmosis");
+
+        // Changing the vote restamps the ballot
+        let msg = QueryMsg::Vote {
+            poll_id: "random_id".to_string(),
+            address: ADDR1.to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: VoteResponse = from_binary(&bin).unwrap();
+        let vote = res.vote.unwrap();
+        assert_eq!(vote.height, env.block.height);
+        assert_eq!(vote.time, env.block.time);
+    }
+
+    #[test]
+    fn test_execute_vote_invalid() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = vote_msg("random_id", "Juno");
+
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+
+        match res {
+            Err(ContractError::PollNotFound {}) => {}
+            _ => panic!("Must return poll not found error"),
+        }
+
+        let msg = create_poll_msg(
+            "random_id",
+            "What's your favorite Cosmos coin?",
+            &["Cosmos Hub", "Juno", "Osmosis"],
+        );
+
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        assert_eq!(res.attributes[2].key, "question");
+
+        let msg = vote_msg("random_id", "Terra");
+
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+
+        match res {
+            Err(ContractError::OptionNotFound {}) => {}
+            _ => panic!("Must return option not found error"),
+        }
+    }
+
+    #[test]
+    fn test_query_all_polls() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = QueryMsg::AllPolls {
+            start_after: None,
+            limit: None,
+        };
+        let binary = query(deps.as_ref(), env.clone(), msg).unwrap();
+
+        let res: AllPollsResponse = from_binary(&binary).unwrap();
+
+        assert_eq!(res.polls.len(), 0);
+
+        let msg = create_poll_msg(
+            "some_id_1",
+            "What is your favorite Cosmos coin?",
+            &["Cosmos Hub", "Juno", "Osmosis"],
+        );
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg(
+            "some_id_2",
+            "What's your favorite color?",
+            &["Red", "Green", "Blue"],
+        );
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = QueryMsg::AllPolls {
+            start_after: None,
+            limit: None,
+        };
+        let binary = query(deps.as_ref(), env.clone(), msg).unwrap();
+
+        let res: AllPollsResponse = from_binary(&binary).unwrap();
+
+        assert_eq!(res.polls.len(), 2);
+
+        let msg = QueryMsg::AllPolls {
+            start_after: None,
+            limit: Some(1),
+        };
+        let binary = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AllPollsResponse = from_binary(&binary).unwrap();
+        assert_eq!(res.polls.len(), 1);
+        assert_eq!(res.polls[0].poll_id, "some_id_1");
+
+        let msg = QueryMsg::AllPolls {
+            start_after: Some(res.polls[0].poll_id.clone()),
+            limit: Some(1),
+        };
+        let binary = query(deps.as_ref(), env, msg).unwrap();
+        let res: AllPollsResponse = from_binary(&binary).unwrap();
+        assert_eq!(res.polls.len(), 1);
+        assert_eq!(res.polls[0].poll_id, "some_id_2");
+    }
+
+    #[test]
+    fn test_query_poll() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg(
+            "some_id_1",
+            "What's your favorite Cosmos coin?",
+            &["Cosmos Hub", "Juno", "Osmosis"],
+        );
+
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id_1".to_string(),
+            option_offset: None,
+            option_limit: None,
+        };
+
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert!(res.poll.is_some());
+
+        let msg = QueryMsg::Poll {
+            poll_id: "id_does_not_exist".to_string(),
+            option_offset: None,
+            option_limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert!(res.poll.is_none());
+    }
+
+    #[test]
+    fn test_query_vote() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg(
+            "some_id_1",
+            "What's your favorite Cosmos coin?",
+            &["Cosmos Hub", "Juno", "Osmosis"],
+        );
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = vote_msg("some_id_1", "Juno");
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = QueryMsg::Vote {
+            poll_id: "some_id_1".to_string(),
+            address: ADDR1.to_string(),
+        };
+
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: VoteResponse = from_binary(&bin).unwrap();
+        let vote = res.vote.unwrap();
+        assert_eq!(vote.options, vec!["Juno"]);
+        assert_eq!(vote.height, env.block.height);
+        assert_eq!(vote.time, env.block.time);
+
+        let msg = QueryMsg::Vote {
+            poll_id: "some_id_2".to_string(),
+            address: ADDR2.to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: VoteResponse = from_binary(&bin).unwrap();
+        assert!(res.vote.is_none());
+    }
+
+    #[test]
+    fn test_query_config() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("config_address", &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg(
+            "some_id_1",
+            "What's your favorite Cosmos coin?",
+            &["Cosmos Hub", "Juno", "Osmosis"],
+        );
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = QueryMsg::GetConfig {};
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: ConfigResponse = from_binary(&bin).unwrap();
+
+        assert_eq!(res.config.admin, "config_address");
+    }
+
+    #[test]
+    fn test_migrate() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.0.1").unwrap();
+
+        let res = migrate(deps.as_mut(), env, MigrateMsg {}).unwrap();
+        assert_eq!(res.attributes[0], attr("action", "migrate"));
+
+        let version = get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn test_migrate_invalid() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", "0.1.0").unwrap();
+        let res = migrate(deps.as_mut(), env.clone(), MigrateMsg {});
+
+        match res {
+            Err(ContractError::CannotMigrate { .. }) => {}
+            _ => panic!("Must return cannot migrate error"),
+        }
+
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "99.0.0").unwrap();
+        let res = migrate(deps.as_mut(), env, MigrateMsg {});
+
+        match res {
+            Err(ContractError::CannotMigrateVersion { .. }) => {}
+            _ => panic!("Must return cannot migrate version error"),
+        }
+    }
+
+    #[test]
+    fn test_query_simulate_vote() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = QueryMsg::SimulateVote {
+            poll_id: "some_id_1".to_string(),
+            address: ADDR1.to_string(),
+            vote: vec!["Juno".to_string()],
+            funds: vec![],
+            amounts: vec![],
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: SimulateVoteResponse = from_binary(&bin).unwrap();
+        assert!(!res.accepted);
+        assert_eq!(res.error, Some(ContractError::PollNotFound {}.to_string()));
+
+        let msg = create_poll_msg(
+            "some_id_1",
+            "What's your favorite Cosmos coin?",
+            &["Cosmos Hub", "Juno", "Osmosis"],
+        );
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = vote_msg("some_id_1", "Juno");
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Switching the vote moves the weight rather than adding to it
+        let msg = QueryMsg::SimulateVote {
+            poll_id: "some_id_1".to_string(),
+            address: ADDR1.to_string(),
+            vote: vec!["Osmosis".to_string()],
+            funds: vec![],
+            amounts: vec![],
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: SimulateVoteResponse = from_binary(&bin).unwrap();
+        assert!(res.accepted);
+        assert_eq!(res.weight, Uint128::new(1));
+        assert_eq!(
+            res.options,
+            vec![
+                ("Cosmos Hub".to_string(), Uint128::new(0)),
+                ("Juno".to_string(), Uint128::new(0)),
+                ("Osmosis".to_string(), Uint128::new(1)),
+            ]
+        );
+
+        let msg = QueryMsg::SimulateVote {
+            poll_id: "some_id_1".to_string(),
+            address: ADDR2.to_string(),
+            vote: vec!["Terra".to_string()],
+            funds: vec![],
+            amounts: vec![],
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: SimulateVoteResponse = from_binary(&bin).unwrap();
+        assert!(!res.accepted);
+        assert_eq!(
+            res.error,
+            Some(ContractError::OptionNotFound {}.to_string())
+        );
+        assert_eq!(res.options[1], ("Juno".to_string(), Uint128::new(1)));
+
+        // Everything voting checks is checked, caps and phases included
+        let mut msg = create_poll_msg("full", "Ship it?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll { max_ballots, .. } = &mut msg {
+            *max_ballots = Some(1);
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = vote_msg("full", "Yes");
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let mut msg = create_poll_msg("sealed", "Ship it?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll {
+            commit_deadline, ..
+        } = &mut msg
+        {
+            *commit_deadline = Some(Expiration::AtHeight(env.block.height + 10));
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let mut msg = create_poll_msg("coins", "Ship it?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll {
+            strategy, basket, ..
+        } = &mut msg
+        {
+            *strategy = Some(VotingStrategy::Coins {});
+            *basket = vec![BasketDenom {
+                denom: "ujuno".to_string(),
+                multiplier: 1,
+            }];
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let simulate = |deps: Deps, poll_id: &str, funds: Vec<Coin>| {
+            let msg = QueryMsg::SimulateVote {
+                poll_id: poll_id.to_string(),
+                address: ADDR2.to_string(),
+                vote: vec!["Yes".to_string()],
+                funds,
+                amounts: vec![],
+            };
+            let bin = query(deps, mock_env(), msg).unwrap();
+            from_binary::<SimulateVoteResponse>(&bin).unwrap()
+        };
+        let res = simulate(deps.as_ref(), "full", vec![]);
+        assert_eq!(res.error, Some(ContractError::PollFull {}.to_string()));
+        let res = simulate(deps.as_ref(), "sealed", vec![]);
+        assert_eq!(
+            res.error,
+            Some(ContractError::CommitPhaseActive {}.to_string())
+        );
+        let res = simulate(deps.as_ref(), "some_id_1", coins(5, "ujuno"));
+        assert_eq!(
+            res.error,
+            Some(ContractError::UnexpectedFunds {}.to_string())
+        );
+        let res = simulate(deps.as_ref(), "coins", coins(5, "ujuno"));
+        assert!(res.accepted);
+        assert_eq!(res.weight, Uint128::new(5));
+        assert_eq!(res.required_funds, coins(5, "ujuno"));
+    }
+
+    #[test]
+    fn test_query_poll_series() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        for poll_id in ["round_2", "round_1", "unrelated"] {
+            let msg = ExecuteMsg::CreatePoll {
+                poll_id: Some(poll_id.to_string()),
+                question: "Who should lead the council?".to_string(),
+                options: vec!["Alice".to_string(), "Bob".to_string()],
+                series_id: if poll_id == "unrelated" {
+                    None
+                } else {
+                    Some("election".to_string())
+                },
+                strategy: None,
+                allowlist: vec![],
+                end_time: None,
+                subject: None,
+                chambers: vec![],
+                weight_curve: None,
+                weight_cap: None,
+                basket: vec![],
+                resolver: None,
+                vote_token: None,
+                correction_window: None,
+                min_support: None,
+                group: None,
+                max_selections: None,
+                upgrade: None,
+                method: None,
+                privacy: None,
+                commit_deadline: None,
+                quorum: None,
+                veto: None,
+                finalizer: None,
+                execution: None,
+                template: None,
+                threshold: None,
+                lottery: false,
+                tie_break: None,
+                labels: vec![],
+                max_ballots: None,
+                description: None,
+                image_url: None,
+                metadata: None,
+                option_details: vec![],
+                captures: vec![],
+                tags: vec![],
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+
+        let msg = QueryMsg::PollSeries {
+            series_id: "election".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollSeriesResponse = from_binary(&bin).unwrap();
+
+        // Polls are returned in the order they joined the series
+        let poll_ids: Vec<_> = res.polls.iter().map(|p| p.poll_id.as_str()).collect();
+        assert_eq!(poll_ids, vec!["round_2", "round_1"]);
+        assert_eq!(res.polls[0].poll.series_id, Some("election".to_string()));
+
+        let msg = QueryMsg::PollSeries {
+            series_id: "missing".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: PollSeriesResponse = from_binary(&bin).unwrap();
+        assert!(res.polls.is_empty());
+    }
+
+    #[test]
+    fn test_execute_claim() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Claim {},
+        );
+        match res {
+            Err(ContractError::NothingToClaim {}) => {}
+            _ => panic!("Must return nothing to claim error"),
+        }
+
+        let addr = Addr::unchecked(ADDR1);
+        credit_claim(deps.as_mut().storage, &addr, &coins(100, "ujuno")).unwrap();
+        credit_claim(deps.as_mut().storage, &addr, &coins(50, "ujuno")).unwrap();
+
+        let msg = QueryMsg::Claims {
+            address: ADDR1.to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: ClaimsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.claims, coins(150, "ujuno"));
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: ADDR1.to_string(),
+                amount: coins(150, "ujuno"),
+            })
+        );
+
+        let res = execute(deps.as_mut(), env, info, ExecuteMsg::Claim {});
+        match res {
+            Err(ContractError::NothingToClaim {}) => {}
+            _ => panic!("Must return nothing to claim error"),
+        }
+    }
+
+    #[test]
+    fn test_execute_vote_allowlist() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "Should we fund the proposal?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            series_id: None,
+            strategy: Some(VotingStrategy::Allowlist {}),
+            allowlist: vec![Member {
+                address: ADDR1.to_string(),
+                weight: 30,
+            }],
+            end_time: None,
+            subject: None,
+            chambers: vec![],
+            weight_curve: None,
+            weight_cap: None,
+            basket: vec![],
+            resolver: None,
+            vote_token: None,
+            correction_window: None,
+            min_support: None,
+            group: None,
+            max_selections: None,
+            upgrade: None,
+            method: None,
+            privacy: None,
+            commit_deadline: None,
+            quorum: None,
+            veto: None,
+            finalizer: None,
+            execution: None,
+            template: None,
+            threshold: None,
+            lottery: false,
+            tie_break: None,
+            labels: vec![],
+            max_ballots: None,
+            description: None,
+            image_url: None,
+            metadata: None,
+            option_details: vec![],
+            captures: vec![],
+            tags: vec![],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = vote_msg("some_id", "Yes");
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(res.attributes[3], attr("weight", "30"));
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::NotEligible {}) => {}
+            _ => panic!("Must return not eligible error"),
+        }
+
+        // Only the creator may upload more of the allowlist
+        let msg = ExecuteMsg::UpdateAllowlist {
+            poll_id: "some_id".to_string(),
+            add: vec![Member {
+                address: ADDR2.to_string(),
+                weight: 5,
+            }],
+            remove: vec![],
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = vote_msg("some_id", "No");
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        // Re-weighting a voter who already voted adjusts the tally in place
+        let msg = ExecuteMsg::UpdateAllowlist {
+            poll_id: "some_id".to_string(),
+            add: vec![Member {
+                address: ADDR1.to_string(),
+                weight: 10,
+            }],
+            remove: vec![ADDR2.to_string()],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+            option_offset: None,
+            option_limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.poll.unwrap().tallies(),
+            vec![
+                ("Yes".to_string(), Uint128::new(10)),
+                ("No".to_string(), Uint128::new(0))
+            ]
+        );
+
+        let msg = QueryMsg::Vote {
+            poll_id: "some_id".to_string(),
+            address: ADDR2.to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: VoteResponse = from_binary(&bin).unwrap();
+        assert!(res.vote.is_none());
+    }
+
+    #[test]
+    fn test_execute_create_poll_allowlist_not_enabled() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "Should we fund the proposal?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            series_id: None,
+            strategy: None,
+            allowlist: vec![Member {
+                address: ADDR1.to_string(),
+                weight: 30,
+            }],
+            end_time: None,
+            subject: None,
+            chambers: vec![],
+            weight_curve: None,
+            weight_cap: None,
+            basket: vec![],
+            resolver: None,
+            vote_token: None,
+            correction_window: None,
+            min_support: None,
+            group: None,
+            max_selections: None,
+            upgrade: None,
+            method: None,
+            privacy: None,
+            commit_deadline: None,
+            quorum: None,
+            veto: None,
+            finalizer: None,
+            execution: None,
+            template: None,
+            threshold: None,
+            lottery: false,
+            tie_break: None,
+            labels: vec![],
+            max_ballots: None,
+            description: None,
+            image_url: None,
+            metadata: None,
+            option_details: vec![],
+            captures: vec![],
+            tags: vec![],
+        };
+        let res = execute(deps.as_mut(), env, info, msg);
+        match res {
+            Err(ContractError::AllowlistNotEnabled {}) => {}
+            _ => panic!("Must return allowlist not enabled error"),
+        }
+    }
+
+    #[test]
+    fn test_execute_vote_memo() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg("some_id", "Adopt the new charter?", &["Yes", "No"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec!["Yes".to_string()],
+            memo: Some("x".repeat(MAX_MEMO_LENGTH + 1)),
+            amounts: vec![],
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::MemoTooLong { .. }) => {}
+            _ => panic!("Must return memo too long error"),
+        }
+
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec!["Yes".to_string()],
+            memo: Some("It addresses the treasury concerns".to_string()),
+            amounts: vec![],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = QueryMsg::Vote {
+            poll_id: "some_id".to_string(),
+            address: ADDR1.to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: VoteResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.vote.unwrap().memo,
+            Some("It addresses the treasury concerns".to_string())
+        );
+    }
+
+    #[test]
+    fn test_execute_watch_poll() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::WatchPoll {
+            poll_id: "some_id".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::PollNotFound {}) => {}
+            _ => panic!("Must return poll not found error"),
+        }
+
+        let create = create_poll_msg("some_id", "Adopt the new charter?", &["Yes", "No"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), create).unwrap();
+
+        // Watching twice is idempotent
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        let query_msg = QueryMsg::Watchers {
+            poll_id: "some_id".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), query_msg.clone()).unwrap();
+        let res: WatchersResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.count, 2);
+        assert_eq!(
+            res.watchers,
+            vec![Addr::unchecked(ADDR1), Addr::unchecked(ADDR2)]
+        );
+
+        let msg = ExecuteMsg::UnwatchPoll {
+            poll_id: "some_id".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let bin = query(deps.as_ref(), env, query_msg).unwrap();
+        let res: WatchersResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.count, 1);
+        assert_eq!(res.watchers, vec![Addr::unchecked(ADDR2)]);
+    }
+
+    #[test]
+    fn test_compute_tally_matches_stored_tallies() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg("some_id", "Adopt the new charter?", &["Yes", "No"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "No"),
+        )
+        .unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info, vote_msg("some_id", "Yes")).unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "Yes"),
+        )
+        .unwrap();
+
+        let ballots: Vec<Ballot> = [ADDR1, ADDR2]
+            .iter()
+            .map(|addr| {
+                BALLOTS
+                    .load(&deps.storage, (Addr::unchecked(*addr), "some_id"))
+                    .unwrap()
+            })
+            .collect();
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        let options: Vec<String> = poll.options.iter().map(|o| o.name.clone()).collect();
+
+        let result = compute_tally(&options, &ballots, &poll.strategy);
+        assert_eq!(result.options, poll.tallies());
+        assert_eq!(result.total_weight, Uint128::new(2));
+        assert_eq!(result.ballots, 2);
+    }
+
+    #[test]
+    fn test_execute_delete_poll() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg("some_id", "Adopt the new charter?", &["Yes", "No"]);
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+        let msg = create_poll_msg("other_id", "Adopt the old charter?", &["Yes", "No"]);
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "No"),
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("other_id", "No"),
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "Yes"),
+        )
+        .unwrap();
+
+        let msg = ExecuteMsg::DeletePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+
+        // The admin may delete polls created by others
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(res.attributes[2], attr("ballots_removed", "2"));
+
+        assert!(!POLLS.has(&deps.storage, "some_id"));
+        assert!(!BALLOTS.has(&deps.storage, (Addr::unchecked(ADDR1), "some_id")));
+        assert!(!BALLOTS.has(&deps.storage, (Addr::unchecked(ADDR2), "some_id")));
+        assert!(BALLOTS.has(&deps.storage, (Addr::unchecked(ADDR1), "other_id")));
+
+        let res = execute(deps.as_mut(), env, info, msg);
+        match res {
+            Err(ContractError::PollNotFound {}) => {}
+            _ => panic!("Must return poll not found error"),
+        }
+    }
+
+    #[test]
+    fn test_prune_deleted_poll() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg("some_id", "Adopt the new charter?", &["Yes", "No"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let voters: Vec<String> = (0..=PRUNE_BATCH)
+            .map(|i| format!("voter{:03}", i))
+            .collect();
+        for voter in &voters {
+            let msg = vote_msg("some_id", "Yes");
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+
+        let prune = ExecuteMsg::PrunePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), prune.clone());
+        match res {
+            Err(ContractError::NothingToPrune {}) => {}
+            _ => panic!("Must return nothing to prune error"),
+        }
+
+        // Deleting removes the poll and a first batch of its ballots
+        let msg = ExecuteMsg::DeletePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert!(res
+            .attributes
+            .contains(&attr("ballots_removed", PRUNE_BATCH.to_string())));
+        assert!(res.attributes.contains(&attr("prune_pending", "true")));
+        assert!(!POLLS.has(&deps.storage, "some_id"));
+        let last = Addr::unchecked(&voters[PRUNE_BATCH]);
+        assert!(BALLOTS.has(&deps.storage, (last.clone(), "some_id")));
+
+        // The id stays taken and the leftover ballot is hidden until pruned
+        let msg = create_poll_msg("some_id", "Adopt it again?", &["Yes", "No"]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::PollAlreadyExists { poll_id }) => assert_eq!(poll_id, "some_id"),
+            _ => panic!("Must return poll already exists error"),
+        }
+        let msg = QueryMsg::AllVotesForAUser {
+            user_address: last.to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AllVotesForAUserResponse = from_binary(&bin).unwrap();
+        assert!(res.votes.is_empty());
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            prune.clone(),
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("ballots_removed", "1")));
+        assert!(res.attributes.contains(&attr("prune_pending", "false")));
+        assert!(!BALLOTS.has(&deps.storage, (last, "some_id")));
+
+        let res = execute(deps.as_mut(), env, info, prune);
+        match res {
+            Err(ContractError::NothingToPrune {}) => {}
+            _ => panic!("Must return nothing to prune error"),
+        }
+    }
+
+    #[test]
+    fn test_execute_revoke_vote() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg("some_id", "Adopt the new charter?", &["Yes", "No"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::RevokeVote {
+            poll_id: "some_id".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::BallotNotFound {}) => {}
+            _ => panic!("Must return ballot not found error"),
+        }
+
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "Yes"),
+        )
+        .unwrap();
+
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "execute_revoke_vote"),
+                attr("poll_id", "some_id"),
+                attr("voter", ADDR1),
+                attr("revoked_vote", "Yes"),
+                attr("weight", "1"),
+            ]
+        );
+
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(
+            poll.tallies(),
+            vec![
+                ("Yes".to_string(), Uint128::new(0)),
+                ("No".to_string(), Uint128::new(0))
+            ]
+        );
+        assert!(!BALLOTS.has(&deps.storage, (Addr::unchecked(ADDR1), "some_id")));
+    }
+
+    #[test]
+    fn test_execute_retire_option() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg(
+            "some_id",
+            "Who should lead the council?",
+            &["Alice", "Bob", "Carol"],
+        );
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "Bob"),
+        )
+        .unwrap();
+
+        let msg = ExecuteMsg::RetireOption {
+            poll_id: "some_id".to_string(),
+            option: "Bob".to_string(),
+            reason: Some("Candidate withdrew".to_string()),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(res.attributes[3], attr("tally_at_retirement", "1"));
+
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::OptionRetired {}) => {}
+            _ => panic!("Must return option retired error"),
+        }
+
+        let res = execute(deps.as_mut(), env.clone(), info, vote_msg("some_id", "Bob"));
+        match res {
+            Err(ContractError::OptionRetired {}) => {}
+            _ => panic!("Must return option retired error"),
+        }
+
+        // Ballots are flagged as they are read rather than rewritten
+        let msg = QueryMsg::Vote {
+            address: ADDR2.to_string(),
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg.clone()).unwrap();
+        let res: VoteResponse = from_binary(&bin).unwrap();
+        assert!(res.vote.unwrap().option_retired);
+        let ballot = BALLOTS
+            .load(&deps.storage, (Addr::unchecked(ADDR2), "some_id"))
+            .unwrap();
+        assert!(!ballot.option_retired);
+
+        // Affected voters may move their vote to a remaining option
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "Carol"),
+        )
+        .unwrap();
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: VoteResponse = from_binary(&bin).unwrap();
+        assert!(!res.vote.unwrap().option_retired);
+
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(poll.tallies()[1], ("Bob".to_string(), Uint128::new(0)));
+        assert_eq!(poll.tallies()[2], ("Carol".to_string(), Uint128::new(1)));
+        assert_eq!(poll.retired_options[0].tally_at_retirement, Uint128::new(1));
+        assert_eq!(
+            poll.retired_options[0].reason,
+            Some("Candidate withdrew".to_string())
+        );
+    }
+
+    #[test]
+    fn test_execute_vote_registry() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateMembers {
+            add: vec![Member {
+                address: ADDR2.to_string(),
+                weight: 7,
+            }],
+            remove: vec![],
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = QueryMsg::Members {
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: MembersResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.members,
+            vec![Member {
+                address: ADDR2.to_string(),
+                weight: 7,
+            }]
+        );
+
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "Should we fund the proposal?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            series_id: None,
+            strategy: Some(VotingStrategy::Registry {}),
+            allowlist: vec![],
+            end_time: None,
+            subject: None,
+            chambers: vec![],
+            weight_curve: None,
+            weight_cap: None,
+            basket: vec![],
+            resolver: None,
+            vote_token: None,
+            correction_window: None,
+            min_support: None,
+            group: None,
+            max_selections: None,
+            upgrade: None,
+            method: None,
+            privacy: None,
+            commit_deadline: None,
+            quorum: None,
+            veto: None,
+            finalizer: None,
+            execution: None,
+            template: None,
+            threshold: None,
+            lottery: false,
+            tie_break: None,
+            labels: vec![],
+            max_ballots: None,
+            description: None,
+            image_url: None,
+            metadata: None,
+            option_details: vec![],
+            captures: vec![],
+            tags: vec![],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let res = execute(deps.as_mut(), env.clone(), info, vote_msg("some_id", "Yes"));
+        match res {
+            Err(ContractError::NotEligible {}) => {}
+            _ => panic!("Must return not eligible error"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "Yes"),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[3], attr("weight", "7"));
+    }
+
+    #[test]
+    fn test_execute_vote_after_end_time() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = create_poll_msg("some_id", "Adopt the new charter?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll { end_time, .. } = &mut msg {
+            *end_time = Some(Expiration::AtHeight(env.block.height));
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidEndTime {}) => {}
+            _ => panic!("Must return invalid end time error"),
+        }
+
+        if let ExecuteMsg::CreatePoll { end_time, .. } = &mut msg {
+            *end_time = Some(Expiration::AtHeight(env.block.height + 10));
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "Yes"),
+        )
+        .unwrap();
+
+        let query_msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+            option_offset: None,
+            option_limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), query_msg.clone()).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert!(res.is_open);
+
+        env.block.height += 10;
+
+        let bin = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert!(!res.is_open);
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "No"),
+        );
+        match res {
+            Err(ContractError::PollClosed {}) => {}
+            _ => panic!("Must return poll closed error"),
+        }
+
+        let msg = ExecuteMsg::RevokeVote {
+            poll_id: "some_id".to_string(),
+        };
+        let res = execute(deps.as_mut(), env, info, msg);
+        match res {
+            Err(ContractError::PollClosed {}) => {}
+            _ => panic!("Must return poll closed error"),
+        }
+    }
+
+    #[test]
+    fn test_execute_annul_poll() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg("some_id", "Adopt the new charter?", &["Yes", "No"]);
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::AnnulPoll {
+            poll_id: "some_id".to_string(),
+            reason: "Ballot stuffing".to_string(),
+        };
+        // Not even the creator may annul a poll
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(poll.status, PollStatus::Annulled);
+        assert_eq!(poll.annul_reason, Some("Ballot stuffing".to_string()));
+
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::PollAnnulled {}) => {}
+            _ => panic!("Must return poll annulled error"),
+        }
+
+        let res = execute(deps.as_mut(), env, info, vote_msg("some_id", "Yes"));
+        match res {
+            Err(ContractError::PollClosed {}) => {}
+            _ => panic!("Must return poll closed error"),
+        }
+    }
+
+    #[test]
+    fn test_execute_annul_poll_unwinds() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let mut msg = default_instantiate_msg();
+        msg.poll_deposit = Some(coin(100, "ujuno"));
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = create_poll_msg("some_id", "Ship it?", &["Yes", "No", "Veto"]);
+        if let ExecuteMsg::CreatePoll {
+            end_time,
+            strategy,
+            basket,
+            veto,
+            ..
+        } = &mut msg
+        {
+            *end_time = Some(Expiration::AtHeight(env.block.height + 10));
+            *strategy = Some(VotingStrategy::Coins {});
+            *basket = vec![BasketDenom {
+                denom: "uatom".to_string(),
+                multiplier: 1,
+            }];
+            *veto = Some(Veto {
+                option: "Veto".to_string(),
+                threshold: Decimal::percent(33),
+            });
+        }
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &coins(100, "ujuno")),
+            msg,
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr3", &coins(40, "uatom")),
+            vote_msg("some_id", "Veto"),
+        )
+        .unwrap();
+
+        let mut msg = create_poll_msg("other_id", "Ship it too?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll { end_time, .. } = &mut msg {
+            *end_time = Some(Expiration::AtHeight(env.block.height + 10));
+        }
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &coins(100, "ujuno")),
+            msg,
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr3", &[]),
+            vote_msg("other_id", "Yes"),
+        )
+        .unwrap();
+
+        env.block.height += 10;
+        let msg = ExecuteMsg::FinalizePoll {
+            poll_id: "other_id".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Though vetoed, the annulled poll's deposit is refunded rather than
+        // forfeited, and its votes' coins can be released
+        let annul = |poll_id: &str| ExecuteMsg::AnnulPoll {
+            poll_id: poll_id.to_string(),
+            reason: "Ballot stuffing".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), annul("some_id")).unwrap();
+
+        let claims = |deps: Deps, address: &str| -> Vec<Coin> {
+            let msg = QueryMsg::Claims {
+                address: address.to_string(),
+            };
+            let bin = query(deps, mock_env(), msg).unwrap();
+            from_binary::<ClaimsResponse>(&bin).unwrap().claims
+        };
+        assert_eq!(claims(deps.as_ref(), ADDR2), coins(100, "ujuno"));
+        assert_eq!(claims(deps.as_ref(), "addr3"), vec![]);
+        let release = ExecuteMsg::ReleaseEscrow {
+            poll_id: "some_id".to_string(),
+        };
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr3", &[]),
+            release.clone(),
+        )
+        .unwrap();
+        assert_eq!(claims(deps.as_ref(), "addr3"), coins(40, "uatom"));
+        let res = execute(deps.as_mut(), env.clone(), mock_info("addr3", &[]), release);
+        match res {
+            Err(ContractError::NothingToClaim {}) => {}
+            _ => panic!("Must return nothing to claim error"),
+        }
+
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(poll.deposit, None);
+
+        // A finalized poll no longer reports its winner
+        let poll = load_poll(&deps.storage, "other_id").unwrap();
+        assert_eq!(poll.result.unwrap().winner, Some("Yes".to_string()));
+        let _res = execute(deps.as_mut(), env.clone(), info, annul("other_id")).unwrap();
+        let poll = load_poll(&deps.storage, "other_id").unwrap();
+        let result = poll.result.unwrap();
+        assert_eq!(result.status, PollStatus::Annulled);
+        assert_eq!(result.winner, None);
+
+        let msg = QueryMsg::Winner {
+            poll_id: "other_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: WinnerResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.option, None);
+        assert!(!res.decided);
+    }
+
+    #[test]
+    fn test_execute_close_poll() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg("some_id", "Adopt the new charter?", &["Yes", "No"]);
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+        let msg = create_poll_msg("other_id", "Adopt the old charter?", &["Yes", "No"]);
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[0], attr("action", "execute_close_poll"));
+
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::PollNotOpen {}) => {}
+            _ => panic!("Must return poll not open error"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "Yes"),
+        );
+        match res {
+            Err(ContractError::PollClosed {}) => {}
+            _ => panic!("Must return poll closed error"),
+        }
+
+        // The admin may cancel polls they did not create
+        let msg = ExecuteMsg::CancelPoll {
+            poll_id: "other_id".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+            option_offset: None,
+            option_limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.status, Some(PollStatus::Closed));
+        assert!(!res.is_open);
+
+        let msg = QueryMsg::Poll {
+            poll_id: "other_id".to_string(),
+            option_offset: None,
+            option_limit: None,
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.status, Some(PollStatus::Cancelled));
+    }
+
+    #[test]
+    fn test_execute_create_poll_unique_subject() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = create_poll_msg("some_id", "Raise the community tax?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll { subject, .. } = &mut msg {
+            *subject = Some("community_tax".to_string());
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+
+        if let ExecuteMsg::CreatePoll { poll_id, .. } = &mut msg {
+            *poll_id = Some("other_id".to_string());
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::SubjectInUse { poll_id }) => assert_eq!(poll_id, "some_id"),
+            _ => panic!("Must return subject in use error"),
+        }
+
+        // Once the first poll closes the subject is free again
+        let close = ExecuteMsg::ClosePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), close).unwrap();
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+    }
+
+    #[test]
+    fn test_execute_create_poll_subjects_not_unique() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = InstantiateMsg {
+            admin: None,
+            unique_subjects: Some(false),
+            admin_pubkey: None,
+            poll_creation_fee: None,
+            poll_deposit: None,
+            treasury: None,
+            cw20_poll_creation_fee: None,
+            max_polls_per_creator: None,
+            poll_creation_cooldown: None,
+            min_options: None,
+            max_options: None,
+            max_question_len: None,
+            max_option_len: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        for id in ["some_id", "other_id"] {
+            let mut msg = create_poll_msg(id, "Raise the community tax?", &["Yes", "No"]);
+            if let ExecuteMsg::CreatePoll { subject, .. } = &mut msg {
+                *subject = Some("community_tax".to_string());
+            }
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_query_all_votes_for_a_user() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        for poll_id in ["poll_1", "poll_2", "poll_3"] {
+            let msg = create_poll_msg(poll_id, "Adopt the new charter?", &["Yes", "No"]);
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+            let _res = execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                vote_msg(poll_id, "Yes"),
+            )
+            .unwrap();
+        }
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            vote_msg("poll_2", "No"),
+        )
+        .unwrap();
+
+        let msg = QueryMsg::AllVotesForAUser {
+            user_address: ADDR1.to_string(),
+            start_after: None,
+            limit: Some(2),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AllVotesForAUserResponse = from_binary(&bin).unwrap();
+        let poll_ids: Vec<_> = res.votes.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(poll_ids, vec!["poll_1", "poll_2"]);
+        assert_eq!(res.votes[0].1.options, vec!["Yes"]);
+
+        let msg = QueryMsg::AllVotesForAUser {
+            user_address: ADDR1.to_string(),
+            start_after: Some("poll_2".to_string()),
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AllVotesForAUserResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.votes.len(), 1);
+        assert_eq!(res.votes[0].0, "poll_3");
+
+        let msg = QueryMsg::AllVotesForAUser {
+            user_address: ADDR2.to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: AllVotesForAUserResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.votes.len(), 1);
+        assert_eq!(res.votes[0].1.options, vec!["No"]);
+    }
+
+    #[test]
+    fn test_execute_archive_poll() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg("some_id", "Adopt the new charter?", &["Yes", "No"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "Yes"),
+        )
+        .unwrap();
+
+        let msg = ExecuteMsg::ArchivePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::PollStillOpen {}) => {}
+            _ => panic!("Must return poll still open error"),
+        }
+
+        let close = ExecuteMsg::ClosePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), close).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Active storage is reclaimed...
+        assert!(!POLLS.has(&deps.storage, "some_id"));
+        assert!(!BALLOTS.has(&deps.storage, (Addr::unchecked(ADDR1), "some_id")));
+
+        // ...while the result stays queryable
+        let msg = QueryMsg::ArchivedResult {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: ArchivedResultResponse = from_binary(&bin).unwrap();
+        let result = res.result.unwrap();
+        assert_eq!(result.status, PollStatus::Closed);
+        assert_eq!(result.ballots, 1);
+        assert_eq!(result.options[0], ("Yes".to_string(), Uint128::new(1)));
+
+        let msg = QueryMsg::ArchivedPolls {
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: ArchivedPollsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.polls.len(), 1);
+        assert_eq!(res.polls[0].0, "some_id");
+    }
+
+    #[test]
+    fn test_execute_create_poll_auto_id() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // An explicit id that collides with the next generated one is skipped over
+        let msg = create_poll_msg("1", "Adopt the new charter?", &["Yes", "No"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = create_poll_msg("", "Adopt the old charter?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll { poll_id, .. } = &mut msg {
+            *poll_id = None;
+        }
+
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(res.attributes[1], attr("poll_id", "2"));
+        let data: CreatePollResponse = from_binary(&res.data.unwrap()).unwrap();
+        assert_eq!(data.poll_id, "2");
+
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(res.attributes[1], attr("poll_id", "3"));
+
+        let poll = load_poll(&deps.storage, "2").unwrap();
+        assert_eq!(poll.question, "Adopt the old charter?");
+    }
+
+    #[test]
+    fn test_execute_vote_chambers() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // ADDR1 sits in both chambers, ADDR2 only among the builders
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: Some("some_id".to_string()),
+            question: "Ratify the upgrade?".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            series_id: None,
+            strategy: Some(VotingStrategy::Chambers {}),
+            allowlist: vec![],
+            end_time: None,
+            subject: None,
+            chambers: vec![
+                ChamberInput {
+                    name: "holders".to_string(),
+                    threshold: Decimal::percent(50),
+                    members: vec![Member {
+                        address: ADDR1.to_string(),
+                        weight: 100,
+                    }],
+                },
+                ChamberInput {
+                    name: "builders".to_string(),
+                    threshold: Decimal::percent(67),
+                    members: vec![
+                        Member {
+                            address: ADDR1.to_string(),
+                            weight: 1,
+                        },
+                        Member {
+                            address: ADDR2.to_string(),
+                            weight: 1,
+                        },
+                    ],
+                },
+            ],
+            weight_curve: None,
+            weight_cap: None,
+            basket: vec![],
+            resolver: None,
+            vote_token: None,
+            correction_window: None,
+            min_support: None,
+            group: None,
+            max_selections: None,
+            upgrade: None,
+            method: None,
+            privacy: None,
+            commit_deadline: None,
+            quorum: None,
+            veto: None,
+            finalizer: None,
+            execution: None,
+            template: None,
+            threshold: None,
+            lottery: false,
+            tie_break: None,
+            labels: vec![],
+            max_ballots: None,
+            description: None,
+            image_url: None,
+            metadata: None,
+            option_details: vec![],
+            captures: vec![],
+            tags: vec![],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("outsider", &[]),
+            vote_msg("some_id", "Yes"),
+        );
+        match res {
+            Err(ContractError::NotEligible {}) => {}
+            _ => panic!("Must return not eligible error"),
+        }
+
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "Yes"),
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "No"),
+        )
+        .unwrap();
+
+        let msg = QueryMsg::ChamberResults {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg.clone()).unwrap();
+        let res: ChamberResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.chambers[0].options,
+            vec![("Yes".to_string(), 100), ("No".to_string(), 0)]
+        );
+        assert_eq!(res.chambers[0].passing_options, vec!["Yes".to_string()]);
+        assert_eq!(res.chambers[1].total_weight, 2);
+        assert!(res.chambers[1].passing_options.is_empty());
+        assert!(res.passing_options.is_empty());
+
+        // Once the builder comes around, "Yes" clears both chambers
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "Yes"),
+        )
+        .unwrap();
+
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: ChamberResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.chambers[1].options,
+            vec![("Yes".to_string(), 2), ("No".to_string(), 0)]
+        );
+        assert_eq!(res.passing_options, vec!["Yes".to_string()]);
+
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(
+            poll.tallies(),
+            vec![
+                ("Yes".to_string(), Uint128::new(2)),
+                ("No".to_string(), Uint128::new(0))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_execute_create_poll_signed() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("relayer", &coins(100, "ujuno"));
+
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]).unwrap();
+        let msg = InstantiateMsg {
+            admin: None,
+            unique_subjects: None,
+            admin_pubkey: Some(Binary::from(
+                signing_key.verifying_key().to_bytes().as_slice(),
+            )),
+            poll_creation_fee: None,
+            poll_deposit: Some(coin(100, "ujuno")),
+            treasury: None,
+            cw20_poll_creation_fee: None,
+            max_polls_per_creator: None,
+            poll_creation_cooldown: None,
+            min_options: None,
+            max_options: None,
+            max_question_len: None,
+            max_option_len: None,
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info(ADDR1, &[]), msg).unwrap();
+
+        let payload = to_vec(&SignedPollPayload {
+            creator: ADDR2.to_string(),
+            nonce: 1,
+            poll: PollInput {
+                poll_id: Some("some_id".to_string()),
+                question: "What's your favourite Cosmos coin?".to_string(),
+                options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+                series_id: None,
+                strategy: None,
+                allowlist: vec![],
+                end_time: None,
+                subject: None,
+                chambers: vec![],
+                weight_curve: None,
+                weight_cap: None,
+                basket: vec![],
+                resolver: None,
+                vote_token: None,
+                correction_window: None,
+                min_support: None,
+                group: None,
+                max_selections: None,
+                upgrade: None,
+                method: None,
+                privacy: None,
+                commit_deadline: None,
+                quorum: None,
+                veto: None,
+                finalizer: None,
+                execution: None,
+                template: None,
+                threshold: None,
+                lottery: false,
+                tie_break: None,
+                labels: vec![],
+                max_ballots: None,
+                description: None,
+                image_url: None,
+                metadata: None,
+                option_details: vec![],
+                captures: vec![],
+                tags: vec![],
+            },
+        })
+        .unwrap();
+        let signature: Signature = signing_key.sign(&payload);
+        let msg = ExecuteMsg::CreatePollSigned {
+            payload: Binary::from(payload.clone()),
+            signature: Binary::from(signature.as_ref()),
+        };
+
+        // Tampering with the payload invalidates the signature
+        let mut tampered = payload.clone();
+        tampered[0] = b' ';
+        let bad_msg = ExecuteMsg::CreatePollSigned {
+            payload: Binary::from(tampered),
+            signature: Binary::from(signature.as_ref()),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), bad_msg);
+        match res {
+            Err(ContractError::InvalidSignature {}) => {}
+            _ => panic!("Must return invalid signature error"),
+        }
+
+        // The submitter pays the deposit, held for the creator
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("relayer", &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::InsufficientDeposit { .. }) => {}
+            _ => panic!("Must return insufficient deposit error"),
+        }
+
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(poll.creator, Addr::unchecked(ADDR2));
+        assert_eq!(poll.deposit, Some(coin(100, "ujuno")));
+
+        // The same payload cannot be replayed
+        let res = execute(deps.as_mut(), env, info, msg);
+        match res {
+            Err(ContractError::NonceUsed { nonce: 1 }) => {}
+            _ => panic!("Must return nonce used error"),
+        }
+    }
+
+    #[test]
+    fn test_execute_create_poll_signed_disabled() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePollSigned {
+            payload: Binary::from(b"{}".as_slice()),
+            signature: Binary::from([0u8; 64].as_slice()),
+        };
+        let res = execute(deps.as_mut(), env, info, msg);
+        match res {
+            Err(ContractError::SignedCreationDisabled {}) => {}
+            _ => panic!("Must return signed creation disabled error"),
+        }
+    }
+
+    #[test]
+    fn test_execute_create_poll_already_exists() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg(
+            "some_id",
+            "What's your favourite Cosmos coin?",
+            &["Cosmos Hub", "Juno", "Osmosis"],
+        );
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "Juno"),
+        )
+        .unwrap();
+
+        let msg = create_poll_msg("some_id", "Overwrite?", &["Yes", "No"]);
+        let res = execute(deps.as_mut(), env, mock_info(ADDR2, &[]), msg);
+        match res {
+            Err(ContractError::PollAlreadyExists { poll_id }) => assert_eq!(poll_id, "some_id"),
+            _ => panic!("Must return poll already exists error"),
+        }
+
+        // The original poll and its tally are untouched
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(poll.creator, Addr::unchecked(ADDR1));
+        assert_eq!(poll.tallies()[1], ("Juno".to_string(), Uint128::new(1)));
+    }
+
+    #[test]
+    fn test_poll_ballots_stream() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg(
+            "some_id",
+            "What's your favourite Cosmos coin?",
+            &["Cosmos Hub", "Juno"],
+        );
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = create_poll_msg("other_id", "Best validator?", &["A", "B"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &[]),
+            vote_msg("some_id", "Juno"),
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "Cosmos Hub"),
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(ADDR1, &[]),
+            vote_msg("other_id", "A"),
+        )
+        .unwrap();
+
+        let ballots = poll_ballots(&deps.storage, "some_id", None)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(ballots.len(), 2);
+        assert_eq!(ballots[0].0, Addr::unchecked(ADDR1));
+        assert_eq!(ballots[0].1.options, vec!["Juno"]);
+        assert_eq!(ballots[1].1.options, vec!["Cosmos Hub"]);
+
+        // Resuming after the first voter yields the rest
+        let first = Addr::unchecked(ADDR1);
+        let rest = poll_ballots(&deps.storage, "some_id", Some(&first))
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].0, Addr::unchecked(ADDR2));
+
+        let polls: Vec<String> = voter_ballots(&deps.storage, &first, None)
+            .map(|item| item.map(|(poll_id, _)| poll_id))
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(polls, vec!["other_id".to_string(), "some_id".to_string()]);
+    }
+
+    #[test]
+    fn test_execute_update_config() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateConfig {
+            unique_subjects: Some(false),
+            admin_pubkey: None,
+            poll_creation_fee: None,
+            poll_deposit: None,
+            treasury: None,
+            cw20_poll_creation_fee: None,
+            max_polls_per_creator: None,
+            poll_creation_cooldown: None,
+            min_options: None,
+            max_options: None,
+            max_question_len: None,
+            max_option_len: None,
+        };
+
+        // Only the admin can update the config
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "execute_update_config")]
+        );
+
+        let bin = query(deps.as_ref(), env, QueryMsg::GetConfig {}).unwrap();
+        let res: ConfigResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.config.admin, Addr::unchecked(ADDR1));
+        assert!(!res.config.unique_subjects);
+    }
+
+    #[test]
+    fn test_execute_transfer_admin() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            ExecuteMsg::AcceptAdmin {},
+        );
+        match res {
+            Err(ContractError::NoPendingAdmin {}) => {}
+            _ => panic!("Must return no pending admin error"),
+        }
+
+        // Only the admin can propose a successor
+        let msg = ExecuteMsg::ProposeAdmin {
+            new_admin: ADDR2.to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Nothing changes until the proposed admin accepts
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::GetConfig {}).unwrap();
+        let res: ConfigResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.config.admin, Addr::unchecked(ADDR1));
+        assert_eq!(res.pending_admin, Some(Addr::unchecked(ADDR2)));
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            ExecuteMsg::AcceptAdmin {},
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            ExecuteMsg::AcceptAdmin {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "execute_accept_admin"), attr("admin", ADDR2)]
+        );
+
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::GetConfig {}).unwrap();
+        let res: ConfigResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.config.admin, Addr::unchecked(ADDR2));
+        assert_eq!(res.pending_admin, None);
+
+        // The previous admin has lost access
+        let msg = ExecuteMsg::ProposeAdmin {
+            new_admin: ADDR1.to_string(),
+        };
+        let res = execute(deps.as_mut(), env, info, msg);
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+    }
+
+    #[test]
+    fn test_execute_vote_weight_cap_and_curve() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = create_poll_msg("some_id", "Fund the proposal?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll { weight_cap, .. } = &mut msg {
+            *weight_cap = Some(WeightCap::Absolute { max: 0 });
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidWeightCap {}) => {}
+            _ => panic!("Must return invalid weight cap error"),
+        }
+        // Without a tracked total a percentage would cap voters at a share of
+        // their own weight
+        if let ExecuteMsg::CreatePoll { weight_cap, .. } = &mut msg {
+            *weight_cap = Some(WeightCap::Percent {
+                max: Decimal::percent(50),
+            });
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidWeightCap {}) => {}
+            _ => panic!("Must return invalid weight cap error"),
+        }
+
+        // No whale may count with more than half of the allowlist's 1000 power,
+        // and whatever remains counts as its square root
+        if let ExecuteMsg::CreatePoll {
+            strategy,
+            allowlist,
+            weight_curve,
+            weight_cap,
+            ..
+        } = &mut msg
+        {
+            *strategy = Some(VotingStrategy::Allowlist {});
+            *allowlist = vec![
+                Member {
+                    address: ADDR1.to_string(),
+                    weight: 900,
+                },
+                Member {
+                    address: ADDR2.to_string(),
+                    weight: 100,
+                },
+            ];
+            *weight_curve = Some(WeightCurve::Sqrt);
+            *weight_cap = Some(WeightCap::Percent {
+                max: Decimal::percent(50),
+            });
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = QueryMsg::SimulateVote {
+            poll_id: "some_id".to_string(),
+            address: ADDR1.to_string(),
+            vote: vec!["Yes".to_string()],
+            funds: vec![],
+            amounts: vec![],
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: SimulateVoteResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.weight, Uint128::new(22));
+
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "Yes"),
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "No"),
+        )
+        .unwrap();
+
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(
+            poll.tallies(),
+            vec![
+                ("Yes".to_string(), Uint128::new(22)),
+                ("No".to_string(), Uint128::new(10))
+            ]
+        );
+
+        // Growing the allowlist raises the cap for re-weighted ballots
+        let msg = ExecuteMsg::UpdateAllowlist {
+            poll_id: "some_id".to_string(),
+            add: vec![Member {
+                address: ADDR1.to_string(),
+                weight: 1600,
+            }],
+            remove: vec![],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(
+            poll.tallies(),
+            vec![
+                ("Yes".to_string(), Uint128::new(29)),
+                ("No".to_string(), Uint128::new(10))
+            ]
+        );
+        let ballot = BALLOTS
+            .load(&deps.storage, (Addr::unchecked(ADDR1), "some_id"))
+            .unwrap();
+        let tally = compute_tally(
+            &["Yes".to_string(), "No".to_string()],
+            &[ballot],
+            &VotingStrategy::Allowlist {},
+        );
+        assert_eq!(tally.options[0].1, Uint128::new(29));
+
+        // Members joining raise the total, and with it the cap of ballots
+        // already cast
+        let msg = ExecuteMsg::UpdateAllowlist {
+            poll_id: "some_id".to_string(),
+            add: vec![Member {
+                address: "addr3".to_string(),
+                weight: 300,
+            }],
+            remove: vec![],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(
+            poll.tallies(),
+            vec![
+                ("Yes".to_string(), Uint128::new(31)),
+                ("No".to_string(), Uint128::new(10))
+            ]
+        );
+
+        // Once the poll is closed its voters are settled
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::UpdateAllowlist {
+            poll_id: "some_id".to_string(),
+            add: vec![],
+            remove: vec![ADDR2.to_string()],
+        };
+        let res = execute(deps.as_mut(), env, info, msg);
+        match res {
+            Err(ContractError::PollClosed {}) => {}
+            _ => panic!("Must return poll closed error"),
+        }
+    }
+
+    #[test]
+    fn test_execute_moderators() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::AddModerator {
+            address: "moderator".to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = QueryMsg::Moderators {
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg.clone()).unwrap();
+        let res: ModeratorsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.moderators, vec![Addr::unchecked("moderator")]);
+
+        for poll_id in ["poll_1", "poll_2", "poll_3"] {
+            let msg = create_poll_msg(poll_id, "Spam?", &["Yes", "No"]);
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+        }
+
+        let moderator = mock_info("moderator", &[]);
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "poll_1".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), moderator.clone(), msg).unwrap();
+        let msg = ExecuteMsg::DeletePoll {
+            poll_id: "poll_2".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), moderator.clone(), msg).unwrap();
+        assert!(!POLLS.has(&deps.storage, "poll_2"));
+
+        // Moderators cannot cancel polls or touch the config
+        let msg = ExecuteMsg::CancelPoll {
+            poll_id: "poll_3".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), moderator.clone(), msg);
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let msg = ExecuteMsg::UpdateConfig {
+            unique_subjects: Some(false),
+            admin_pubkey: None,
+            poll_creation_fee: None,
+            poll_deposit: None,
+            treasury: None,
+            cw20_poll_creation_fee: None,
+            max_polls_per_creator: None,
+            poll_creation_cooldown: None,
+            min_options: None,
+            max_options: None,
+            max_question_len: None,
+            max_option_len: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), moderator.clone(), msg);
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+
+        let msg = ExecuteMsg::RemoveModerator {
+            address: "moderator".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "poll_3".to_string(),
+        };
+        let res = execute(deps.as_mut(), env, moderator, msg);
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+    }
+
+    #[test]
+    fn test_execute_vote_coin_basket() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = create_poll_msg("some_id", "Upgrade the chain?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll {
+            strategy, basket, ..
+        } = &mut msg
+        {
+            *strategy = Some(VotingStrategy::Coins {});
+            *basket = vec![
+                BasketDenom {
+                    denom: "uatom".to_string(),
+                    multiplier: 5,
+                },
+                BasketDenom {
+                    denom: "ujuno".to_string(),
+                    multiplier: 1,
+                },
+            ];
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &coins(10, "uosmo")),
+            vote_msg("some_id", "Yes"),
+        );
+        match res {
+            Err(ContractError::DenomNotAccepted { denom }) => assert_eq!(denom, "uosmo"),
+            _ => panic!("Must return denom not accepted error"),
+        }
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &[]),
+            vote_msg("some_id", "Yes"),
+        );
+        match res {
+            Err(ContractError::Payment(PaymentError::NoFunds {})) => {}
+            _ => panic!("Must return no funds error"),
+        }
+
+        let funds = vec![coin(10, "uatom"), coin(3, "ujuno")];
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &funds),
+            vote_msg("some_id", "Yes"),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[3], attr("weight", "53"));
+
+        // Changing the vote locks the new funds and frees the old ones
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &coins(4, "ujuno")),
+            vote_msg("some_id", "No"),
+        )
+        .unwrap();
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(
+            poll.tallies(),
+            vec![
+                ("Yes".to_string(), Uint128::new(0)),
+                ("No".to_string(), Uint128::new(4))
+            ]
+        );
+
+        let msg = QueryMsg::Claims {
+            address: ADDR1.to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg.clone()).unwrap();
+        let res: ClaimsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.claims, funds);
+
+        let release = ExecuteMsg::ReleaseEscrow {
+            poll_id: "some_id".to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &[]),
+            release.clone(),
+        );
+        match res {
+            Err(ContractError::PollStillOpen {}) => {}
+            _ => panic!("Must return poll still open error"),
+        }
+
+        let close = ExecuteMsg::ClosePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR1, &[]), close).unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &[]),
+            release.clone(),
+        )
+        .unwrap();
+
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: ClaimsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.claims, vec![coin(10, "uatom"), coin(7, "ujuno")]);
+
+        let res = execute(deps.as_mut(), env, mock_info(ADDR1, &[]), release);
+        match res {
+            Err(ContractError::NothingToClaim {}) => {}
+            _ => panic!("Must return nothing to claim error"),
+        }
+    }
+
+    #[test]
+    fn test_execute_create_poll_fee() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let mut msg = default_instantiate_msg();
+        msg.poll_creation_fee = Some(coin(100, "ujuno"));
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info(ADDR1, &[]), msg).unwrap();
+
+        let msg = create_poll_msg(
+            "some_id",
+            "What's your favourite Cosmos coin?",
+            &["Cosmos Hub", "Juno"],
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Payment(PaymentError::NoFunds {})) => {}
+            _ => panic!("Must return no funds error"),
+        }
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &coins(50, "ujuno")),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::InsufficientFee { fee }) => assert_eq!(fee, coin(100, "ujuno")),
+            _ => panic!("Must return insufficient fee error"),
+        }
+
+        // Overpaying credits the difference back to the creator
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &coins(150, "ujuno")),
+            msg,
+        )
+        .unwrap();
+        assert_eq!(
+            COLLECTED_FEES.load(&deps.storage).unwrap(),
+            coins(100, "ujuno")
+        );
+
+        let msg = QueryMsg::Claims {
+            address: ADDR2.to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: ClaimsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.claims, coins(50, "ujuno"));
+    }
+
+    #[test]
+    fn test_query_voting_power() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = create_poll_msg("some_id", "Fund the proposal?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll {
+            strategy,
+            allowlist,
+            weight_curve,
+            ..
+        } = &mut msg
+        {
+            *strategy = Some(VotingStrategy::Allowlist {});
+            *allowlist = vec![Member {
+                address: ADDR2.to_string(),
+                weight: 49,
+            }];
+            *weight_curve = Some(WeightCurve::Sqrt);
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = QueryMsg::VotingPower {
+            poll_id: "some_id".to_string(),
+            address: ADDR2.to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: VotingPowerResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.weight, Uint128::new(7));
+
+        let msg = QueryMsg::VotingPower {
+            poll_id: "some_id".to_string(),
+            address: ADDR1.to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: VotingPowerResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.weight, Uint128::new(0));
+    }
+
+    #[test]
+    fn test_execute_withdraw_fees() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let mut msg = default_instantiate_msg();
+        msg.poll_creation_fee = Some(coin(100, "ujuno"));
+        msg.treasury = Some("treasury".to_string());
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        for poll_id in ["poll_1", "poll_2"] {
+            let msg = create_poll_msg(poll_id, "Question?", &["Yes", "No"]);
+            let _res = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(ADDR2, &coins(100, "ujuno")),
+                msg,
+            )
+            .unwrap();
+        }
+
+        let msg = ExecuteMsg::WithdrawFees {
+            amount: Some(coin(50, "ujuno")),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: coins(50, "ujuno"),
+            })
+        );
+
+        let msg = ExecuteMsg::WithdrawFees {
+            amount: Some(coin(500, "ujuno")),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::InsufficientCollectedFees {}) => {}
+            _ => panic!("Must return insufficient collected fees error"),
+        }
+
+        // Withdrawing without an amount empties the fee pot
+        let msg = ExecuteMsg::WithdrawFees { amount: None };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: coins(150, "ujuno"),
+            })
+        );
+        assert!(COLLECTED_FEES.load(&deps.storage).unwrap().is_empty());
+
+        let res = execute(deps.as_mut(), env, info, msg);
+        match res {
+            Err(ContractError::InsufficientCollectedFees {}) => {}
+            _ => panic!("Must return insufficient collected fees error"),
+        }
+    }
+
+    #[test]
+    fn test_execute_schedule_config() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = ExecuteMsg::ScheduleConfig {
+            unique_subjects: None,
+            admin_pubkey: None,
+            poll_creation_fee: Some(coin(100, "ujuno")),
+            poll_deposit: None,
+            treasury: None,
+            activate_at: Scheduled::AtHeight(env.block.height),
+            cw20_poll_creation_fee: None,
+            max_polls_per_creator: None,
+            poll_creation_cooldown: None,
+            min_options: None,
+            max_options: None,
+            max_question_len: None,
+            max_option_len: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidSchedule {}) => {}
+            _ => panic!("Must return invalid schedule error"),
+        }
+
+        if let ExecuteMsg::ScheduleConfig { activate_at, .. } = &mut msg {
+            *activate_at = Scheduled::AtHeight(env.block.height + 100);
+        }
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Announced but not active yet, polls are still free
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::GetConfig {}).unwrap();
+        let res: ConfigResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.config.poll_creation_fee, None);
+        assert_eq!(
+            res.scheduled.unwrap().changes.poll_creation_fee,
+            Some(coin(100, "ujuno"))
+        );
+
+        let msg = create_poll_msg("poll_1", "Question?", &["Yes", "No"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        env.block.height += 100;
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::GetConfig {}).unwrap();
+        let res: ConfigResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.config.poll_creation_fee, Some(coin(100, "ujuno")));
+        assert_eq!(res.scheduled, None);
+
+        let msg = create_poll_msg("poll_2", "Question?", &["Yes", "No"]);
+        let res = execute(deps.as_mut(), env.clone(), info, msg);
+        match res {
+            Err(ContractError::Payment(PaymentError::NoFunds {})) => {}
+            _ => panic!("Must return no funds error"),
+        }
+
+        // The change was persisted by the first execute after activation
+        assert_eq!(
+            CONFIG.load(&deps.storage).unwrap().poll_creation_fee,
+            Some(coin(100, "ujuno"))
+        );
+    }
+
+    #[test]
+    fn test_execute_resolve_outcome() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = create_poll_msg("some_id", "Will ATOM flip ETH by 2030?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll { resolver, .. } = &mut msg {
+            *resolver = Some(ResolverInput {
+                resolver: "oracle".to_string(),
+                arbiter: None,
+                deadline: Expiration::AtHeight(env.block.height + 100),
+            });
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let resolve = |outcome: &str| ExecuteMsg::ResolveOutcome {
+            poll_id: "some_id".to_string(),
+            outcome: outcome.to_string(),
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("oracle", &[]),
+            resolve("Yes"),
+        );
+        match res {
+            Err(ContractError::PollStillOpen {}) => {}
+            _ => panic!("Must return poll still open error"),
+        }
+
+        let msg = ExecuteMsg::ClosePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Before the deadline only the resolver may report the outcome
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), resolve("Yes"));
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+
+        // Past it, the resolver has gone silent and the admin arbitrates
+        env.block.height += 100;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("oracle", &[]),
+            resolve("Yes"),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), resolve("No")).unwrap();
+
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        let resolution = poll.resolution.unwrap();
+        assert_eq!(resolution.outcome, Some("No".to_string()));
+        assert_eq!(resolution.resolved_by, Some(Addr::unchecked(ADDR1)));
+
+        let res = execute(deps.as_mut(), env, info, resolve("Yes"));
+        match res {
+            Err(ContractError::AlreadyResolved {}) => {}
+            _ => panic!("Must return already resolved error"),
+        }
+    }
+
+    #[test]
+    fn test_execute_receive_cw20_fee() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let mut msg = default_instantiate_msg();
+        msg.cw20_poll_creation_fee = Some(Cw20Coin {
+            address: "fee_token".to_string(),
+            amount: Uint128::new(100),
+        });
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let poll = PollInput {
+            poll_id: Some("some_id".to_string()),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+            series_id: None,
+            strategy: None,
+            allowlist: vec![],
+            end_time: None,
+            subject: None,
+            chambers: vec![],
+            weight_curve: None,
+            weight_cap: None,
+            basket: vec![],
+            resolver: None,
+            vote_token: None,
+            correction_window: None,
+            min_support: None,
+            group: None,
+            max_selections: None,
+            upgrade: None,
+            method: None,
+            privacy: None,
+            commit_deadline: None,
+            quorum: None,
+            veto: None,
+            finalizer: None,
+            execution: None,
+            template: None,
+            threshold: None,
+            lottery: false,
+            tie_break: None,
+            labels: vec![],
+            max_ballots: None,
+            description: None,
+            image_url: None,
+            metadata: None,
+            option_details: vec![],
+            captures: vec![],
+            tags: vec![],
+        };
+        let receive = |amount: u128| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: ADDR2.to_string(),
+                amount: Uint128::new(amount),
+                msg: to_binary(&ReceiveMsg::CreatePoll(poll.clone())).unwrap(),
+            })
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("other_token", &[]),
+            receive(100),
+        );
+        match res {
+            Err(ContractError::UnexpectedToken {}) => {}
+            _ => panic!("Must return unexpected token error"),
+        }
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("fee_token", &[]),
+            receive(99),
+        );
+        match res {
+            Err(ContractError::IncorrectCw20Fee { .. }) => {}
+            _ => panic!("Must return incorrect cw20 fee error"),
+        }
+
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("fee_token", &[]),
+            receive(100),
+        )
+        .unwrap();
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(poll.creator, Addr::unchecked(ADDR2));
+
+        // Token callbacks can't carry a native deposit
+        let msg = ExecuteMsg::UpdateConfig {
+            unique_subjects: None,
+            admin_pubkey: None,
+            poll_creation_fee: None,
+            poll_deposit: Some(coin(100, "ujuno")),
+            treasury: None,
+            cw20_poll_creation_fee: None,
+            max_polls_per_creator: None,
+            poll_creation_cooldown: None,
+            min_options: None,
+            max_options: None,
+            max_question_len: None,
+            max_option_len: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("fee_token", &[]),
+            receive(100),
+        );
+        match res {
+            Err(ContractError::DepositNotPayable { deposit }) => {
+                assert_eq!(deposit, coin(100, "ujuno"))
+            }
+            _ => panic!("Must return deposit not payable error"),
+        }
+
+        // The cw20 fees go to the treasury along with native ones
+        let msg = ExecuteMsg::WithdrawFees { amount: None };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "fee_token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: ADDR1.to_string(),
+                    amount: Uint128::new(100),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn test_execute_vote_for() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg(
+            "some_id",
+            "What's your favourite Cosmos coin?",
+            &["Cosmos Hub", "Juno"],
+        );
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let vote_for = ExecuteMsg::VoteFor {
+            voter: ADDR2.to_string(),
+            poll_id: "some_id".to_string(),
+            vote: vec!["Juno".to_string()],
+            memo: None,
+            proof: Some(Binary::from(b"session".as_slice())),
+            amounts: vec![],
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("gateway", &[]),
+            vote_for.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+
+        let msg = ExecuteMsg::AddGateway {
+            address: "gateway".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = QueryMsg::Gateways {
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: GatewaysResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.gateways, vec![Addr::unchecked("gateway")]);
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("gateway", &[]),
+            vote_for.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[0], attr("action", "execute_vote"));
+        assert!(res.attributes.contains(&attr("voter", ADDR2)));
+        assert!(res.attributes.contains(&attr("proof", "c2Vzc2lvbg==")));
+
+        // The ballot belongs to the user, attributed to the gateway
+        let ballot = BALLOTS
+            .load(&deps.storage, (Addr::unchecked(ADDR2), "some_id"))
+            .unwrap();
+        assert_eq!(ballot.options, vec!["Juno"]);
+        assert_eq!(ballot.gateway, Some(Addr::unchecked("gateway")));
+
+        let msg = ExecuteMsg::RemoveGateway {
+            address: "gateway".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let res = execute(deps.as_mut(), env, mock_info("gateway", &[]), vote_for);
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+    }
+
+    #[test]
+    fn test_execute_unexpected_funds() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let paying = mock_info(ADDR1, &coins(10, "ujuno"));
+
+        // Free poll creation doesn't take funds
+        let msg = create_poll_msg(
+            "some_id",
+            "What's your favourite Cosmos coin?",
+            &["Cosmos Hub", "Juno"],
+        );
+        let res = execute(deps.as_mut(), env.clone(), paying.clone(), msg.clone());
+        match res {
+            Err(ContractError::UnexpectedFunds {}) => {}
+            _ => panic!("Must return unexpected funds error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Neither do votes outside coin polls
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            paying.clone(),
+            vote_msg("some_id", "Juno"),
+        );
+        match res {
+            Err(ContractError::UnexpectedFunds {}) => {}
+            _ => panic!("Must return unexpected funds error"),
+        }
+
+        let msg = ExecuteMsg::WatchPoll {
+            poll_id: "some_id".to_string(),
+        };
+        let res = execute(deps.as_mut(), env, paying, msg);
+        match res {
+            Err(ContractError::UnexpectedFunds {}) => {}
+            _ => panic!("Must return unexpected funds error"),
+        }
+    }
+
+    #[test]
+    fn test_query_storage_stats() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let stats = |deps: Deps| -> StorageStats {
+            let bin = query(deps, mock_env(), QueryMsg::StorageStats {}).unwrap();
+            let res: StorageStatsResponse = from_binary(&bin).unwrap();
+            res.stats
+        };
+        assert_eq!(stats(deps.as_ref()), StorageStats::default());
+
+        let msg = create_poll_msg("some_id", "Adopt the new charter?", &["Yes", "No"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "Yes"),
+        )
+        .unwrap();
+        let res = stats(deps.as_ref());
+        assert_eq!(res.polls.count, 1);
+        assert_eq!(res.ballots.count, 1);
+        assert_eq!(res.indexes.count, 3);
+        assert!(res.polls.bytes > 0 && res.ballots.bytes > 0);
+
+        // Changing a vote rewrites the ballot without adding an entry
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "No"),
+        )
+        .unwrap();
+        assert_eq!(stats(deps.as_ref()).ballots.count, 1);
+
+        let close = ExecuteMsg::ClosePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), close).unwrap();
+        let msg = ExecuteMsg::ArchivePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        let res = stats(deps.as_ref());
+        assert_eq!(res.polls, SectionStats::default());
+        assert_eq!(res.ballots, SectionStats::default());
+        assert_eq!(res.indexes, SectionStats::default());
+        assert_eq!(res.archive.count, 1);
+        assert!(res.archive.bytes > 0);
+    }
+
+    #[test]
+    fn test_execute_vote_cw20_balance() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "token" => {
+                let balance = match from_binary(msg).unwrap() {
+                    Cw20QueryMsg::Balance { address } if address == ADDR1 => 1_000_000_000_000u128,
+                    _ => 0,
+                };
+                let res = BalanceResponse {
+                    balance: Uint128::new(balance),
+                };
+                SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "unexpected query".to_string(),
+            }),
+        });
+
+        // Token-weighted polls need a token, and only they accept one
+        let mut msg = create_poll_msg("some_id", "Adopt the new charter?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll { strategy, .. } = &mut msg {
+            *strategy = Some(VotingStrategy::Cw20Balance {});
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::MissingVoteToken {}) => {}
+            _ => panic!("Must return missing vote token error"),
+        }
+
+        let mut wrong = create_poll_msg("other_id", "Adopt the old charter?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll { vote_token, .. } = &mut wrong {
+            *vote_token = Some("token".to_string());
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), wrong);
+        match res {
+            Err(ContractError::VoteTokenNotEnabled {}) => {}
+            _ => panic!("Must return vote token not enabled error"),
+        }
+
+        if let ExecuteMsg::CreatePoll { vote_token, .. } = &mut msg {
+            *vote_token = Some("token".to_string());
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Balances beyond u64 are counted in full
+        let res = execute(deps.as_mut(), env.clone(), info, vote_msg("some_id", "Yes")).unwrap();
+        assert_eq!(res.attributes[3], attr("weight", "1000000000000"));
+        let ballot = BALLOTS
+            .load(&deps.storage, (Addr::unchecked(ADDR1), "some_id"))
+            .unwrap();
+        assert_eq!(ballot.weight, Uint128::new(1_000_000_000_000));
+
+        // Holders of none of the token can't vote
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "Yes"),
+        );
+        match res {
+            Err(ContractError::NotEligible {}) => {}
+            _ => panic!("Must return not eligible error"),
+        }
+
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(poll.options[0].tally, Uint128::new(1_000_000_000_000));
+    }
+
+    #[test]
+    fn test_query_random_polls() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        for i in 0..6 {
+            let poll_id = format!("poll_{}", i);
+            let msg = create_poll_msg(&poll_id, "Adopt the new charter?", &["Yes", "No"]);
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+        let close = ExecuteMsg::ClosePoll {
+            poll_id: "poll_0".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, close).unwrap();
+
+        let sample = |seed: u64, limit: u32, env: Env| -> Vec<String> {
+            let msg = QueryMsg::RandomPolls {
+                seed,
+                limit: Some(limit),
+            };
+            let bin = query(deps.as_ref(), env, msg).unwrap();
+            let res: AllPollsResponse = from_binary(&bin).unwrap();
+            res.polls.into_iter().map(|info| info.poll_id).collect()
+        };
+
+        // The same seed in the same block gives the same sample...
+        let first = sample(7, 3, env.clone());
+        assert_eq!(first.len(), 3);
+        assert_eq!(first, sample(7, 3, env.clone()));
+
+        // ...of open polls only
+        let all = sample(7, 10, env.clone());
+        assert_eq!(all.len(), 5);
+        assert!(!all.contains(&"poll_0".to_string()));
+        assert_eq!(all[..3], first[..]);
+
+        // Other seeds or blocks reshuffle it
+        let reshuffled = (0..10).any(|seed| sample(seed, 10, env.clone()) != all);
+        assert!(reshuffled);
+        let mut later = env;
+        later.block.height += 1;
+        let mut seeds = (0..10).map(|seed| sample(seed, 10, later.clone()));
+        assert!(seeds.any(|order| order != all));
+    }
+
+    #[test]
+    fn test_execute_vote_staked() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let delegation = |validator: &str, amount: Coin| FullDelegation {
+            delegator: Addr::unchecked(ADDR1),
+            validator: validator.to_string(),
+            amount: amount.clone(),
+            can_redelegate: amount,
+            accumulated_rewards: vec![],
+        };
+        deps.querier.update_staking(
+            "ustake",
+            &[],
+            &[
+                delegation("validator1", coin(30, "ustake")),
+                delegation("validator2", coin(12, "ustake")),
+                // Only the staking denom counts
+                delegation("validator2", coin(500, "uother")),
+            ],
+        );
+
+        let mut msg = create_poll_msg("some_id", "Adopt the new charter?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll { strategy, .. } = &mut msg {
+            *strategy = Some(VotingStrategy::Staked {});
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = QueryMsg::VotingPower {
+            poll_id: "some_id".to_string(),
+            address: ADDR1.to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: VotingPowerResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.weight, Uint128::new(42));
+
+        let _res = execute(deps.as_mut(), env.clone(), info, vote_msg("some_id", "Yes")).unwrap();
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(poll.options[0].tally, Uint128::new(42));
+
+        // Nothing bonded, no vote
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "Yes"),
+        );
+        match res {
+            Err(ContractError::NotEligible {}) => {}
+            _ => panic!("Must return not eligible error"),
+        }
+    }
+
+    #[test]
+    fn test_execute_vote_correction_window() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = create_poll_msg("some_id", "Adopt the new charter?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll {
+            correction_window, ..
+        } = &mut msg
+        {
+            *correction_window = Some(Duration::Time(600));
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "Yes"),
+        )
+        .unwrap();
+
+        // Free to change within the window, which doesn't restart on changes
+        env.block.time = env.block.time.plus_seconds(300);
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "No"),
+        )
+        .unwrap();
+
+        env.block.time = env.block.time.plus_seconds(400);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "Yes"),
+        );
+        match res {
+            Err(ContractError::BallotLocked {}) => {}
+            _ => panic!("Must return ballot locked error"),
+        }
+        let msg = ExecuteMsg::RevokeVote {
+            poll_id: "some_id".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg);
+        match res {
+            Err(ContractError::BallotLocked {}) => {}
+            _ => panic!("Must return ballot locked error"),
+        }
+
+        // Late voters get a window of their own
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "Yes"),
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "No"),
+        )
+        .unwrap();
+
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(
+            poll.tallies(),
+            vec![
+                ("Yes".to_string(), Uint128::zero()),
+                ("No".to_string(), Uint128::new(2))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_query_poll_viable_options() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = create_poll_msg("some_id", "Pick a chain", &["Juno", "Osmosis", "Stargaze"]);
+        if let ExecuteMsg::CreatePoll { min_support, .. } = &mut msg {
+            *min_support = Some(Decimal::percent(101));
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidThreshold {}) => {}
+            _ => panic!("Must return invalid threshold error"),
+        }
+
+        if let ExecuteMsg::CreatePoll { min_support, .. } = &mut msg {
+            *min_support = Some(Decimal::percent(20));
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let votes = [
+            ("voter1", "Juno"),
+            ("voter2", "Juno"),
+            ("voter3", "Juno"),
+            ("voter4", "Juno"),
+            ("voter5", "Osmosis"),
+        ];
+        for (voter, vote) in votes {
+            let _res = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(voter, &[]),
+                vote_msg("some_id", vote),
+            )
+            .unwrap();
+        }
+
+        // Exactly 20% is enough, no votes at all isn't
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+            option_offset: None,
+            option_limit: None,
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.viable_options, vec!["Juno", "Osmosis"]);
+    }
+
+    #[test]
+    fn test_execute_vote_cw4_group() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "group" => {
+                let weight = match from_binary(msg).unwrap() {
+                    Cw4QueryMsg::Member { addr, .. } if addr == ADDR1 => Some(7),
+                    Cw4QueryMsg::Member { addr, .. } if addr == "zero" => Some(0),
+                    _ => None,
+                };
+                let res = MemberResponse { weight };
+                SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "unexpected query".to_string(),
+            }),
+        });
+
+        let mut msg = create_poll_msg("some_id", "Adopt the new charter?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll { strategy, .. } = &mut msg {
+            *strategy = Some(VotingStrategy::Cw4Group {});
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::MissingGroup {}) => {}
+            _ => panic!("Must return missing group error"),
+        }
+
+        let mut wrong = create_poll_msg("other_id", "Adopt the old charter?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll { group, .. } = &mut wrong {
+            *group = Some("group".to_string());
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), wrong);
+        match res {
+            Err(ContractError::GroupNotEnabled {}) => {}
+            _ => panic!("Must return group not enabled error"),
+        }
+
+        if let ExecuteMsg::CreatePoll { group, .. } = &mut msg {
+            *group = Some("group".to_string());
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let res = execute(deps.as_mut(), env.clone(), info, vote_msg("some_id", "Yes")).unwrap();
+        assert_eq!(res.attributes[3], attr("weight", "7"));
+
+        // Non-members and weightless members are turned away
+        for voter in [ADDR2, "zero"] {
+            let res = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(voter, &[]),
+                vote_msg("some_id", "Yes"),
+            );
+            match res {
+                Err(ContractError::NotEligible {}) => {}
+                _ => panic!("Must return not eligible error"),
+            }
+        }
+
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(poll.options[0].tally, Uint128::new(7));
+    }
+
+    #[test]
+    fn test_query_verify_ballots() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg("some_id", "Adopt the new charter?", &["Yes", "No"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info, vote_msg("some_id", "Yes")).unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "No"),
+        )
+        .unwrap();
+
+        let claim = |address: &str, option: &str| (address.to_string(), option.to_string());
+        let msg = QueryMsg::VerifyBallots {
+            poll_id: "some_id".to_string(),
+            claims: vec![
+                claim(ADDR1, "Yes"),
+                claim(ADDR2, "Yes"),
+                claim(ADDR2, "No"),
+                claim("addr3", "Yes"),
+                claim("", "Yes"),
+            ],
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: VerifyBallotsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.results, vec![true, false, true, false, false]);
+
+        let msg = QueryMsg::VerifyBallots {
+            poll_id: "some_id".to_string(),
+            claims: vec![claim(ADDR1, "Yes"); 501],
+        };
+        assert!(query(deps.as_ref(), env, msg).is_err());
+    }
+
+    #[test]
+    fn test_execute_vote_multi_choice() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = create_poll_msg("some_id", "Pick chains", &["Juno", "Osmosis", "Stargaze"]);
+        if let ExecuteMsg::CreatePoll { max_selections, .. } = &mut msg {
+            *max_selections = Some(4);
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidMaxSelections {}) => {}
+            _ => panic!("Must return invalid max selections error"),
+        }
+
+        if let ExecuteMsg::CreatePoll { max_selections, .. } = &mut msg {
+            *max_selections = Some(2);
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let multi_vote = |vote: &[&str]| ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vote.iter().map(|option| option.to_string()).collect(),
+            memo: None,
+            amounts: vec![],
+        };
+
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), multi_vote(&[]));
+        match res {
+            Err(ContractError::EmptySelection {}) => {}
+            _ => panic!("Must return empty selection error"),
+        }
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            multi_vote(&["Juno", "Juno"]),
+        );
+        match res {
+            Err(ContractError::DuplicateSelection { option }) => assert_eq!(option, "Juno"),
+            _ => panic!("Must return duplicate selection error"),
+        }
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            multi_vote(&["Juno", "Osmosis", "Stargaze"]),
+        );
+        match res {
+            Err(ContractError::TooManySelections { max }) => assert_eq!(max, 2),
+            _ => panic!("Must return too many selections error"),
+        }
+
+        // Each selection counts in full
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            multi_vote(&["Juno", "Osmosis"]),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[2], attr("vote", "Juno,Osmosis"));
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        let tallies: Vec<u128> = poll.options.iter().map(|o| o.tally.u128()).collect();
+        assert_eq!(tallies, vec![1, 1, 0]);
+
+        // Re-voting takes back every previous selection
+        let _res = execute(deps.as_mut(), env, info, multi_vote(&["Stargaze"])).unwrap();
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        let tallies: Vec<u128> = poll.options.iter().map(|o| o.tally.u128()).collect();
+        assert_eq!(tallies, vec![0, 0, 1]);
+        let ballot = BALLOTS
+            .load(&deps.storage, (Addr::unchecked(ADDR1), "some_id"))
+            .unwrap();
+        assert_eq!(ballot.options, vec!["Stargaze"]);
+    }
+
+    #[test]
+    fn test_execute_upgrade() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let upgrade_poll = |poll_id: &str, options: &[&str], end_height: u64| {
+            let mut msg = create_poll_msg(poll_id, "Upgrade to code 42?", options);
+            if let ExecuteMsg::CreatePoll {
+                end_time, upgrade, ..
+            } = &mut msg
+            {
+                *end_time = Some(Expiration::AtHeight(end_height));
+                *upgrade = Some(UpgradeInput {
+                    code_id: 42,
+                    msg: to_binary(&MigrateMsg {}).unwrap(),
+                    timelock: Duration::Height(5),
+                });
+            }
+            msg
+        };
+        let end_height = env.block.height + 10;
+
+        // Only the admin proposes upgrades unless they're permitted, as a
+        // yes/no question
+        let msg = upgrade_poll("some_id", &["Yes", "No"], end_height);
+        let res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg);
+        match res {
+            Err(ContractError::MsgNotPermitted {}) => {}
+            _ => panic!("Must return message not permitted error"),
+        }
+        let msg = upgrade_poll("some_id", &["Yes", "No", "Later"], end_height);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::InvalidUpgrade {}) => {}
+            _ => panic!("Must return invalid upgrade error"),
+        }
+
+        let msg = upgrade_poll("some_id", &["Yes", "No"], end_height);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = upgrade_poll("other_id", &["Yes", "No"], end_height);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "Yes"),
+        )
+        .unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info, vote_msg("other_id", "No")).unwrap();
+
+        let upgrade = |poll_id: &str| ExecuteMsg::ExecuteUpgrade {
+            poll_id: poll_id.to_string(),
+        };
+        let anyone = mock_info(ADDR2, &[]);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            anyone.clone(),
+            upgrade("some_id"),
+        );
+        match res {
+            Err(ContractError::PollStillOpen {}) => {}
+            _ => panic!("Must return poll still open error"),
+        }
+
+        env.block.height = end_height;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            anyone.clone(),
+            upgrade("some_id"),
+        );
+        match res {
+            Err(ContractError::NotFinalized {}) => {}
+            _ => panic!("Must return not finalized error"),
+        }
+        for poll_id in ["some_id", "other_id"] {
+            let msg = ExecuteMsg::FinalizePoll {
+                poll_id: poll_id.to_string(),
+            };
+            let _res = execute(deps.as_mut(), env.clone(), anyone.clone(), msg).unwrap();
+        }
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            anyone.clone(),
+            upgrade("some_id"),
+        );
+        match res {
+            Err(ContractError::UpgradeTimelocked {}) => {}
+            _ => panic!("Must return upgrade timelocked error"),
+        }
+
+        env.block.height += 5;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            anyone.clone(),
+            upgrade("other_id"),
+        );
+        match res {
+            Err(ContractError::UpgradeRejected {}) => {}
+            _ => panic!("Must return upgrade rejected error"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            anyone.clone(),
+            upgrade("some_id"),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Migrate {
+                contract_addr: env.contract.address.to_string(),
+                new_code_id: 42,
+                msg: to_binary(&MigrateMsg {}).unwrap(),
+            })
+        );
+
+        let res = execute(deps.as_mut(), env, anyone, upgrade("some_id"));
+        match res {
+            Err(ContractError::UpgradeExecuted {}) => {}
+            _ => panic!("Must return upgrade executed error"),
+        }
+    }
+
+    #[test]
+    fn test_execute_vote_approval() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let options = ["Juno", "Osmosis", "Stargaze", "Cosmos Hub"];
+        let mut msg = create_poll_msg("some_id", "Which chains do you use?", &options);
+        if let ExecuteMsg::CreatePoll {
+            method,
+            max_selections,
+            ..
+        } = &mut msg
+        {
+            *method = Some(VotingMethod::Approval {});
+            *max_selections = Some(2);
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidMaxSelections {}) => {}
+            _ => panic!("Must return invalid max selections error"),
+        }
+
+        if let ExecuteMsg::CreatePoll { max_selections, .. } = &mut msg {
+            *max_selections = None;
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let approve = |vote: &[&str]| ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vote.iter().map(|option| option.to_string()).collect(),
+            memo: None,
+            amounts: vec![],
+        };
+        let tallies = |deps: Deps| -> Vec<u128> {
+            let poll = load_poll(deps.storage, "some_id").unwrap();
+            poll.options.iter().map(|o| o.tally.u128()).collect()
+        };
+
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), approve(&options)).unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            approve(&["Osmosis"]),
+        )
+        .unwrap();
+        assert_eq!(tallies(deps.as_ref()), vec![1, 2, 1, 1]);
+
+        // Updating keeps shared approvals and drops the rest
+        let _res = execute(
+            deps.as_mut(),
+            env,
+            info,
+            approve(&["Cosmos Hub", "Osmosis"]),
+        )
+        .unwrap();
+        assert_eq!(tallies(deps.as_ref()), vec![0, 2, 0, 1]);
+    }
+
+    #[test]
+    fn test_execute_set_space_info() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::SpaceInfo {}).unwrap();
+        let res: SpaceInfoResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.info, None);
+
+        let mut space_info = SpaceInfo {
+            name: "Juno DAO".to_string(),
+            logo_uri: Some("ipfs://logo".to_string()),
+            colors: vec!["#f0827d".to_string(), "#000000".to_string()],
+            links: vec![SpaceLink {
+                label: "Forum".to_string(),
+                url: "https://forum.example".to_string(),
+            }],
+        };
+
+        let msg = ExecuteMsg::SetSpaceInfo {
+            info: space_info.clone(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::SpaceInfo {}).unwrap();
+        let res: SpaceInfoResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.info, Some(space_info.clone()));
+
+        space_info.colors = vec!["#ffffff".to_string(); 5];
+        let msg = ExecuteMsg::SetSpaceInfo { info: space_info };
+        let res = execute(deps.as_mut(), env, info, msg);
+        match res {
+            Err(ContractError::InvalidSpaceInfo {}) => {}
+            _ => panic!("Must return invalid space info error"),
+        }
+    }
+
+    #[test]
+    fn test_poll_privacy() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let polls = [
+            ("public", Privacy::Public),
+            ("anonymous", Privacy::Anonymous),
+            ("reveal", Privacy::PostCloseReveal),
+        ];
+        for (poll_id, level) in polls {
+            let mut msg = create_poll_msg(poll_id, "Adopt the new charter?", &["Yes", "No"]);
+            if let ExecuteMsg::CreatePoll { privacy, .. } = &mut msg {
+                *privacy = Some(level);
+            }
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+            let res = execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                vote_msg(poll_id, "Yes"),
+            )
+            .unwrap();
+            let shown = if poll_id == "public" { "Yes" } else { "hidden" };
+            assert_eq!(res.attributes[2], attr("vote", shown));
+
+            // Revoking gives away no more than voting did
+            let msg = ExecuteMsg::RevokeVote {
+                poll_id: poll_id.to_string(),
+            };
+            let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+            assert_eq!(res.attributes[3], attr("revoked_vote", shown));
+            let msg = vote_msg(poll_id, "Yes");
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+            // Nor does simulating a change of ballot
+            let msg = QueryMsg::SimulateVote {
+                poll_id: poll_id.to_string(),
+                address: ADDR1.to_string(),
+                vote: vec!["No".to_string()],
+                amounts: vec![],
+                funds: vec![],
+            };
+            let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+            let res: SimulateVoteResponse = from_binary(&bin).unwrap();
+            assert!(res.accepted);
+            if poll_id == "public" {
+                assert_eq!(
+                    res.options,
+                    vec![
+                        ("Yes".to_string(), Uint128::zero()),
+                        ("No".to_string(), Uint128::new(1))
+                    ]
+                );
+            } else {
+                assert!(res.options.is_empty());
+            }
+        }
+
+        let lookup = |deps: Deps, poll_id: &str| {
+            let msg = QueryMsg::Vote {
+                address: ADDR1.to_string(),
+                poll_id: poll_id.to_string(),
+            };
+            query(deps, mock_env(), msg)
+        };
+        let listed = |deps: Deps| -> Vec<String> {
+            let msg = QueryMsg::AllVotesForAUser {
+                user_address: ADDR1.to_string(),
+                start_after: None,
+                limit: None,
+            };
+            let bin = query(deps, mock_env(), msg).unwrap();
+            let res: AllVotesForAUserResponse = from_binary(&bin).unwrap();
+            res.votes.into_iter().map(|(poll_id, _)| poll_id).collect()
+        };
+
+        assert!(lookup(deps.as_ref(), "public").is_ok());
+        assert!(lookup(deps.as_ref(), "anonymous").is_err());
+        assert!(lookup(deps.as_ref(), "reveal").is_err());
+        assert_eq!(listed(deps.as_ref()), vec!["public"]);
+
+        let msg = QueryMsg::AllBallotsForPoll {
+            poll_id: "public".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AllBallotsForPollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.ballots.len(), 1);
+        assert_eq!(res.ballots[0].0, Addr::unchecked(ADDR1));
+        let msg = QueryMsg::AllBallotsForPoll {
+            poll_id: "anonymous".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        assert!(query(deps.as_ref(), env.clone(), msg).is_err());
+
+        // Closing reveals post-close ballots, anonymous ones stay hidden
+        for poll_id in ["anonymous", "reveal"] {
+            let msg = ExecuteMsg::ClosePoll {
+                poll_id: poll_id.to_string(),
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+        assert!(lookup(deps.as_ref(), "anonymous").is_err());
+        assert!(lookup(deps.as_ref(), "reveal").is_ok());
+        assert_eq!(listed(deps.as_ref()), vec!["public", "reveal"]);
+    }
+
+    #[test]
+    fn test_execute_vote_quadratic() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = create_poll_msg("some_id", "Which chains do you use?", &["Juno", "Osmosis"]);
+        if let ExecuteMsg::CreatePoll { method, .. } = &mut msg {
+            *method = Some(VotingMethod::Quadratic { credits: 0 });
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidCredits {}) => {}
+            _ => panic!("Must return invalid credits error"),
+        }
+
+        if let ExecuteMsg::CreatePoll { method, .. } = &mut msg {
+            *method = Some(VotingMethod::Quadratic { credits: 10 });
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let spend = |vote: &[&str], amounts: &[u64]| ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vote.iter().map(|option| option.to_string()).collect(),
+            amounts: amounts.to_vec(),
+            memo: None,
+        };
+        let credits = |deps: Deps| -> CreditsResponse {
+            let msg = QueryMsg::Credits {
+                poll_id: "some_id".to_string(),
+                address: ADDR1.to_string(),
+            };
+            from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+        };
+
+        // Amounts are required, one positive amount per selection
+        for amounts in [&[][..], &[1, 0], &[1]] {
+            let res = execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                spend(&["Juno", "Osmosis"], amounts),
+            );
+            match res {
+                Err(ContractError::InvalidVoteAmounts {}) => {}
+                _ => panic!("Must return invalid vote amounts error"),
+            }
+        }
+
+        // 3² + 1² = 10 fits the budget, 3² + 2² doesn't
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            spend(&["Juno", "Osmosis"], &[3, 2]),
+        );
+        match res {
+            Err(ContractError::InsufficientCredits { credits: 10 }) => {}
+            _ => panic!("Must return insufficient credits error"),
+        }
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            spend(&["Juno", "Osmosis"], &[3, 1]),
+        )
+        .unwrap();
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(
+            poll.tallies(),
+            vec![
+                ("Juno".to_string(), Uint128::new(3)),
+                ("Osmosis".to_string(), Uint128::new(1))
+            ]
+        );
+        assert_eq!(
+            credits(deps.as_ref()),
+            CreditsResponse {
+                budget: 10,
+                spent: 10,
+                remaining: 0,
+            }
+        );
+
+        // Re-voting refunds the old allocation
+        let _res = execute(deps.as_mut(), env, info, spend(&["Osmosis"], &[2])).unwrap();
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(
+            poll.tallies(),
+            vec![
+                ("Juno".to_string(), Uint128::zero()),
+                ("Osmosis".to_string(), Uint128::new(2))
+            ]
+        );
+        assert_eq!(credits(deps.as_ref()).remaining, 6);
+
+        // Single-choice polls don't take amounts
+        let msg = create_poll_msg("other_id", "Which chain?", &["Juno", "Osmosis"]);
+        let _res = execute(deps.as_mut(), mock_env(), mock_info(ADDR1, &[]), msg).unwrap();
+        let mut msg = vote_msg("other_id", "Juno");
+        if let ExecuteMsg::Vote { amounts, .. } = &mut msg {
+            *amounts = vec![1];
+        }
+        let res = execute(deps.as_mut(), mock_env(), mock_info(ADDR1, &[]), msg);
+        match res {
+            Err(ContractError::InvalidVoteAmounts {}) => {}
+            _ => panic!("Must return invalid vote amounts error"),
+        }
+    }
+
+    #[test]
+    fn test_scenarios() {
+        let mut app = App::default();
+        let code_id = app.store_code(scenarios::contract());
+
+        let tallies = weighted_election(&mut app, code_id, &WeightedElection::default());
+        assert_eq!(
+            tallies,
+            vec![
+                ("Juno".to_string(), Uint128::new(5)),
+                ("Osmosis".to_string(), Uint128::new(3))
+            ]
+        );
+
+        prize_poll(&mut app, code_id, &PrizePoll::default());
+
+        let tallies = commit_reveal_vote(&mut app, code_id, &CommitRevealVote::default());
+        assert_eq!(
+            tallies,
+            vec![
+                ("Juno".to_string(), Uint128::new(1)),
+                ("Osmosis".to_string(), Uint128::new(1))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_execute_vote_score() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let options = ["Juno", "Osmosis", "Stargaze"];
+        let mut msg = create_poll_msg("some_id", "Rate these chains", &options);
+        if let ExecuteMsg::CreatePoll { method, .. } = &mut msg {
+            *method = Some(VotingMethod::Score {});
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let score = |scores: &[u64]| ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: options.iter().map(|option| option.to_string()).collect(),
+            amounts: scores.to_vec(),
+            memo: None,
+        };
+
+        // Every option needs a score within range
+        let mut partial = score(&[5, 4]);
+        if let ExecuteMsg::Vote { vote, .. } = &mut partial {
+            vote.pop();
+        }
+        for msg in [score(&[5, 4]), score(&[6, 0, 0]), partial] {
+            let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+            match res {
+                Err(ContractError::InvalidScores { max: MAX_SCORE }) => {}
+                _ => panic!("Must return invalid scores error"),
+            }
+        }
+
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), score(&[5, 0, 2])).unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            score(&[2, 3, 2]),
+        )
+        .unwrap();
+        // Re-scoring replaces the voter's previous scores
+        let _res = execute(deps.as_mut(), env.clone(), info, score(&[4, 0, 2])).unwrap();
+
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+            option_offset: None,
+            option_limit: None,
+        };
+        let res: PollResponse = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+        let poll = res.poll.unwrap();
+        assert_eq!(
+            poll.options
+                .iter()
+                .map(|o| o.tally.u128())
+                .collect::<Vec<_>>(),
+            vec![6, 3, 4]
+        );
+        assert_eq!(
+            poll.score_counts
+                .iter()
+                .map(|c| c.1.u128())
+                .collect::<Vec<_>>(),
+            vec![2, 2, 2]
+        );
+        assert_eq!(
+            res.average_scores,
+            vec![
+                ("Juno".to_string(), Decimal::from_ratio(3u128, 1u128)),
+                ("Osmosis".to_string(), Decimal::from_ratio(3u128, 2u128)),
+                ("Stargaze".to_string(), Decimal::from_ratio(2u128, 1u128)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_execute_commit_vote() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // The commit phase has to end before the poll does
+        let mut msg = create_poll_msg("some_id", "Which chain?", &["Juno", "Osmosis"]);
+        if let ExecuteMsg::CreatePoll {
+            end_time,
+            commit_deadline,
+            ..
+        } = &mut msg
+        {
+            *end_time = Some(Expiration::AtHeight(env.block.height + 10));
+            *commit_deadline = Some(Expiration::AtHeight(env.block.height + 10));
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidEndTime {}) => {}
+            _ => panic!("Must return invalid end time error"),
+        }
+
+        if let ExecuteMsg::CreatePoll {
+            commit_deadline, ..
+        } = &mut msg
+        {
+            *commit_deadline = Some(Expiration::AtHeight(env.block.height + 5));
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let vote = vec!["Juno".to_string()];
+        let commit = ExecuteMsg::CommitVote {
+            poll_id: "some_id".to_string(),
+            commitment: ballot_commitment(&vote, "salt"),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), commit.clone()).unwrap();
+        let ballot = BALLOTS
+            .may_load(&deps.storage, (Addr::unchecked(ADDR1), "some_id"))
+            .unwrap();
+        assert_eq!(ballot, None);
+
+        env.block.height += 5;
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), commit);
+        match res {
+            Err(ContractError::CommitPhaseOver {}) => {}
+            _ => panic!("Must return commit phase over error"),
+        }
+
+        let reveal = ExecuteMsg::RevealVote {
+            poll_id: "some_id".to_string(),
+            vote,
+            amounts: vec![],
+            salt: "salt".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), reveal.clone()).unwrap();
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(poll.options[0].tally, Uint128::new(1));
+
+        // Revealing consumes the commitment
+        let res = execute(deps.as_mut(), env, info, reveal);
+        match res {
+            Err(ContractError::CommitmentNotFound {}) => {}
+            _ => panic!("Must return commitment not found error"),
+        }
+    }
+
+    #[test]
+    fn test_query_total_power_at_height() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+        let start = env.block.height;
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = create_poll_msg("some_id", "Which chain?", &["Juno", "Osmosis"]);
+        if let ExecuteMsg::CreatePoll { strategy, .. } = &mut msg {
+            *strategy = Some(VotingStrategy::Registry {});
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let member = |address: &str, weight: u64| Member {
+            address: address.to_string(),
+            weight,
+        };
+        let msg = ExecuteMsg::UpdateMembers {
+            add: vec![member(ADDR1, 3), member(ADDR2, 2)],
+            remove: vec![],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        env.block.height += 10;
+        let msg = ExecuteMsg::UpdateMembers {
+            add: vec![member(ADDR2, 6)],
+            remove: vec![ADDR1.to_string()],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let total_power = |height: Option<u64>| -> u128 {
+            let msg = QueryMsg::TotalPowerAtHeight {
+                poll_id: "some_id".to_string(),
+                height,
+            };
+            let res: TotalPowerAtHeightResponse =
+                from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+            res.power.u128()
+        };
+        // Totals are as of the start of each height
+        assert_eq!(total_power(Some(start)), 0);
+        assert_eq!(total_power(Some(start + 1)), 5);
+        assert_eq!(total_power(Some(start + 10)), 5);
+        assert_eq!(total_power(Some(start + 11)), 6);
+        assert_eq!(total_power(None), 6);
+
+        let msg = create_poll_msg("other_id", "Which chain?", &["Juno", "Osmosis"]);
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR1, &[]), msg).unwrap();
+        let msg = QueryMsg::TotalPowerAtHeight {
+            poll_id: "other_id".to_string(),
+            height: None,
+        };
+        assert!(query(deps.as_ref(), env, msg).is_err());
+    }
+
+    #[test]
+    fn test_abstain_and_quorum() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Quorum needs a known total power
+        let mut msg = create_poll_msg("some_id", "Which chain?", &["Juno", "Osmosis"]);
+        if let ExecuteMsg::CreatePoll { quorum, .. } = &mut msg {
+            *quorum = Some(Decimal::percent(50));
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidQuorum {}) => {}
+            _ => panic!("Must return invalid quorum error"),
+        }
+
+        if let ExecuteMsg::CreatePoll {
+            strategy,
+            allowlist,
+            ..
+        } = &mut msg
+        {
+            *strategy = Some(VotingStrategy::Allowlist {});
+            *allowlist = vec![
+                Member {
+                    address: ADDR1.to_string(),
+                    weight: 3,
+                },
+                Member {
+                    address: ADDR2.to_string(),
+                    weight: 7,
+                },
+            ];
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let results = |deps: Deps| -> ResultsResponse {
+            let msg = QueryMsg::Results {
+                poll_id: "some_id".to_string(),
+                option_offset: None,
+                option_limit: None,
+            };
+            from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+        };
+
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "Juno"),
+        )
+        .unwrap();
+        let res = results(deps.as_ref());
+        assert_eq!(res.participation, Uint128::new(3));
+        assert_eq!(res.total_power, Some(Uint128::new(10)));
+        assert!(!res.valid);
+
+        // Abstaining reaches quorum without backing any option
+        let abstain = ExecuteMsg::Abstain {
+            poll_id: "some_id".to_string(),
+            memo: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), abstain).unwrap();
+        assert_eq!(res.attributes[2], attr("vote", "abstain"));
+        let res = results(deps.as_ref());
+        assert_eq!(
+            res.options,
+            vec![
+                ("Juno".to_string(), Uint128::new(3)),
+                ("Osmosis".to_string(), Uint128::zero())
+            ]
+        );
+        assert_eq!(res.abstain, Uint128::new(7));
+        assert_eq!(res.participation, Uint128::new(10));
+        assert!(res.valid);
+
+        // Voting afterwards replaces the abstention
+        let _res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "Osmosis"),
+        )
+        .unwrap();
+        let res = results(deps.as_ref());
+        assert_eq!(res.abstain, Uint128::zero());
+        assert_eq!(res.participation, Uint128::new(10));
+        assert_eq!(res.options[1].1, Uint128::new(7));
+    }
+
+    #[test]
+    fn test_execute_set_permitted_msgs() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let permitted = vec![
+            PermittedMsg::BankSend {
+                max: vec![coin(100, "ujuno")],
+            },
+            PermittedMsg::Migrate {},
+        ];
+        let msg = ExecuteMsg::SetPermittedMsgs {
+            permitted: permitted.clone(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::PermittedMsgs {}).unwrap();
+        let res: PermittedMsgsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.permitted, permitted);
+
+        // Once migrations are permitted anyone may propose an upgrade
+        let mut msg = create_poll_msg("some_id", "Upgrade to code 42?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll {
+            end_time, upgrade, ..
+        } = &mut msg
+        {
+            *end_time = Some(Expiration::AtHeight(env.block.height + 10));
+            *upgrade = Some(UpgradeInput {
+                code_id: 42,
+                msg: to_binary(&MigrateMsg {}).unwrap(),
+                timelock: Duration::Height(5),
+            });
+        }
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        let send = |amount: u128| {
+            let msg: CosmosMsg = BankMsg::Send {
+                to_address: ADDR2.to_string(),
+                amount: vec![coin(amount, "ujuno")],
+            }
+            .into();
+            ensure_permitted(deps.as_ref(), &env, &msg)
+        };
+        assert!(send(100).is_ok());
+        match send(101) {
+            Err(ContractError::MsgNotPermitted {}) => {}
+            _ => panic!("Must return message not permitted error"),
+        }
+    }
+
+    #[test]
+    fn test_execute_veto_deposit() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let mut msg = default_instantiate_msg();
+        msg.poll_deposit = Some(coin(100, "ujuno"));
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let mut msg = create_poll_msg("some_id", "Ship it?", &["Yes", "No", "Veto"]);
+        if let ExecuteMsg::CreatePoll { veto, .. } = &mut msg {
+            *veto = Some(Veto {
+                option: "Abstain".to_string(),
+                threshold: Decimal::percent(33),
+            });
+        }
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &coins(100, "ujuno")),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::InvalidVeto {}) => {}
+            _ => panic!("Must return invalid veto error"),
+        }
+
+        if let ExecuteMsg::CreatePoll { end_time, veto, .. } = &mut msg {
+            *end_time = Some(Expiration::AtHeight(env.block.height + 10));
+            *veto = Some(Veto {
+                option: "Veto".to_string(),
+                threshold: Decimal::percent(33),
+            });
+        }
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &coins(50, "ujuno")),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::InsufficientDeposit { deposit }) => {
+                assert_eq!(deposit, coin(100, "ujuno"))
+            }
+            _ => panic!("Must return insufficient deposit error"),
+        }
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &coins(100, "ujuno")),
+            msg,
+        )
+        .unwrap();
+
+        let mut msg = create_poll_msg("other_id", "Ship it too?", &["Yes", "No", "Veto"]);
+        if let ExecuteMsg::CreatePoll { end_time, veto, .. } = &mut msg {
+            *end_time = Some(Expiration::AtHeight(env.block.height + 10));
+            *veto = Some(Veto {
+                option: "Veto".to_string(),
+                threshold: Decimal::percent(67),
+            });
+        }
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &coins(100, "ujuno")),
+            msg,
+        )
+        .unwrap();
+
+        for (voter, vote) in [(ADDR1, "Veto"), (ADDR2, "Yes"), ("addr3", "Veto")] {
+            for poll_id in ["some_id", "other_id"] {
+                let msg = vote_msg(poll_id, vote);
+                let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+            }
+        }
+
+        let msg = ExecuteMsg::SettleDeposit {
+            poll_id: "some_id".to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::PollStillOpen {}) => {}
+            _ => panic!("Must return poll still open error"),
+        }
+
+        env.block.height += 10;
+        let query_msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+            option_offset: None,
+            option_limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.status, Some(PollStatus::Vetoed));
+
+        // Two thirds vetoed, which is over the first poll's threshold only
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("forfeited", "true")));
+        assert_eq!(
+            COLLECTED_FEES.load(&deps.storage).unwrap(),
+            coins(100, "ujuno")
+        );
+        let res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg);
+        match res {
+            Err(ContractError::NoDeposit {}) => {}
+            _ => panic!("Must return no deposit error"),
+        }
+
+        let msg = ExecuteMsg::SettleDeposit {
+            poll_id: "other_id".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info(ADDR1, &[]), msg).unwrap();
+        assert!(res.attributes.contains(&attr("forfeited", "false")));
+        let msg = QueryMsg::Claims {
+            address: ADDR2.to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: ClaimsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.claims, coins(100, "ujuno"));
+    }
+
+    #[test]
+    fn test_query_streaks() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        for poll_id in ["poll_1", "poll_2", "poll_3"] {
+            let msg = create_poll_msg(poll_id, "Question?", &["Yes", "No"]);
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+        let votes = [
+            (ADDR1, "poll_1"),
+            (ADDR1, "poll_2"),
+            (ADDR1, "poll_3"),
+            // Changing a ballot doesn't count twice
+            (ADDR1, "poll_3"),
+            (ADDR2, "poll_1"),
+            (ADDR2, "poll_3"),
+            ("addr3", "poll_3"),
+            ("addr3", "poll_2"),
+        ];
+        for (voter, poll_id) in votes {
+            let msg = vote_msg(poll_id, "Yes");
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+
+        let msg = QueryMsg::Streak {
+            address: ADDR1.to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: StreakResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.streak,
+            Streak {
+                current: 3,
+                longest: 3,
+                last_poll: 3,
+                polls_voted: 3,
+            }
+        );
+
+        // Skipping a poll starts a new streak
+        let msg = QueryMsg::Streak {
+            address: ADDR2.to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: StreakResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.streak.current, 1);
+        assert_eq!(res.streak.polls_voted, 2);
+
+        let msg = QueryMsg::Streak {
+            address: "addr4".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: StreakResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.streak, Streak::default());
+
+        let leaders = |deps: Deps| -> Vec<String> {
+            let msg = QueryMsg::Leaderboard { limit: Some(2) };
+            let bin = query(deps, mock_env(), msg).unwrap();
+            let res: LeaderboardResponse = from_binary(&bin).unwrap();
+            res.entries.into_iter().map(|e| e.address).collect()
+        };
+        assert_eq!(leaders(deps.as_ref()), vec![ADDR1, ADDR2]);
+
+        // The ranking is rebuilt from the streaks when migrating
+        STREAK_RANKING.remove(
+            &mut deps.storage,
+            (u64::MAX - 3, u64::MAX - 3, &Addr::unchecked(ADDR1)),
+        );
+        assert_eq!(leaders(deps.as_ref()), vec![ADDR2, "addr3"]);
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.2.0").unwrap();
+        let _res = migrate(deps.as_mut(), env, MigrateMsg {}).unwrap();
+        assert_eq!(leaders(deps.as_ref()), vec![ADDR1, ADDR2]);
+    }
+
+    #[test]
+    fn test_execute_backup_finalizer() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = create_poll_msg("some_id", "Will ATOM flip ETH by 2030?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll {
+            resolver,
+            finalizer,
+            ..
+        } = &mut msg
+        {
+            *resolver = Some(ResolverInput {
+                resolver: "oracle".to_string(),
+                arbiter: None,
+                deadline: Expiration::AtHeight(env.block.height + 100),
+            });
+            *finalizer = Some(FinalizerInput {
+                address: "backup".to_string(),
+                timeout: Duration::Height(20),
+            });
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidFinalizer {}) => {}
+            _ => panic!("Must return invalid finalizer error"),
+        }
+
+        if let ExecuteMsg::CreatePoll { end_time, .. } = &mut msg {
+            *end_time = Some(Expiration::AtHeight(env.block.height + 10));
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        if let ExecuteMsg::CreatePoll { poll_id, .. } = &mut msg {
+            *poll_id = Some("other_id".to_string());
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let resolve = ExecuteMsg::ResolveOutcome {
+            poll_id: "some_id".to_string(),
+            outcome: "No".to_string(),
+        };
+        let archive = ExecuteMsg::ArchivePoll {
+            poll_id: "other_id".to_string(),
+        };
+
+        // Closed, but the timeout runs from the end time
+        env.block.height += 10;
+        for msg in [resolve.clone(), archive.clone()] {
+            let res = execute(deps.as_mut(), env.clone(), mock_info("backup", &[]), msg);
+            match res {
+                Err(ContractError::Unauthorized {}) => {}
+                _ => panic!("Must return unauthorized error"),
+            }
+        }
+
+        env.block.height += 20;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("backup", &[]),
+            resolve,
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("resolved_by", "backup")));
+        let _res = execute(deps.as_mut(), env, mock_info("backup", &[]), archive).unwrap();
+        assert!(!POLLS.has(&deps.storage, "other_id"));
+    }
+
+    #[test]
+    fn test_execute_binding_poll() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let payout = CosmosMsg::Bank(BankMsg::Send {
+            to_address: "grantee".to_string(),
+            amount: coins(1000, "ujuno"),
+        });
+        let mut msg = create_poll_msg("some_id", "Fund the grant?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll {
+            strategy,
+            allowlist,
+            end_time,
+            quorum,
+            execution,
+            ..
+        } = &mut msg
+        {
+            *strategy = Some(VotingStrategy::Allowlist {});
+            *allowlist = [ADDR1, ADDR2, "addr3"]
+                .iter()
+                .map(|address| Member {
+                    address: address.to_string(),
+                    weight: 1,
+                })
+                .collect();
+            *end_time = Some(Expiration::AtHeight(env.block.height + 10));
+            *quorum = Some(Decimal::percent(67));
+            *execution = Some(ExecutionInput {
+                pass_option: "Maybe".to_string(),
+                msgs: vec![payout.clone()],
+            });
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidExecution {}) => {}
+            _ => panic!("Must return invalid execution error"),
+        }
+
+        if let ExecuteMsg::CreatePoll { execution, .. } = &mut msg {
+            *execution = Some(ExecutionInput {
+                pass_option: "Yes".to_string(),
+                msgs: vec![payout.clone()],
+            });
+        }
+        // Only the admin may attach messages that aren't permitted
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::MsgNotPermitted {}) => {}
+            _ => panic!("Must return msg not permitted error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        if let ExecuteMsg::CreatePoll { poll_id, .. } = &mut msg {
+            *poll_id = Some("other_id".to_string());
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = create_poll_msg("plain_id", "Question?", &["Yes", "No"]);
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let votes = [(ADDR1, "some_id"), (ADDR1, "other_id"), (ADDR2, "other_id")];
+        for (voter, poll_id) in votes {
+            let msg = vote_msg(poll_id, "Yes");
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+
+        let run = |poll_id: &str| ExecuteMsg::Execute {
+            poll_id: poll_id.to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            run("other_id"),
+        );
+        match res {
+            Err(ContractError::PollStillOpen {}) => {}
+            _ => panic!("Must return poll still open error"),
+        }
+
+        env.block.height += 10;
+        // Nothing runs off the live tallies before the result is finalized
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            run("other_id"),
+        );
+        match res {
+            Err(ContractError::NotFinalized {}) => {}
+            _ => panic!("Must return not finalized error"),
+        }
+        for poll_id in ["some_id", "other_id"] {
+            let msg = ExecuteMsg::FinalizePoll {
+                poll_id: poll_id.to_string(),
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+        }
+
+        // A lone vote falls short of the quorum
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            run("some_id"),
+        );
+        match res {
+            Err(ContractError::PollNotPassed {}) => {}
+            _ => panic!("Must return poll not passed error"),
+        }
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            run("plain_id"),
+        );
+        match res {
+            Err(ContractError::NotBinding {}) => {}
+            _ => panic!("Must return not binding error"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            run("other_id"),
+        )
+        .unwrap();
+        assert_eq!(res.messages[0].msg, payout);
+        let res = execute(deps.as_mut(), env, mock_info(ADDR2, &[]), run("other_id"));
+        match res {
+            Err(ContractError::PollExecuted {}) => {}
+            _ => panic!("Must return poll executed error"),
+        }
+    }
+
+    #[test]
+    fn test_execute_templates() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let rules = PollRules {
+            method: Some(VotingMethod::Approval {}),
+            privacy: Some(Privacy::Anonymous),
+            ..PollRules::default()
+        };
+        let msg = ExecuteMsg::SetTemplate {
+            name: "council".to_string(),
+            rules: Some(rules.clone()),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = QueryMsg::ExportTemplate {
+            name: "council".to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: ExportTemplateResponse = from_binary(&bin).unwrap();
+        let export = res.export;
+
+        // Importing into a fresh deployment recreates the template as is
+        let mut other = mock_dependencies();
+        let msg = default_instantiate_msg();
+        let _res = instantiate(other.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let mut tampered: TemplateExport = from_binary(&export).unwrap();
+        tampered.format += 1;
+        let msg = ExecuteMsg::ImportTemplate {
+            export: to_binary(&tampered).unwrap(),
+        };
+        let res = execute(other.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::InvalidTemplate {}) => {}
+            _ => panic!("Must return invalid template error"),
+        }
+        let msg = ExecuteMsg::ImportTemplate { export };
+        let _res = execute(other.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = QueryMsg::Templates {
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(other.as_ref(), env.clone(), msg).unwrap();
+        let res: TemplatesResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.templates, vec![("council".to_string(), rules)]);
+
+        // Settings left unset come from the template
+        let mut msg = create_poll_msg("some_id", "Which grants?", &["A", "B", "C"]);
+        if let ExecuteMsg::CreatePoll { template, .. } = &mut msg {
+            *template = Some("board".to_string());
+        }
+        let res = execute(other.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::TemplateNotFound {}) => {}
+            _ => panic!("Must return template not found error"),
+        }
+        if let ExecuteMsg::CreatePoll {
+            template, privacy, ..
+        } = &mut msg
+        {
+            *template = Some("council".to_string());
+            *privacy = Some(Privacy::Public);
+        }
+        let _res = execute(other.as_mut(), env, info, msg).unwrap();
+        let poll = load_poll(&other.storage, "some_id").unwrap();
+        assert_eq!(poll.method, VotingMethod::Approval {});
+        assert_eq!(poll.privacy, Privacy::Public);
+        assert_eq!(poll.max_selections, 3);
+        assert_eq!(poll.template, Some("council".to_string()));
+    }
+
+    #[test]
+    fn test_query_results_threshold() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = create_poll_msg("some_id", "Which logo?", &["A", "B", "C"]);
+        if let ExecuteMsg::CreatePoll { threshold, .. } = &mut msg {
+            *threshold = Some(Decimal::percent(150));
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidThreshold {}) => {}
+            _ => panic!("Must return invalid threshold error"),
+        }
+        if let ExecuteMsg::CreatePoll { threshold, .. } = &mut msg {
+            *threshold = Some(Decimal::percent(50));
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let results = |deps: Deps| -> ResultsResponse {
+            let msg = QueryMsg::Results {
+                poll_id: "some_id".to_string(),
+                option_offset: None,
+                option_limit: None,
+            };
+            from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+        };
+        let votes = [(ADDR1, "A"), (ADDR2, "A"), ("addr3", "B"), ("addr4", "C")];
+        for (voter, vote) in votes {
+            let msg = vote_msg("some_id", vote);
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+        // A leads, but with exactly half of the weight
+        assert_eq!(results(deps.as_ref()).winner, None);
+
+        // Abstentions don't count against the threshold
+        let msg = ExecuteMsg::Abstain {
+            poll_id: "some_id".to_string(),
+            memo: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("addr4", &[]), msg).unwrap();
+        assert_eq!(results(deps.as_ref()).winner, Some("A".to_string()));
+    }
+
+    #[test]
+    fn test_execute_finalize_poll() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = create_poll_msg("some_id", "Fund the proposal?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll {
+            strategy,
+            allowlist,
+            end_time,
+            quorum,
+            ..
+        } = &mut msg
+        {
+            *strategy = Some(VotingStrategy::Allowlist {});
+            *allowlist = [ADDR1, ADDR2]
+                .iter()
+                .map(|address| Member {
+                    address: address.to_string(),
+                    weight: 1,
+                })
+                .collect();
+            *end_time = Some(Expiration::AtHeight(env.block.height + 10));
+            *quorum = Some(Decimal::percent(50));
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = vote_msg("some_id", "Yes");
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let finalize = ExecuteMsg::FinalizePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            finalize.clone(),
+        );
+        match res {
+            Err(ContractError::PollStillOpen {}) => {}
+            _ => panic!("Must return poll still open error"),
+        }
+
+        env.block.height += 10;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            finalize.clone(),
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("winner", "Yes")));
+        let res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), finalize);
+        match res {
+            Err(ContractError::AlreadyFinalized {}) => {}
+            _ => panic!("Must return already finalized error"),
+        }
+
+        // Growing the allowlist afterwards could sink the quorum, so it is
+        // refused and the finalized result stands
+        let msg = ExecuteMsg::UpdateAllowlist {
+            poll_id: "some_id".to_string(),
+            add: vec![Member {
+                address: "addr3".to_string(),
+                weight: 10,
+            }],
+            remove: vec![],
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg);
+        match res {
+            Err(ContractError::PollClosed {}) => {}
+            _ => panic!("Must return poll closed error"),
+        }
+        let msg = QueryMsg::Results {
+            poll_id: "some_id".to_string(),
+            option_offset: None,
+            option_limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: ResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.total_power, Some(Uint128::new(2)));
+        assert!(res.valid);
+        assert_eq!(res.winner, Some("Yes".to_string()));
+        assert_eq!(res.finalized_at, Some(env.block.height));
+    }
+
+    #[test]
+    fn test_execute_finalize_lottery() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = create_poll_msg("some_id", "Which project gets the slot?", &["A", "B"]);
+        if let ExecuteMsg::CreatePoll {
+            end_time, lottery, ..
+        } = &mut msg
+        {
+            *end_time = Some(Expiration::AtHeight(env.block.height + 10));
+            *lottery = true;
+        }
+        // Lotteries need somewhere to draw from
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::RandomnessUnavailable {}) => {}
+            _ => panic!("Must return randomness unavailable error"),
+        }
+        let set_proxy = ExecuteMsg::SetRandomnessProxy {
+            proxy: Some("nois_proxy".to_string()),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            set_proxy.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), set_proxy).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let votes = [(ADDR1, "A"), (ADDR2, "A"), ("addr3", "B")];
+        for (voter, vote) in votes {
+            let msg = vote_msg("some_id", vote);
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+
+        let results = |deps: Deps| -> ResultsResponse {
+            let msg = QueryMsg::Results {
+                poll_id: "some_id".to_string(),
+                option_offset: None,
+                option_limit: None,
+            };
+            from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+        };
+        // A leads, but nothing is won until the draw
+        assert_eq!(results(deps.as_ref()).winner, None);
+
+        // Finalizing asks the proxy for the next beacon round, paying its fee
+        env.block.height += 10;
+        let msg = ExecuteMsg::FinalizePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let fee = coins(50, "unois");
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &fee),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "nois_proxy".to_string(),
+                msg: to_binary(&NoisProxyExecuteMsg::GetNextRandomness {
+                    job_id: "some_id".to_string(),
+                })
+                .unwrap(),
+                funds: fee,
+            })
+        );
+        let res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg);
+        match res {
+            Err(ContractError::DrawPending {}) => {}
+            _ => panic!("Must return draw pending error"),
+        }
+        assert_eq!(results(deps.as_ref()).winner, None);
+
+        // Only the proxy delivers the randomness, which settles the draw
+        let randomness = Sha256::new().chain("round 1024").finalize();
+        let receive = ExecuteMsg::NoisReceive {
+            callback: NoisCallback {
+                job_id: "some_id".to_string(),
+                published: env.block.time,
+                randomness: hex::encode(randomness),
+            },
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            receive.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("nois_proxy", &[]),
+            receive.clone(),
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("nois_proxy", &[]),
+            receive,
+        );
+        match res {
+            Err(ContractError::NoPendingDraw {}) => {}
+            _ => panic!("Must return no pending draw error"),
+        }
+
+        // Anyone can replay the draw from the beacon round
+        let result = load_poll(&deps.storage, "some_id").unwrap().result.unwrap();
+        let draw = result.draw.unwrap();
+        assert_eq!(draw.randomness.as_slice(), randomness.as_slice());
+        assert_eq!(draw.published, Some(env.block.time));
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&randomness[..16]);
+        assert_eq!(draw.point, Uint128::new(u128::from_be_bytes(bytes) % 3));
+        let expected = if draw.point < Uint128::new(2) {
+            "A"
+        } else {
+            "B"
+        };
+        assert_eq!(results(deps.as_ref()).winner, Some(expected.to_string()));
+    }
+
+    #[test]
+    fn test_execute_precheck_eligibility() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "group" => {
+                let weight = match from_binary(msg).unwrap() {
+                    Cw4QueryMsg::Member { addr, .. } if addr == ADDR1 => Some(7),
+                    _ => None,
+                };
+                let res = MemberResponse { weight };
+                SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "unexpected query".to_string(),
+            }),
+        });
+
+        let mut msg = create_poll_msg("some_id", "Adopt the new charter?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll {
+            strategy, group, ..
+        } = &mut msg
+        {
+            *strategy = Some(VotingStrategy::Cw4Group {});
+            *group = Some("group".to_string());
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = create_poll_msg("other_id", "Adopt the old charter?", &["Yes", "No"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let precheck = |poll_id: &str| ExecuteMsg::PrecheckEligibility {
+            poll_id: poll_id.to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            precheck("other_id"),
+        );
+        match res {
+            Err(ContractError::PrecheckNotNeeded {}) => {}
+            _ => panic!("Must return precheck not needed error"),
+        }
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            precheck("some_id"),
+        );
+        match res {
+            Err(ContractError::NotEligible {}) => {}
+            _ => panic!("Must return not eligible error"),
+        }
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            precheck("some_id"),
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("weight", "7")));
+
+        // The vote no longer reaches out to the group
+        deps.querier.update_wasm(|_| {
+            SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "unexpected query".to_string(),
+            })
+        });
+        let res = execute(deps.as_mut(), env, info, vote_msg("some_id", "Yes")).unwrap();
+        assert!(res.attributes.contains(&attr("weight", "7")));
+    }
+
+    #[test]
+    fn test_query_winner_tie_break() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::SetRandomnessProxy {
+            proxy: Some("nois_proxy".to_string()),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let polls = [
+            ("first_id", TieBreak::FirstListed),
+            ("none_id", TieBreak::None),
+            ("random_id", TieBreak::Random),
+        ];
+        for (poll_id, rule) in polls {
+            let mut msg = create_poll_msg(poll_id, "Which venue?", &["A", "B", "C"]);
+            if let ExecuteMsg::CreatePoll {
+                end_time,
+                tie_break,
+                ..
+            } = &mut msg
+            {
+                *end_time = Some(Expiration::AtHeight(env.block.height + 10));
+                *tie_break = Some(rule);
+            }
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+            for (voter, vote) in [(ADDR1, "B"), (ADDR2, "C")] {
+                let msg = vote_msg(poll_id, vote);
+                let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+            }
+        }
+
+        let winner = |deps: Deps, env: Env, poll_id: &str| -> WinnerResponse {
+            let msg = QueryMsg::Winner {
+                poll_id: poll_id.to_string(),
+            };
+            from_binary(&query(deps, env, msg).unwrap()).unwrap()
+        };
+        let res = winner(deps.as_ref(), env.clone(), "first_id");
+        assert_eq!(res.option, Some("B".to_string()));
+        assert_eq!(res.count, Uint128::new(1));
+        assert!(!res.decided);
+
+        env.block.height += 10;
+        assert!(winner(deps.as_ref(), env.clone(), "first_id").decided);
+        let res = winner(deps.as_ref(), env.clone(), "none_id");
+        assert_eq!(res.option, Some("B".to_string()));
+        assert!(!res.decided);
+        assert!(!winner(deps.as_ref(), env.clone(), "random_id").decided);
+
+        // The tie is settled by the next beacon round
+        let msg = ExecuteMsg::FinalizePoll {
             poll_id: "random_id".to_string(),
-            question: "What's your favorite Cosmos coin?".to_string(),
-            options: vec![
-                "Cosmos Hub".to_string(),
-                "Juno".to_string(),
-                "Osmosis".to_string(),
-            ],
         };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert!(!winner(deps.as_ref(), env.clone(), "random_id").decided);
+        let msg = ExecuteMsg::NoisReceive {
+            callback: NoisCallback {
+                job_id: "random_id".to_string(),
+                published: env.block.time,
+                randomness: hex::encode(Sha256::new().chain("round 2048").finalize()),
+            },
+        };
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("nois_proxy", &[]),
+            msg,
+        )
+        .unwrap();
+        let result = load_poll(&deps.storage, "random_id")
+            .unwrap()
+            .result
+            .unwrap();
+        let point = result.draw.unwrap().point;
+        let res = winner(deps.as_ref(), env, "random_id");
+        let drawn = if point.is_zero() { "B" } else { "C" };
+        assert_eq!(res.option, Some(drawn.to_string()));
+        assert!(res.decided);
+    }
+
+    #[test]
+    fn test_reputation_weighting() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::SetReputationParams {
+            params: ReputationParams {
+                participation: 1,
+                accuracy: 2,
+                scale: 0,
+            },
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::InvalidReputationParams {}) => {}
+            _ => panic!("Must return invalid reputation params error"),
+        }
+
+        let mut msg = create_poll_msg("some_id", "Ship it?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll { end_time, .. } = &mut msg {
+            *end_time = Some(Expiration::AtHeight(env.block.height + 10));
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let votes = [(ADDR1, "Yes"), (ADDR2, "No"), ("addr3", "Yes")];
+        for (voter, vote) in votes {
+            let msg = vote_msg("some_id", vote);
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+
+        // Reputation only accrues once the poll is finalized
+        env.block.height += 10;
+        let msg = ExecuteMsg::FinalizePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = QueryMsg::Reputation {
+            address: ADDR1.to_string(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: ReputationResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.reputation,
+            Reputation {
+                polls_voted: 1,
+                polls_won: 1,
+            }
+        );
+        assert_eq!(res.score, 3);
+        assert_eq!(res.weight, Uint128::new(13));
+
+        let mut msg = create_poll_msg("other_id", "Ship it again?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll { strategy, .. } = &mut msg {
+            *strategy = Some(VotingStrategy::Reputation {});
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let votes = [(ADDR1, "13"), (ADDR2, "11"), ("addr4", "10")];
+        for (voter, weight) in votes {
+            let msg = vote_msg("other_id", "Yes");
+            let res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+            assert!(res.attributes.contains(&attr("weight", weight)));
+        }
+    }
+
+    #[test]
+    fn test_credit_reputation_in_batches() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = create_poll_msg("some_id", "Ship it?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll { end_time, .. } = &mut msg {
+            *end_time = Some(Expiration::AtHeight(env.block.height + 10));
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let voters: Vec<String> = (0..=REPUTATION_BATCH)
+            .map(|i| format!("voter{:03}", i))
+            .collect();
+        for voter in &voters {
+            let msg = vote_msg("some_id", "Yes");
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+
+        let msg = ExecuteMsg::CreditReputation {
+            poll_id: "some_id".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::NothingToCredit {}) => {}
+            _ => panic!("Must return nothing to credit error"),
+        }
+
+        // Finalizing credits one batch and leaves the last voter pending
+        env.block.height += 10;
+        let finalize = ExecuteMsg::FinalizePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), finalize).unwrap();
+        assert!(res.attributes.contains(&attr("reputation_pending", "true")));
+        let last = Addr::unchecked(&voters[REPUTATION_BATCH]);
+        assert_eq!(REPUTATION.may_load(&deps.storage, &last).unwrap(), None);
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .contains(&attr("reputation_pending", "false")));
+        assert_eq!(
+            REPUTATION.load(&deps.storage, &last).unwrap(),
+            Reputation {
+                polls_voted: 1,
+                polls_won: 1,
+            }
+        );
+
+        let res = execute(deps.as_mut(), env, info, msg);
+        match res {
+            Err(ContractError::NothingToCredit {}) => {}
+            _ => panic!("Must return nothing to credit error"),
+        }
+    }
+
+    #[test]
+    fn test_query_poll_voters() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg("some_id", "Which chain?", &["Juno", "Osmosis"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let mut msg = create_poll_msg("private_id", "Which chain?", &["Juno", "Osmosis"]);
+        if let ExecuteMsg::CreatePoll { privacy, .. } = &mut msg {
+            *privacy = Some(Privacy::Anonymous);
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let votes = [("addr3", "Juno"), (ADDR1, "Osmosis"), (ADDR2, "Juno")];
+        for (voter, vote) in votes {
+            for poll_id in ["some_id", "private_id"] {
+                let msg = vote_msg(poll_id, vote);
+                let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+            }
+        }
+
+        let msg = QueryMsg::PollVoters {
+            poll_id: "some_id".to_string(),
+            start_after: None,
+            limit: Some(2),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollVotersResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.voters,
+            vec![
+                PollVoter {
+                    address: ADDR1.to_string(),
+                    options: vec!["Osmosis".to_string()],
+                    weight: Uint128::new(1),
+                },
+                PollVoter {
+                    address: ADDR2.to_string(),
+                    options: vec!["Juno".to_string()],
+                    weight: Uint128::new(1),
+                },
+            ]
+        );
+
+        let msg = QueryMsg::PollVoters {
+            poll_id: "some_id".to_string(),
+            start_after: Some(ADDR2.to_string()),
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollVotersResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.voters.len(), 1);
+        assert_eq!(res.voters[0].address, "addr3");
+
+        let msg = QueryMsg::PollVoters {
+            poll_id: "private_id".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        assert!(query(deps.as_ref(), env, msg).is_err());
+    }
+
+    #[test]
+    fn test_read_only_mode() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = create_poll_msg("some_id", "Which chain?", &["Juno", "Osmosis"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::SetGuardian {
+            guardian: Some("guardian".to_string()),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let read_only = |read_only: bool| ExecuteMsg::SetReadOnly { read_only };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            read_only(true),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("guardian", &[]),
+            read_only(true),
+        )
+        .unwrap();
+
+        // Even the admin is turned away, but queries keep answering
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "Juno"),
+        );
+        match res {
+            Err(ContractError::ReadOnly {}) => {}
+            _ => panic!("Must return read only error"),
+        }
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+            option_offset: None,
+            option_limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert!(res.is_open);
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::ReadOnly {}).unwrap();
+        let res: ReadOnlyResponse = from_binary(&bin).unwrap();
+        assert!(res.read_only);
+        assert_eq!(res.guardian, Some(Addr::unchecked("guardian")));
+
+        // The chain can lift it too
+        let _res = sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SetReadOnly { read_only: false },
+        )
+        .unwrap();
+        let _res = execute(deps.as_mut(), env, info, vote_msg("some_id", "Juno")).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_split_tallies() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = create_poll_msg("some_id", "Which chain?", &["Juno", "Osmosis"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            vote_msg("some_id", "Osmosis"),
+        )
+        .unwrap();
+
+        // Votes only touch the counters, the stored poll holds none
+        let raw = deps.storage.get(&POLLS.key("some_id")).unwrap();
+        let raw = String::from_utf8(raw).unwrap();
+        assert!(!raw.contains("tally") && !raw.contains("participation"));
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(poll.options[1].tally, Uint128::new(1));
+        assert_eq!(poll.participation, Uint128::new(1));
+
+        // Lay the poll out as before the split
+        store_v2_poll(&mut deps.storage, "some_id", &poll);
+        TALLIES.remove(&mut deps.storage, ("some_id", 0));
+        TALLIES.remove(&mut deps.storage, ("some_id", 1));
+        POLL_TOTALS.remove(&mut deps.storage, "some_id");
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.1.0").unwrap();
+
+        let res = migrate(deps.as_mut(), env, MigrateMsg {}).unwrap();
+        assert_eq!(res.attributes[4], attr("polls_migrated", "1"));
+        assert_eq!(load_poll(&deps.storage, "some_id").unwrap(), poll);
+        let stored = POLLS.load(&deps.storage, "some_id").unwrap();
+        assert_eq!(stored, static_part(&poll));
+    }
+
+    #[test]
+    fn test_migrate_split_counters() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let mut msg = create_poll_msg("some_id", "Rate these chains", &["Juno", "Osmosis"]);
+        if let ExecuteMsg::CreatePoll { method, .. } = &mut msg {
+            *method = Some(VotingMethod::Score {});
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::Vote {
+            poll_id: "some_id".to_string(),
+            vote: vec!["Juno".to_string(), "Osmosis".to_string()],
+            amounts: vec![4, 0],
+            memo: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(poll.score_counts[0].1, Uint128::new(1));
+
+        // 0.2 kept the score counts in the poll and the rest apart
+        let mut stored = poll.clone();
+        for option in stored.options.iter_mut() {
+            option.tally = Uint128::zero();
+        }
+        stored.participation = Uint128::zero();
+        store_v2_poll(&mut deps.storage, "some_id", &stored);
+        let totals = PollTotals {
+            participation: poll.participation,
+            abstain: poll.abstain,
+            ..PollTotals::default()
+        };
+        POLL_TOTALS
+            .save(&mut deps.storage, "some_id", &totals)
+            .unwrap();
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.2.0").unwrap();
+
+        let res = migrate(deps.as_mut(), env, MigrateMsg {}).unwrap();
+        assert_eq!(res.attributes[4], attr("polls_migrated", "1"));
+        assert_eq!(load_poll(&deps.storage, "some_id").unwrap(), poll);
+        let raw = deps.storage.get(&POLLS.key("some_id")).unwrap();
+        assert!(!String::from_utf8(raw).unwrap().contains("score_counts"));
+    }
+
+    /// Stores `poll` as 0.2 did, counters included and its options as
+    /// (name, tally) pairs.
+    fn store_v2_poll(storage: &mut dyn Storage, poll_id: &str, poll: &Poll) {
+        let options = String::from_utf8(to_vec(&poll.options).unwrap()).unwrap();
+        let pairs = String::from_utf8(to_vec(&poll.tallies()).unwrap()).unwrap();
+        let stored = String::from_utf8(to_vec(poll).unwrap()).unwrap();
+        let stored = stored.replace(&options, &pairs);
+        storage.set(&POLLS.key(poll_id), stored.as_bytes());
+    }
+
+    #[test]
+    fn test_query_polls_by_label() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let label = |key: &str, value: &str| Label {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+        let polls = [
+            (
+                "poll_1",
+                vec![label("team", "infra"), label("quarter", "Q3")],
+            ),
+            ("poll_2", vec![label("team", "ops")]),
+            ("poll_3", vec![label("team", "infra")]),
+        ];
+        for (poll_id, poll_labels) in polls.iter() {
+            let mut msg = create_poll_msg(poll_id, "Which chain?", &["Juno", "Osmosis"]);
+            if let ExecuteMsg::CreatePoll { labels, .. } = &mut msg {
+                *labels = poll_labels.clone();
+            }
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+
+        let mut msg = create_poll_msg("poll_4", "Which chain?", &["Juno", "Osmosis"]);
+        if let ExecuteMsg::CreatePoll { labels, .. } = &mut msg {
+            *labels = vec![label("team", "infra"), label("team", "ops")];
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidLabel { key }) => assert_eq!(key, "team"),
+            _ => panic!("Must return invalid label error"),
+        }
+        if let ExecuteMsg::CreatePoll { labels, .. } = &mut msg {
+            *labels = (0..9).map(|i| label(&i.to_string(), "x")).collect();
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::TooManyLabels { max: 8 }) => {}
+            _ => panic!("Must return too many labels error"),
+        }
+
+        let by_label = |start_after: Option<&str>| QueryMsg::PollsByLabel {
+            key: "team".to_string(),
+            value: "infra".to_string(),
+            start_after: start_after.map(String::from),
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), by_label(None)).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let ids: Vec<_> = res.polls.iter().map(|p| p.poll_id.as_str()).collect();
+        assert_eq!(ids, vec!["poll_1", "poll_3"]);
+        assert_eq!(res.polls[0].poll.labels[1], label("quarter", "Q3"));
+
+        let bin = query(deps.as_ref(), env.clone(), by_label(Some("poll_1"))).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.polls.len(), 1);
+        assert_eq!(res.polls[0].poll_id, "poll_3");
+
+        // Deleted polls leave the index
+        let msg = ExecuteMsg::DeletePoll {
+            poll_id: "poll_3".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let bin = query(deps.as_ref(), env, by_label(None)).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.polls.len(), 1);
+        assert_eq!(res.polls[0].poll_id, "poll_1");
+    }
+
+    #[test]
+    fn test_execute_publish_draft() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let mut msg = default_instantiate_msg();
+        msg.poll_creation_fee = Some(coin(10, "ujuno"));
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut poll = PollInput {
+            poll_id: Some("some_id".to_string()),
+            question: "Which chain?".to_string(),
+            options: vec!["Juno".to_string(), "Osmosis".to_string()],
+            series_id: None,
+            strategy: None,
+            allowlist: vec![],
+            end_time: None,
+            subject: None,
+            chambers: vec![],
+            weight_curve: None,
+            weight_cap: None,
+            basket: vec![],
+            resolver: None,
+            vote_token: None,
+            correction_window: None,
+            min_support: None,
+            group: None,
+            max_selections: None,
+            upgrade: None,
+            method: None,
+            privacy: None,
+            commit_deadline: None,
+            quorum: None,
+            veto: None,
+            finalizer: None,
+            execution: None,
+            template: None,
+            threshold: None,
+            lottery: false,
+            tie_break: None,
+            labels: vec![],
+            max_ballots: None,
+            description: None,
+            image_url: None,
+            metadata: None,
+            option_details: vec![],
+            captures: vec![],
+            tags: vec![],
+        };
+        let save = |poll: &PollInput| ExecuteMsg::SaveDraft {
+            draft_id: "draft".to_string(),
+            poll: poll.clone(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), save(&poll)).unwrap();
+        poll.question = "Which chain is best?".to_string();
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), save(&poll)).unwrap();
+
+        let msg = QueryMsg::Drafts {
+            creator: ADDR1.to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: DraftsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.drafts.len(), 1);
+        assert_eq!(res.drafts[0].poll, poll);
+
+        // Drafts are held to the poll size limits, and capped per creator
+        let mut oversized = poll.clone();
+        oversized.metadata = Some("x".repeat(MAX_METADATA_LENGTH + 1));
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), save(&oversized));
+        match res {
+            Err(ContractError::MetadataTooLong { max }) => assert_eq!(max, MAX_METADATA_LENGTH),
+            _ => panic!("Must return metadata too long error"),
+        }
+        let extra = |draft_id: String| ExecuteMsg::SaveDraft {
+            draft_id,
+            poll: poll.clone(),
+        };
+        for i in 1..MAX_DRAFTS {
+            let msg = extra(format!("extra_{}", i));
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+        let msg = extra("one_too_many".to_string());
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::TooManyDrafts { max }) => assert_eq!(max, MAX_DRAFTS),
+            _ => panic!("Must return too many drafts error"),
+        }
+        // Saving over an existing draft is still allowed
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), save(&poll)).unwrap();
+        for i in 1..MAX_DRAFTS {
+            let msg = ExecuteMsg::DeleteDraft {
+                draft_id: format!("extra_{}", i),
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+
+        // Drafts are neither votable nor listed
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "Juno"),
+        );
+        match res {
+            Err(ContractError::PollNotFound {}) => {}
+            _ => panic!("Must return poll not found error"),
+        }
+        let msg = QueryMsg::AllPolls {
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        assert!(res.polls.is_empty());
+
+        let publish = ExecuteMsg::PublishPoll {
+            draft_id: "draft".to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &coins(10, "ujuno")),
+            publish.clone(),
+        );
+        match res {
+            Err(ContractError::DraftNotFound {}) => {}
+            _ => panic!("Must return draft not found error"),
+        }
+        // The fee is only due once published
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), publish.clone());
+        match res {
+            Err(ContractError::Payment(_)) => {}
+            _ => panic!("Must return payment error"),
+        }
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &coins(10, "ujuno")),
+            publish,
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("draft_id", "draft")));
+
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(poll.question, "Which chain is best?");
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "Juno"),
+        )
+        .unwrap();
+
+        let msg = ExecuteMsg::DeleteDraft {
+            draft_id: "draft".to_string(),
+        };
+        let res = execute(deps.as_mut(), env, info, msg);
+        match res {
+            Err(ContractError::DraftNotFound {}) => {}
+            _ => panic!("Must return draft not found error"),
+        }
+    }
+
+    #[test]
+    fn test_query_option_pages() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let options = ["Juno", "Osmosis", "Stargaze", "Cosmos Hub"];
+        let msg = create_poll_msg("some_id", "Which chain?", &options);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            vote_msg("some_id", "Stargaze"),
+        )
+        .unwrap();
+
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+            option_offset: Some(1),
+            option_limit: Some(2),
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.option_count, 4);
+        assert_eq!(
+            res.poll.unwrap().tallies(),
+            vec![
+                ("Osmosis".to_string(), Uint128::zero()),
+                ("Stargaze".to_string(), Uint128::new(1)),
+            ]
+        );
+        assert_eq!(res.viable_options, vec!["Osmosis", "Stargaze"]);
+
+        let results = |option_offset: u32| QueryMsg::Results {
+            poll_id: "some_id".to_string(),
+            option_offset: Some(option_offset),
+            option_limit: Some(2),
+        };
+        let bin = query(deps.as_ref(), env.clone(), results(3)).unwrap();
+        let res: ResultsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.option_count, 4);
+        assert_eq!(
+            res.options,
+            vec![("Cosmos Hub".to_string(), Uint128::zero())]
+        );
+        // The winner is decided over every option, not just the page
+        assert_eq!(res.winner, Some("Stargaze".to_string()));
+
+        let bin = query(deps.as_ref(), env, results(10)).unwrap();
+        let res: ResultsResponse = from_binary(&bin).unwrap();
+        assert!(res.options.is_empty());
+    }
+
+    #[test]
+    fn test_migrate_v1_state() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        // State as written by a 0.1 release
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.1.0").unwrap();
+        deps.storage.set(b"config", br#"{"admin":"creator"}"#);
+        deps.storage.set(
+            &POLLS.key("legacy_id"),
+            br#"{"creator":"creator","question":"Which chain?","options":[["Juno",1],["Osmosis",0]]}"#,
+        );
+        deps.storage.set(
+            &BALLOTS.key((Addr::unchecked(ADDR2), "legacy_id")),
+            br#"{"option":"Juno"}"#,
+        );
+
+        let res = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+        assert!(res.attributes.contains(&attr("polls_upgraded", "1")));
+
+        let config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(config.admin, "creator");
+        assert_eq!(config.treasury, "creator");
+        let poll = load_poll(&deps.storage, "legacy_id").unwrap();
+        assert_eq!(poll.tallies()[0], ("Juno".to_string(), Uint128::new(1)));
+        assert_eq!(poll.participation, Uint128::new(1));
+        assert_eq!(poll.sequence, 1);
+        let ballot = BALLOTS
+            .load(&deps.storage, (Addr::unchecked(ADDR2), "legacy_id"))
+            .unwrap();
+        assert_eq!(ballot.options, vec!["Juno"]);
+        assert!(POLL_VOTERS.has(&deps.storage, ("legacy_id", &Addr::unchecked(ADDR2))));
+
+        // Upgraded polls take votes and changes like any other
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR1, &[]),
+            vote_msg("legacy_id", "Osmosis"),
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(ADDR2, &[]),
+            vote_msg("legacy_id", "Osmosis"),
+        )
+        .unwrap();
+        let poll = load_poll(&deps.storage, "legacy_id").unwrap();
+        assert_eq!(
+            poll.tallies(),
+            vec![
+                ("Juno".to_string(), Uint128::zero()),
+                ("Osmosis".to_string(), Uint128::new(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_query_feed_muted_creators() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for (creator, poll_id) in [(ADDR1, "poll_1"), (ADDR2, "poll_2"), (ADDR2, "poll_3")] {
+            let msg = create_poll_msg(poll_id, "Which chain?", &["Juno", "Osmosis"]);
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(creator, &[]), msg).unwrap();
+        }
+
+        let msg = ExecuteMsg::MuteCreator {
+            creator: ADDR2.to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("viewer", &[]), msg).unwrap();
+
+        let feed = |address: &str, start_after: Option<&str>, limit: Option<u32>| QueryMsg::Feed {
+            address: address.to_string(),
+            start_after: start_after.map(String::from),
+            limit,
+        };
+        let page = |bin: Binary| -> (Vec<String>, Option<String>) {
+            let res: FeedResponse = from_binary(&bin).unwrap();
+            let poll_ids = res.polls.into_iter().map(|p| p.poll_id).collect();
+            (poll_ids, res.next_start_after)
+        };
+        let bin = query(deps.as_ref(), env.clone(), feed("viewer", None, None)).unwrap();
+        assert_eq!(page(bin), (vec!["poll_1".to_string()], None));
+        // Muting only affects the address that muted
+        let bin = query(deps.as_ref(), env.clone(), feed(ADDR1, None, None)).unwrap();
+        assert_eq!(page(bin).0, vec!["poll_1", "poll_2", "poll_3"]);
+
+        // A full page points past itself, even if only muted polls follow
+        let bin = query(deps.as_ref(), env.clone(), feed("viewer", None, Some(1))).unwrap();
+        assert_eq!(
+            page(bin),
+            (vec!["poll_1".to_string()], Some("poll_1".to_string()))
+        );
+        let msg = feed("viewer", Some("poll_1"), Some(1));
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        assert_eq!(page(bin), (vec![], None));
+
+        let msg = QueryMsg::MutedCreators {
+            address: "viewer".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: MutedCreatorsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.creators, vec![Addr::unchecked(ADDR2)]);
+
+        // Followed creators stay hidden while muted
+        let msg = ExecuteMsg::FollowCreator {
+            creator: ADDR2.to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("viewer", &[]), msg).unwrap();
+        let followed = QueryMsg::FollowedPolls {
+            address: "viewer".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), followed.clone()).unwrap();
+        assert_eq!(page(bin), (vec![], None));
+
+        let msg = ExecuteMsg::UnmuteCreator {
+            creator: ADDR2.to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("viewer", &[]), msg).unwrap();
+        let bin = query(deps.as_ref(), env.clone(), feed("viewer", None, None)).unwrap();
+        assert_eq!(page(bin).0, vec!["poll_1", "poll_2", "poll_3"]);
+        let bin = query(deps.as_ref(), env.clone(), followed.clone()).unwrap();
+        assert_eq!(page(bin).0, vec!["poll_2", "poll_3"]);
+
+        let msg = ExecuteMsg::UnfollowCreator {
+            creator: ADDR2.to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("viewer", &[]), msg).unwrap();
+        let bin = query(deps.as_ref(), env, followed).unwrap();
+        assert_eq!(page(bin), (vec![], None));
+    }
+
+    #[test]
+    fn test_execute_pause() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = create_poll_msg("some_id", "Which chain?", &["Juno", "Osmosis"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            ExecuteMsg::Pause {},
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "Juno"),
+        );
+        match res {
+            Err(ContractError::ContractPaused {}) => {}
+            _ => panic!("Must return contract paused error"),
+        }
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::Paused {}).unwrap();
+        let res: PausedResponse = from_binary(&bin).unwrap();
+        assert!(res.paused);
+
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Unpause {},
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "Juno"),
+        )
+        .unwrap();
+
+        // Paused and read-only at once, the admin can still lift both
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::SetReadOnly { read_only: true },
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "Osmosis"),
+        );
+        match res {
+            Err(ContractError::ReadOnly {}) => {}
+            _ => panic!("Must return read only error"),
+        }
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Unpause {},
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::SetReadOnly { read_only: false },
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "Osmosis"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_query_vote_proof() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let mut msg = create_poll_msg("some_id", "Which chain?", &["Juno", "Osmosis"]);
+        if let ExecuteMsg::CreatePoll { end_time, .. } = &mut msg {
+            *end_time = Some(Expiration::AtHeight(env.block.height + 10));
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            vote_msg("some_id", "Juno"),
+        )
+        .unwrap();
+
+        let proof = |voter: &str| QueryMsg::VoteProof {
+            poll_id: "some_id".to_string(),
+            voter: voter.to_string(),
+        };
+        // Only finalized polls have a result to bind the proof to
+        query(deps.as_ref(), env.clone(), proof(ADDR1)).unwrap_err();
+
+        env.block.height += 10;
+        let msg = ExecuteMsg::FinalizePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        let bin = query(deps.as_ref(), env.clone(), proof(ADDR1)).unwrap();
+        let res: VoteProofResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.weight, Uint128::new(1));
+        assert_eq!(res.options, vec!["Juno"]);
+        let result = load_poll(&deps.storage, "some_id").unwrap().result.unwrap();
+        let result_hash = Sha256::digest(&to_vec(&result).unwrap());
+        assert_eq!(res.result_hash.as_slice(), result_hash.as_slice());
+        let record = to_vec(&(
+            &res.contract,
+            "some_id",
+            ADDR1,
+            res.weight,
+            &res.options,
+            &res.result_hash,
+        ))
+        .unwrap();
+        assert_eq!(res.proof.as_slice(), Sha256::digest(&record).as_slice());
+        query(deps.as_ref(), env.clone(), proof(ADDR2)).unwrap_err();
+
+        let verify = |voter: &str, proof: &Binary| QueryMsg::VerifyVoteProof {
+            poll_id: "some_id".to_string(),
+            voter: voter.to_string(),
+            proof: proof.clone(),
+        };
+        let bin = query(deps.as_ref(), env.clone(), verify(ADDR1, &res.proof)).unwrap();
+        let valid: VerifyVoteProofResponse = from_binary(&bin).unwrap();
+        assert!(valid.valid);
+        let bin = query(deps.as_ref(), env, verify(ADDR2, &res.proof)).unwrap();
+        let valid: VerifyVoteProofResponse = from_binary(&bin).unwrap();
+        assert!(!valid.valid);
+    }
+
+    #[test]
+    fn test_execute_freeze_poll() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = create_poll_msg("some_id", "Which chain?", &["Juno", "Osmosis"]);
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "Juno"),
+        )
+        .unwrap();
+
+        let freeze = ExecuteMsg::FreezePoll {
+            poll_id: "some_id".to_string(),
+        };
+        // Not even the creator may freeze their poll
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            freeze.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), freeze).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "Juno"),
+        );
+        match res {
+            Err(ContractError::PollFrozen {}) => {}
+            _ => panic!("Must return poll frozen error"),
+        }
+        let msg = ExecuteMsg::RevokeVote {
+            poll_id: "some_id".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg);
+        match res {
+            Err(ContractError::PollFrozen {}) => {}
+            _ => panic!("Must return poll frozen error"),
+        }
+
+        let msg = ExecuteMsg::UnfreezePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env, info, vote_msg("some_id", "Juno")).unwrap();
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert!(!poll.frozen);
+        assert_eq!(poll.options[0].tally, Uint128::new(2));
+    }
+
+    #[test]
+    fn test_execute_vote_poll_full() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let mut msg = create_poll_msg("some_id", "Coming to the meetup?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll { max_ballots, .. } = &mut msg {
+            *max_ballots = Some(0);
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidMaxBallots {}) => {}
+            _ => panic!("Must return invalid max ballots error"),
+        }
+        if let ExecuteMsg::CreatePoll { max_ballots, .. } = &mut msg {
+            *max_ballots = Some(2);
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "Yes"),
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "Yes"),
+        )
+        .unwrap();
+        let poll_query = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+            option_offset: None,
+            option_limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), poll_query.clone()).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.remaining_ballots, Some(0));
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr3", &[]),
+            vote_msg("some_id", "Yes"),
+        );
+        match res {
+            Err(ContractError::PollFull {}) => {}
+            _ => panic!("Must return poll full error"),
+        }
+        // Voters holding a slot may still change their ballot
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            vote_msg("some_id", "No"),
+        )
+        .unwrap();
+
+        // Revoking frees the slot for the next voter
+        let msg = ExecuteMsg::RevokeVote {
+            poll_id: "some_id".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+        let bin = query(deps.as_ref(), env.clone(), poll_query).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.remaining_ballots, Some(1));
+        let _res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("addr3", &[]),
+            vote_msg("some_id", "No"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_execute_create_poll_description() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let mut msg = create_poll_msg("some_id", "Which chain?", &["Juno", "Osmosis"]);
+        if let ExecuteMsg::CreatePoll { metadata, .. } = &mut msg {
+            *metadata = Some("x".repeat(MAX_METADATA_LENGTH + 1));
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::MetadataTooLong { .. }) => {}
+            _ => panic!("Must return metadata too long error"),
+        }
+
+        if let ExecuteMsg::CreatePoll {
+            description,
+            image_url,
+            metadata,
+            ..
+        } = &mut msg
+        {
+            *description = Some("Where the next hackathon takes place".to_string());
+            *image_url = Some("https://example.com/chains.png".to_string());
+            *metadata = Some(r#"{"color":"purple"}"#.to_string());
+        }
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(
+            poll.description,
+            Some("Where the next hackathon takes place".to_string())
+        );
+        assert_eq!(
+            poll.image_url,
+            Some("https://example.com/chains.png".to_string())
+        );
+        assert_eq!(poll.metadata, Some(r#"{"color":"purple"}"#.to_string()));
+    }
+
+    #[test]
+    fn test_adaptive_poll_deposit() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let mut msg = default_instantiate_msg();
+        msg.poll_deposit = Some(coin(100, "ujuno"));
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let pricing = DepositPricing {
+            window: 3600,
+            target: 1,
+            step: Decimal::percent(50),
+        };
+        let msg = ExecuteMsg::SetDepositPricing {
+            pricing: Some(DepositPricing {
+                window: 0,
+                ..pricing.clone()
+            }),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::InvalidDepositPricing {}) => {}
+            _ => panic!("Must return invalid deposit pricing error"),
+        }
+        let msg = ExecuteMsg::SetDepositPricing {
+            pricing: Some(pricing.clone()),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // The target is charged the base deposit, each poll past it half more
+        for (poll_id, deposit) in [("first", 100), ("second", 100), ("third", 150)] {
+            let msg = create_poll_msg(poll_id, "Ship it?", &["Yes", "No"]);
+            let res = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(ADDR2, &coins(deposit - 1, "ujuno")),
+                msg.clone(),
+            );
+            match res {
+                Err(ContractError::InsufficientDeposit { deposit: required }) => {
+                    assert_eq!(required, coin(deposit, "ujuno"))
+                }
+                _ => panic!("Must return insufficient deposit error"),
+            }
+            let _res = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(ADDR2, &coins(deposit, "ujuno")),
+                msg,
+            )
+            .unwrap();
+            let poll = load_poll(&deps.storage, poll_id).unwrap();
+            assert_eq!(poll.deposit, Some(coin(deposit, "ujuno")));
+        }
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::PollDeposit {}).unwrap();
+        let res: PollDepositResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.deposit, Some(coin(200, "ujuno")));
+        assert_eq!(res.recent_polls, 3);
+        assert_eq!(res.pricing, Some(pricing));
+
+        // Polls count towards the volume however they are created
+        let poll = poll_input("fourth", "Ship it?", &["Yes", "No"]);
+        create_poll(deps.as_mut(), &env, Addr::unchecked(ADDR2), poll, None).unwrap();
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::PollDeposit {}).unwrap();
+        let res: PollDepositResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.deposit, Some(coin(250, "ujuno")));
+        assert_eq!(res.recent_polls, 4);
+
+        // A scheduled change is quoted as soon as it is due
+        let msg = ExecuteMsg::ScheduleConfig {
+            unique_subjects: None,
+            admin_pubkey: None,
+            poll_creation_fee: None,
+            poll_deposit: Some(coin(200, "ujuno")),
+            treasury: None,
+            cw20_poll_creation_fee: None,
+            max_polls_per_creator: None,
+            poll_creation_cooldown: None,
+            min_options: None,
+            max_options: None,
+            max_question_len: None,
+            max_option_len: None,
+            activate_at: Scheduled::AtHeight(env.block.height + 1),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let mut due = env.clone();
+        due.block.height += 1;
+        let bin = query(deps.as_ref(), due, QueryMsg::PollDeposit {}).unwrap();
+        let res: PollDepositResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.deposit, Some(coin(500, "ujuno")));
+
+        // Once the volume has aged out the base deposit applies again
+        env.block.time = env.block.time.plus_seconds(7200);
+        let bin = query(deps.as_ref(), env, QueryMsg::PollDeposit {}).unwrap();
+        let res: PollDepositResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.deposit, Some(coin(100, "ujuno")));
+        assert_eq!(res.recent_polls, 0);
+    }
+
+    #[test]
+    fn test_execute_create_poll_option_details() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let juno = OptionDetails {
+            label: Some("Juno Network".to_string()),
+            image_url: Some("https://example.com/juno.png".to_string()),
+            link: Some("https://junonetwork.io".to_string()),
+        };
+        let mut msg = create_poll_msg("some_id", "Which chain?", &["Juno", "Osmosis"]);
+        if let ExecuteMsg::CreatePoll { option_details, .. } = &mut msg {
+            *option_details = vec![juno.clone()];
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidOptionDetails { option }) => assert_eq!(option, "Osmosis"),
+            _ => panic!("Must return invalid option details error"),
+        }
+        let osmosis = OptionDetails {
+            label: Some("x".repeat(MAX_OPTION_LABEL_LENGTH + 1)),
+            ..OptionDetails::default()
+        };
+        if let ExecuteMsg::CreatePoll { option_details, .. } = &mut msg {
+            *option_details = vec![juno.clone(), osmosis];
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidOptionDetails { option }) => assert_eq!(option, "Osmosis"),
+            _ => panic!("Must return invalid option details error"),
+        }
+        if let ExecuteMsg::CreatePoll { option_details, .. } = &mut msg {
+            *option_details = vec![juno.clone(), OptionDetails::default()];
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Details are paged along with the options they describe
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+            option_offset: Some(1),
+            option_limit: Some(1),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        let poll = res.poll.unwrap();
+        assert_eq!(
+            poll.options,
+            vec![PollOption {
+                name: "Osmosis".to_string(),
+                tally: Uint128::zero(),
+                details: OptionDetails::default(),
+            }]
+        );
+        let poll = load_poll(&deps.storage, "some_id").unwrap();
+        assert_eq!(poll.options[0].details, juno);
+    }
+
+    #[test]
+    fn test_execute_finalize_poll_captures_data() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let supply = TokenInfoResponse {
+            name: "Token".to_string(),
+            symbol: "TKN".to_string(),
+            decimals: 6,
+            total_supply: Uint128::new(1_000_000),
+        };
+        let token_info = to_binary(&supply).unwrap();
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == "token" => {
+                SystemResult::Ok(ContractResult::Ok(token_info.clone()))
+            }
+            _ => SystemResult::Ok(ContractResult::Err("unknown contract".to_string())),
+        });
+
+        let capture = |name: &str, source: DataSource| DataCapture {
+            name: name.to_string(),
+            source,
+        };
+        let mut msg = create_poll_msg("some_id", "Mint more?", &["Yes", "No"]);
+        if let ExecuteMsg::CreatePoll {
+            end_time, captures, ..
+        } = &mut msg
+        {
+            *end_time = Some(Expiration::AtHeight(env.block.height + 10));
+            *captures = vec![
+                capture(
+                    "supply",
+                    DataSource::TokenInfo {
+                        token: "token".to_string(),
+                    },
+                ),
+                capture(
+                    "supply",
+                    DataSource::GroupWeight {
+                        group: "group".to_string(),
+                    },
+                ),
+            ];
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidCapture { name }) => assert_eq!(name, "supply"),
+            _ => panic!("Must return invalid capture error"),
+        }
+        if let ExecuteMsg::CreatePoll { captures, .. } = &mut msg {
+            captures[1].name = "members".to_string();
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            vote_msg("some_id", "Yes"),
+        )
+        .unwrap();
 
-        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        env.block.height += 10;
+        let msg = ExecuteMsg::FinalizePoll {
+            poll_id: "some_id".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let result = load_poll(&deps.storage, "some_id").unwrap().result.unwrap();
+        assert_eq!(result.captured.len(), 2);
+        assert_eq!(result.captured[0].name, "supply");
+        let captured: TokenInfoResponse =
+            from_binary(result.captured[0].value.as_ref().unwrap()).unwrap();
+        assert_eq!(captured, supply);
+        // A failing query is recorded without holding up finalization
+        assert_eq!(result.captured[1].value, None);
+        assert_eq!(
+            result.captured[1].error,
+            Some("unknown contract".to_string())
+        );
+    }
 
-        assert_eq!(res.attributes[2].key, "question");
+    #[test]
+    fn test_query_polls_by_tag() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
 
-        let msg = ExecuteMsg::Vote {
-            poll_id: "random_id".to_string(),
-            vote: "Terra".to_string(),
-        };
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
-        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        let polls = [
+            ("poll_1", vec!["Governance", "treasury"]),
+            ("poll_2", vec!["memes"]),
+            ("poll_3", vec!["governance"]),
+        ];
+        for (poll_id, poll_tags) in polls.iter() {
+            let mut msg = create_poll_msg(poll_id, "Which chain?", &["Juno", "Osmosis"]);
+            if let ExecuteMsg::CreatePoll { tags, .. } = &mut msg {
+                *tags = poll_tags.iter().map(|tag| tag.to_string()).collect();
+            }
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
 
+        // Tags differing only in case are the same tag
+        let mut msg = create_poll_msg("poll_4", "Which chain?", &["Juno", "Osmosis"]);
+        if let ExecuteMsg::CreatePoll { tags, .. } = &mut msg {
+            *tags = vec!["memes".to_string(), "Memes".to_string()];
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
         match res {
-            Err(ContractError::OptionNotFound {}) => {}
-            _ => panic!("Must return option not found error"),
+            Err(ContractError::InvalidTag { tag }) => assert_eq!(tag, "Memes"),
+            _ => panic!("Must return invalid tag error"),
         }
+
+        let by_tag = |tag: &str| QueryMsg::PollsByTag {
+            tag: tag.to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), by_tag("GOVERNANCE")).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let ids: Vec<_> = res.polls.iter().map(|p| p.poll_id.as_str()).collect();
+        assert_eq!(ids, vec!["poll_1", "poll_3"]);
+        assert_eq!(res.polls[0].poll.tags, vec!["governance", "treasury"]);
+
+        // Deleted polls leave the index
+        let msg = ExecuteMsg::DeletePoll {
+            poll_id: "poll_1".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let bin = query(deps.as_ref(), env, by_tag("governance")).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.polls.len(), 1);
+        assert_eq!(res.polls[0].poll_id, "poll_3");
     }
 
     #[test]
-    fn test_query_all_polls() {
+    fn test_query_polls_by_creator() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let info = mock_info(ADDR1, &vec![]);
+        let info = mock_info(ADDR1, &[]);
 
-        let msg = InstantiateMsg { admin: None };
+        let msg = default_instantiate_msg();
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for (poll_id, creator) in [("poll_1", ADDR1), ("poll_2", ADDR2), ("poll_3", ADDR1)] {
+            let msg = create_poll_msg(poll_id, "Which chain?", &["Juno", "Osmosis"]);
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(creator, &[]), msg).unwrap();
+        }
 
-        let msg = QueryMsg::AllPolls {};
-        let binary = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let by_creator = |start_after: Option<&str>| QueryMsg::PollsByCreator {
+            creator: ADDR1.to_string(),
+            start_after: start_after.map(String::from),
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env.clone(), by_creator(None)).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let ids: Vec<_> = res.polls.iter().map(|p| p.poll_id.as_str()).collect();
+        assert_eq!(ids, vec!["poll_1", "poll_3"]);
+        let bin = query(deps.as_ref(), env.clone(), by_creator(Some("poll_1"))).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.polls.len(), 1);
+        assert_eq!(res.polls[0].poll_id, "poll_3");
 
-        let res: AllPollsResponse = from_binary(&binary).unwrap();
+        // Deleted polls leave the index
+        let msg = ExecuteMsg::DeletePoll {
+            poll_id: "poll_3".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-        assert_eq!(res.polls.len(), 0);
+        // Polls created before the index are added to it by the migration
+        CREATOR_POLLS.remove(&mut deps.storage, (&Addr::unchecked(ADDR1), "poll_1"));
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.2.0").unwrap();
+        let res = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+        assert!(res.attributes.contains(&attr("creators_indexed", "2")));
+        let bin = query(deps.as_ref(), env, by_creator(None)).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let ids: Vec<_> = res.polls.iter().map(|p| p.poll_id.as_str()).collect();
+        assert_eq!(ids, vec!["poll_1"]);
+    }
 
-        let msg = ExecuteMsg::CreatePoll {
-            poll_id: "some_id_1".to_string(),
-            question: "What is your favorite Cosmos coin?".to_string(),
-            options: vec![
-                "Cosmos Hub".to_string(),
-                "Juno".to_string(),
-                "Osmosis".to_string(),
-            ],
+    #[test]
+    fn test_query_schema() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = QueryMsg::Schema {
+            kind: SchemaKind::Execute,
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: SchemaResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.kind, SchemaKind::Execute);
+        assert_eq!(res.version, env!("CARGO_PKG_VERSION"));
+        let schema = String::from_utf8(res.schema.to_vec()).unwrap();
+        assert!(schema.starts_with(r#"{"$schema":"#));
+        assert!(!schema.contains('\n'));
+        // Whitespace inside strings is kept
+        assert!(schema.contains(r#""title":"ExecuteMsg""#));
+        assert!(schema.contains("Saves a poll in preparation"));
+    }
 
-        let msg = ExecuteMsg::CreatePoll {
-            poll_id: "some_id_2".to_string(),
-            question: "What's your favorite color?".to_string(),
-            options: vec!["Red".to_string(), "Green".to_string(), "Blue".to_string()],
+    #[test]
+    fn test_query_latest_polls() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        // Ids sort differently from creation order
+        for poll_id in ["b_poll", "c_poll", "a_poll"] {
+            env.block.height += 1;
+            env.block.time = env.block.time.plus_seconds(5);
+            let msg = create_poll_msg(poll_id, "Which chain?", &["Juno", "Osmosis"]);
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+        let poll = load_poll(&deps.storage, "a_poll").unwrap();
+        assert_eq!(
+            poll.created_at,
+            Some(BlockStamp {
+                height: env.block.height,
+                time: env.block.time,
+            })
+        );
+
+        let latest = |start_after: Option<u64>| QueryMsg::LatestPolls {
+            start_after,
+            limit: Some(2),
+        };
+        let bin = query(deps.as_ref(), env.clone(), latest(None)).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let ids: Vec<_> = res.polls.iter().map(|p| p.poll_id.as_str()).collect();
+        assert_eq!(ids, vec!["a_poll", "c_poll"]);
+        let cursor = res.polls[1].poll.sequence;
+        let bin = query(deps.as_ref(), env.clone(), latest(Some(cursor))).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let ids: Vec<_> = res.polls.iter().map(|p| p.poll_id.as_str()).collect();
+        assert_eq!(ids, vec!["b_poll"]);
+
+        // Deleted polls leave the listing, but can still be paged past
+        let msg = ExecuteMsg::DeletePoll {
+            poll_id: "c_poll".to_string(),
         };
         let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let bin = query(deps.as_ref(), env.clone(), latest(None)).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let ids: Vec<_> = res.polls.iter().map(|p| p.poll_id.as_str()).collect();
+        assert_eq!(ids, vec!["a_poll", "b_poll"]);
+        let bin = query(deps.as_ref(), env, latest(Some(cursor))).unwrap();
+        let res: AllPollsResponse = from_binary(&bin).unwrap();
+        let ids: Vec<_> = res.polls.iter().map(|p| p.poll_id.as_str()).collect();
+        assert_eq!(ids, vec!["b_poll"]);
+    }
 
-        let msg = QueryMsg::AllPolls {};
-        let binary = query(deps.as_ref(), env, msg).unwrap();
+    #[test]
+    fn test_query_poll_summaries() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
 
-        let res: AllPollsResponse = from_binary(&binary).unwrap();
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let mut msg = create_poll_msg("poll_1", "Which chain?", &["Juno", "Osmosis"]);
+        if let ExecuteMsg::CreatePoll { end_time, .. } = &mut msg {
+            *end_time = Some(Expiration::AtHeight(env.block.height + 10));
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let mut msg = create_poll_msg("poll_2", "Which validator?", &["A", "B"]);
+        if let ExecuteMsg::CreatePoll { max_ballots, .. } = &mut msg {
+            *max_ballots = Some(5);
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = vote_msg("poll_2", "A");
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for voter in [ADDR1, ADDR2] {
+            let msg = vote_msg("poll_1", "Juno");
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
 
-        assert_eq!(res.polls.len(), 2);
+        env.block.height += 10;
+        let msg = QueryMsg::PollSummaries {
+            start_after: None,
+            limit: None,
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: PollSummariesResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.polls,
+            vec![
+                PollSummary {
+                    poll_id: "poll_1".to_string(),
+                    question: "Which chain?".to_string(),
+                    status: PollStatus::Closed,
+                    total_votes: Uint128::new(2),
+                    end_time: Expiration::AtHeight(mock_env().block.height + 10),
+                    remaining_ballots: None,
+                },
+                PollSummary {
+                    poll_id: "poll_2".to_string(),
+                    question: "Which validator?".to_string(),
+                    status: PollStatus::Open,
+                    total_votes: Uint128::new(1),
+                    end_time: Expiration::Never {},
+                    remaining_ballots: Some(4),
+                },
+            ]
+        );
     }
 
     #[test]
-    fn test_query_poll() {
+    fn test_query_stats() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let info = mock_info(ADDR1, &vec![]);
+        let info = mock_info(ADDR1, &[]);
 
-        let msg = InstantiateMsg { admin: None };
+        let msg = default_instantiate_msg();
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for poll_id in ["poll_1", "poll_2"] {
+            let msg = create_poll_msg(poll_id, "Which chain?", &["Juno", "Osmosis"]);
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+        // Changing a ballot doesn't count as another vote
+        let votes = [
+            (ADDR1, "poll_1", "Juno"),
+            (ADDR1, "poll_1", "Osmosis"),
+            (ADDR1, "poll_2", "Juno"),
+            (ADDR2, "poll_2", "Juno"),
+        ];
+        for (voter, poll_id, option) in votes {
+            let msg = vote_msg(poll_id, option);
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
 
-        let msg = ExecuteMsg::CreatePoll {
-            poll_id: "some_id_1".to_string(),
-            question: "What's your favorite Cosmos coin?".to_string(),
-            options: vec![
-                "Cosmos Hub".to_string(),
-                "Juno".to_string(),
-                "Osmosis".to_string(),
-            ],
+        let expected = Stats {
+            polls_created: 2,
+            votes_cast: 3,
+            unique_voters: 2,
         };
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::Stats {}).unwrap();
+        let res: StatsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.stats, expected);
 
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        // Deployments predating the counters have them seeded when migrating
+        STATS.remove(&mut deps.storage);
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.2.0").unwrap();
+        let _res = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+        let bin = query(deps.as_ref(), env, QueryMsg::Stats {}).unwrap();
+        let res: StatsResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.stats, expected);
+    }
 
-        let msg = QueryMsg::Poll {
-            poll_id: "some_id_1".to_string(),
+    #[test]
+    fn test_query_top_voters() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for poll_id in ["poll_1", "poll_2", "poll_3"] {
+            let msg = create_poll_msg(poll_id, "Which chain?", &["Juno", "Osmosis"]);
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+        let votes = [
+            ("addr1", "poll_1"),
+            ("addr1", "poll_2"),
+            ("addr1", "poll_3"),
+            ("addr2", "poll_1"),
+            ("addr2", "poll_2"),
+            ("addr3", "poll_1"),
+            // Changes don't count as more ballots
+            ("addr3", "poll_1"),
+        ];
+        for (voter, poll_id) in votes {
+            let msg = vote_msg(poll_id, "Juno");
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), msg).unwrap();
+        }
+
+        let top = |deps: Deps| -> Vec<(Addr, u64)> {
+            let msg = QueryMsg::TopVoters { limit: Some(2) };
+            let bin = query(deps, mock_env(), msg).unwrap();
+            let res: TopVotersResponse = from_binary(&bin).unwrap();
+            res.voters
         };
+        assert_eq!(
+            top(deps.as_ref()),
+            vec![(Addr::unchecked("addr1"), 3), (Addr::unchecked("addr2"), 2)]
+        );
 
-        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
-        let res: PollResponse = from_binary(&bin).unwrap();
-        assert!(res.poll.is_some());
+        // Revoked ballots are taken off the count
+        for poll_id in ["poll_1", "poll_2"] {
+            let msg = ExecuteMsg::RevokeVote {
+                poll_id: poll_id.to_string(),
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info("addr1", &[]), msg).unwrap();
+        }
+        assert_eq!(
+            top(deps.as_ref()),
+            vec![(Addr::unchecked("addr2"), 2), (Addr::unchecked("addr3"), 1)]
+        );
 
-        let msg = QueryMsg::Poll {
-            poll_id: "id_does_not_exist".to_string(),
+        // The ranking is rebuilt from the ballots held when migrating
+        VOTE_RANKING.remove(&mut deps.storage, (2, &Addr::unchecked("addr2")));
+        VOTE_COUNTS.remove(&mut deps.storage, &Addr::unchecked("addr2"));
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.2.0").unwrap();
+        let _res = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+        assert_eq!(
+            top(deps.as_ref()),
+            vec![(Addr::unchecked("addr2"), 2), (Addr::unchecked("addr3"), 1)]
+        );
+
+        // So are ballots removed with their poll or from an allowlist
+        let msg = ExecuteMsg::DeletePoll {
+            poll_id: "poll_2".to_string(),
         };
-        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
-        let res: PollResponse = from_binary(&bin).unwrap();
-        assert!(res.poll.is_none());
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let mut msg = create_poll_msg("poll_4", "Which chain?", &["Juno", "Osmosis"]);
+        if let ExecuteMsg::CreatePoll {
+            strategy,
+            allowlist,
+            ..
+        } = &mut msg
+        {
+            *strategy = Some(VotingStrategy::Allowlist {});
+            *allowlist = vec![Member {
+                address: "addr3".to_string(),
+                weight: 1,
+            }];
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = vote_msg("poll_4", "Juno");
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("addr3", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateAllowlist {
+            poll_id: "poll_4".to_string(),
+            add: vec![],
+            remove: vec!["addr3".to_string()],
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let count = |deps: Deps, voter: &str| {
+            VOTE_COUNTS
+                .may_load(deps.storage, &Addr::unchecked(voter))
+                .unwrap()
+        };
+        assert_eq!(count(deps.as_ref(), "addr1"), Some(1));
+        assert_eq!(count(deps.as_ref(), "addr2"), Some(1));
+        assert_eq!(count(deps.as_ref(), "addr3"), Some(1));
     }
 
     #[test]
-    fn test_query_vote() {
+    fn test_execute_create_polls() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let info = mock_info(ADDR1, &vec![]);
+        let info = mock_info(ADDR1, &[]);
 
-        let msg = InstantiateMsg { admin: None };
-        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let mut msg = default_instantiate_msg();
+        msg.poll_deposit = Some(coin(100, "ujuno"));
+        msg.poll_creation_fee = Some(coin(10, "uatom"));
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-        let msg = ExecuteMsg::CreatePoll {
-            poll_id: "some_id_1".to_string(),
-            question: "What's your favorite Cosmos coin?".to_string(),
-            options: vec![
-                "Cosmos Hub".to_string(),
-                "Juno".to_string(),
-                "Osmosis".to_string(),
+        // The failing entry is named
+        let msg = ExecuteMsg::CreatePolls {
+            polls: vec![
+                poll_input("poll_1", "Which chain?", &["Juno", "Osmosis"]),
+                poll_input("poll_2", "Which chain?", &["Juno"; 11]),
             ],
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let funds = [coin(200, "ujuno"), coin(20, "uatom")];
+        let res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &funds), msg);
+        match res {
+            Err(ContractError::BatchItemFailed { index, error }) => {
+                assert_eq!(index, 1);
+                assert!(matches!(*error, ContractError::TooManyOptions { .. }));
+            }
+            _ => panic!("Must return batch item failed error"),
+        }
 
-        let msg = ExecuteMsg::Vote {
-            poll_id: "some_id_1".to_string(),
-            vote: "Juno".to_string(),
+        let msg = ExecuteMsg::CreatePolls {
+            polls: vec![
+                poll_input("poll_3", "Which chain?", &["Juno", "Osmosis"]),
+                poll_input("poll_4", "Which validator?", &["A", "B"]),
+            ],
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[coin(150, "ujuno"), coin(20, "uatom")]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::BatchItemFailed { index, error }) => {
+                assert_eq!(index, 1);
+                assert!(matches!(*error, ContractError::InsufficientDeposit { .. }));
+            }
+            _ => panic!("Must return batch item failed error"),
+        }
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[coin(250, "ujuno"), coin(20, "uatom")]),
+            ExecuteMsg::CreatePolls {
+                polls: vec![
+                    poll_input("poll_5", "Which chain?", &["Juno", "Osmosis"]),
+                    poll_input("poll_6", "Which validator?", &["A", "B"]),
+                ],
+            },
+        );
+        match res {
+            Err(ContractError::UnexpectedFunds {}) => {}
+            _ => panic!("Must return unexpected funds error"),
+        }
 
-        let msg = QueryMsg::Vote {
-            poll_id: "some_id_1".to_string(),
-            address: ADDR1.to_string(),
+        let msg = ExecuteMsg::CreatePolls {
+            polls: vec![
+                poll_input("poll_7", "Which chain?", &["Juno", "Osmosis"]),
+                poll_input("poll_8", "Which validator?", &["A", "B"]),
+            ],
         };
+        let res = execute(deps.as_mut(), env, mock_info(ADDR2, &funds), msg).unwrap();
+        let created: CreatePollsResponse = from_binary(&res.data.unwrap()).unwrap();
+        assert_eq!(created.poll_ids, vec!["poll_7", "poll_8"]);
+        for poll_id in created.poll_ids.iter() {
+            let poll = load_poll(&deps.storage, poll_id).unwrap();
+            assert_eq!(poll.creator, ADDR2);
+            assert_eq!(poll.deposit, Some(coin(100, "ujuno")));
+        }
+    }
 
-        let bin = query(deps.as_ref(), env.clone(), msg).unwrap();
-        let res: VoteResponse = from_binary(&bin).unwrap();
-        assert!(res.vote.is_some());
+    #[test]
+    fn test_execute_create_poll_max_polls_per_creator() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
 
-        let msg = QueryMsg::Vote {
-            poll_id: "some_id_2".to_string(),
-            address: ADDR2.to_string(),
-        };
-        let bin = query(deps.as_ref(), env, msg).unwrap();
-        let res: VoteResponse = from_binary(&bin).unwrap();
-        assert!(res.vote.is_none());
+        let mut msg = default_instantiate_msg();
+        msg.max_polls_per_creator = Some(2);
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mut msg = create_poll_msg("poll_1", "Which chain?", &["Juno", "Osmosis"]);
+        if let ExecuteMsg::CreatePoll { end_time, .. } = &mut msg {
+            *end_time = Some(Expiration::AtTime(env.block.time.plus_seconds(100)));
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = create_poll_msg("poll_2", "Which validator?", &["A", "B"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg("poll_3", "Which DEX?", &["A", "B"]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::TooManyPolls { max }) => assert_eq!(max, 2),
+            _ => panic!("Must return too many polls error"),
+        }
+        // Other creators have their own allowance
+        let msg = create_poll_msg("poll_4", "Which DEX?", &["A", "B"]);
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        // Expired polls no longer count
+        env.block.time = env.block.time.plus_seconds(200);
+        let msg = create_poll_msg("poll_5", "Which DEX?", &["A", "B"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = create_poll_msg("poll_6", "Which wallet?", &["A", "B"]);
+        let res = execute(deps.as_mut(), env, info, msg);
+        match res {
+            Err(ContractError::TooManyPolls { .. }) => {}
+            _ => panic!("Must return too many polls error"),
+        }
     }
 
     #[test]
-    fn test_query_config() {
+    fn test_execute_create_poll_cooldown() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let mut msg = default_instantiate_msg();
+        msg.poll_creation_cooldown = Some(60);
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg("poll_1", "Which chain?", &["Juno", "Osmosis"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg("poll_2", "Which validator?", &["A", "B"]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::CreationCooldown { available_at }) => {
+                assert_eq!(available_at, env.block.time.plus_seconds(60))
+            }
+            _ => panic!("Must return creation cooldown error"),
+        }
+        // Other creators are not held back
+        let msg = create_poll_msg("poll_3", "Which validator?", &["A", "B"]);
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(ADDR2, &[]), msg).unwrap();
+
+        env.block.time = env.block.time.plus_seconds(60);
+        let msg = create_poll_msg("poll_4", "Which validator?", &["A", "B"]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Nothing is recorded while no cooldown is configured
+        let mut deps = mock_dependencies();
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = create_poll_msg("poll_1", "Which chain?", &["Juno", "Osmosis"]);
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert!(!LAST_CREATED.has(&deps.storage, &Addr::unchecked(ADDR1)));
+    }
+
+    #[test]
+    fn test_execute_create_poll_option_limits() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let info = mock_info("config_address", &vec![]);
+        let info = mock_info(ADDR1, &[]);
 
-        let msg = InstantiateMsg { admin: None };
+        let mut msg = default_instantiate_msg();
+        msg.min_options = Some(3);
+        msg.max_options = Some(2);
+        let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidOptionLimits {}) => {}
+            _ => panic!("Must return invalid option limits error"),
+        }
+        msg.max_options = Some(4);
         let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
-        let msg = ExecuteMsg::CreatePoll {
-            poll_id: "some_id_1".to_string(),
-            question: "What's your favorite Cosmos coin?".to_string(),
-            options: vec![
-                "Cosmos Hub".to_string(),
-                "Juno".to_string(),
-                "Osmosis".to_string(),
-            ],
-        };
+        let msg = create_poll_msg("poll_1", "Which chain?", &["Juno", "Osmosis"]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::TooFewOptions { min }) => assert_eq!(min, 3),
+            _ => panic!("Must return too few options error"),
+        }
+        let msg = create_poll_msg("poll_2", "Which chain?", &["A", "B", "C", "D", "E"]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::TooManyOptions { max }) => assert_eq!(max, 4),
+            _ => panic!("Must return too many options error"),
+        }
+        let msg = create_poll_msg("poll_3", "Which chain?", &["Juno", "Osmosis", "Cosmos"]);
         let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
-        let msg = QueryMsg::GetConfig {};
-        let bin = query(deps.as_ref(), env, msg).unwrap();
-        let res: ConfigResponse = from_binary(&bin).unwrap();
+        // Changes are checked against the limits they leave in place
+        let update =
+            |min_options: Option<u32>, max_options: Option<u32>| ExecuteMsg::UpdateConfig {
+                unique_subjects: None,
+                admin_pubkey: None,
+                poll_creation_fee: None,
+                poll_deposit: None,
+                treasury: None,
+                cw20_poll_creation_fee: None,
+                max_polls_per_creator: None,
+                poll_creation_cooldown: None,
+                min_options,
+                max_options,
+                max_question_len: None,
+                max_option_len: None,
+            };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            update(Some(5), None),
+        );
+        match res {
+            Err(ContractError::InvalidOptionLimits {}) => {}
+            _ => panic!("Must return invalid option limits error"),
+        }
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            update(Some(0), None),
+        );
+        match res {
+            Err(ContractError::InvalidOptionLimits {}) => {}
+            _ => panic!("Must return invalid option limits error"),
+        }
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            update(Some(1), Some(12)),
+        )
+        .unwrap();
 
-        assert_eq!(res.config.admin, "config_address");
+        let options: Vec<String> = (0..12).map(|i| format!("Option {}", i)).collect();
+        let options: Vec<&str> = options.iter().map(String::as_str).collect();
+        let msg = create_poll_msg("poll_4", "Which chain?", &options);
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+    }
+
+    #[test]
+    fn test_execute_create_poll_invalid_options() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let msg = default_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg("poll_1", "Which chain?", &["Juno", " "]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::EmptyOption {}) => {}
+            _ => panic!("Must return empty option error"),
+        }
+        let msg = create_poll_msg("poll_2", "Which chain?", &["Juno", ""]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::EmptyOption {}) => {}
+            _ => panic!("Must return empty option error"),
+        }
+
+        let msg = create_poll_msg("poll_3", "Which chain?", &["Juno", "Osmosis", "Juno"]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::DuplicateOption { option }) => assert_eq!(option, "Juno"),
+            _ => panic!("Must return duplicate option error"),
+        }
+        // Case and surrounding whitespace are ignored
+        let msg = create_poll_msg("poll_4", "Which chain?", &["Juno", "juno "]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::DuplicateOption { option }) => assert_eq!(option, "juno "),
+            _ => panic!("Must return duplicate option error"),
+        }
+
+        let msg = create_poll_msg("poll_5", "Which chain?", &["Juno", "Juno Network"]);
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+    }
+
+    #[test]
+    fn test_execute_create_poll_length_limits() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        let mut msg = default_instantiate_msg();
+        msg.max_question_len = Some(0);
+        let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::InvalidLengthLimits {}) => {}
+            _ => panic!("Must return invalid length limits error"),
+        }
+        msg.max_question_len = Some(12);
+        msg.max_option_len = Some(7);
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = create_poll_msg("poll_1", "Which chain??", &["Juno", "Osmosis"]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::QuestionTooLong { max }) => assert_eq!(max, 12),
+            _ => panic!("Must return question too long error"),
+        }
+        let msg = create_poll_msg("poll_2", "Which chain?", &["Juno", "Osmosis", "Cosmos Hub"]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::OptionTooLong { option, max }) => {
+                assert_eq!(option, "Cosmos Hub");
+                assert_eq!(max, 7);
+            }
+            _ => panic!("Must return option too long error"),
+        }
+
+        let msg = create_poll_msg("poll_3", "Which chain?", &["Juno", "Osmosis"]);
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
     }
 }
@@ -1,4 +1,5 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Coin, StdError, Timestamp, Uint128};
+use cw_utils::PaymentError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,15 +7,371 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("Semver parsing error: {0}")]
+    SemVer(String),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("Poll creation requires a fee of {fee}")]
+    InsufficientFee { fee: Coin },
+
+    #[error("Not enough collected fees to withdraw")]
+    InsufficientCollectedFees {},
+
+    #[error("Scheduled changes must activate in the future")]
+    InvalidSchedule {},
+
+    #[error("A backup finalizer needs a poll with an end time")]
+    InvalidFinalizer {},
+
+    #[error("Poll has no resolver")]
+    ResolverNotSet {},
+
+    #[error("Poll outcome already resolved")]
+    AlreadyResolved {},
+
+    #[error("Poll creation requires exactly {amount} of cw20 token {address}")]
+    IncorrectCw20Fee { address: String, amount: Uint128 },
+
+    #[error("Token is not accepted for poll creation fees")]
+    UnexpectedToken {},
+
+    #[error("This message does not accept funds")]
+    UnexpectedFunds {},
+
     #[error("Unauthorized")]
     Unauthorized {},
 
-    #[error("Too many poll options")]
-    TooManyOptions {},
+    #[error("Too many poll options, at most {max} are allowed")]
+    TooManyOptions { max: u32 },
+
+    #[error("Too few poll options, at least {min} are required")]
+    TooFewOptions { min: u32 },
+
+    #[error("Question exceeds {max} bytes")]
+    QuestionTooLong { max: u32 },
+
+    #[error("Option {option} exceeds {max} bytes")]
+    OptionTooLong { option: String, max: u32 },
+
+    #[error("Poll options may not be blank")]
+    EmptyOption {},
+
+    #[error("Option {option} is listed more than once")]
+    DuplicateOption { option: String },
+
+    #[error("Option limits must be at least 1, with the minimum no greater than the maximum")]
+    InvalidOptionLimits {},
+
+    #[error("Question and option length limits must be at least 1")]
+    InvalidLengthLimits {},
+
+    #[error("Too many poll labels, at most {max} are allowed")]
+    TooManyLabels { max: usize },
+
+    #[error("Invalid label {key}")]
+    InvalidLabel { key: String },
+
+    #[error("Too many open polls, at most {max} are allowed per creator")]
+    TooManyPolls { max: u64 },
+
+    #[error("Polls may not be created again before {available_at}")]
+    CreationCooldown { available_at: Timestamp },
+
+    #[error("Too many poll tags, at most {max} are allowed")]
+    TooManyTags { max: usize },
+
+    #[error("Invalid tag {tag}")]
+    InvalidTag { tag: String },
 
     #[error("Poll not found")]
     PollNotFound {},
 
+    #[error("Poll {poll_id} already exists")]
+    PollAlreadyExists { poll_id: String },
+
     #[error("Option not found")]
     OptionNotFound {},
+
+    #[error("Poll {poll_id} is already open for this subject")]
+    SubjectInUse { poll_id: String },
+
+    #[error("Poll is closed")]
+    PollClosed {},
+
+    #[error("Poll is frozen")]
+    PollFrozen {},
+
+    #[error("Poll has reached its maximum number of ballots")]
+    PollFull {},
+
+    #[error("Poll is not open")]
+    PollNotOpen {},
+
+    #[error("Poll is still open")]
+    PollStillOpen {},
+
+    #[error("Contract is in read-only mode")]
+    ReadOnly {},
+
+    #[error("Contract is paused")]
+    ContractPaused {},
+
+    #[error("Reputation scale must be above zero")]
+    InvalidReputationParams {},
+
+    #[error("Deposit pricing window must be above zero")]
+    InvalidDepositPricing {},
+
+    #[error("Poll strategy checks eligibility without querying other contracts")]
+    PrecheckNotNeeded {},
+
+    #[error("Poll has already been finalized")]
+    AlreadyFinalized {},
+
+    #[error("No randomness proxy is set to draw from")]
+    RandomnessUnavailable {},
+
+    #[error("Poll is waiting for its draw")]
+    DrawPending {},
+
+    #[error("No draw was requested for this poll")]
+    NoPendingDraw {},
+
+    #[error("Invalid randomness")]
+    InvalidRandomness {},
+
+    #[error("Poll has not been finalized yet")]
+    NotFinalized {},
+
+    #[error("Poll has already been annulled")]
+    PollAnnulled {},
+
+    #[error("Poll end time has already passed")]
+    InvalidEndTime {},
+
+    #[error("Option has been retired")]
+    OptionRetired {},
+
+    #[error("Ballot not found")]
+    BallotNotFound {},
+
+    #[error("Poll does not use an allowlist")]
+    AllowlistNotEnabled {},
+
+    #[error("Poll does not use chambers")]
+    ChambersNotEnabled {},
+
+    #[error("Chamber {name} is defined more than once")]
+    DuplicateChamber { name: String },
+
+    #[error("Weight cap must be positive and at most 100%")]
+    InvalidWeightCap {},
+
+    #[error("Basket denoms must be unique with a positive multiplier")]
+    InvalidBasket {},
+
+    #[error("Poll does not accept coin baskets")]
+    BasketNotEnabled {},
+
+    #[error("Upgrade polls need two options (approval first), an end time and a timelock in the same unit")]
+    InvalidUpgrade {},
+
+    #[error("Poll does not propose an upgrade")]
+    NotAnUpgrade {},
+
+    #[error("Upgrade was not approved")]
+    UpgradeRejected {},
+
+    #[error("Upgrade timelock has not passed")]
+    UpgradeTimelocked {},
+
+    #[error("Upgrade was already executed")]
+    UpgradeExecuted {},
+
+    #[error("Binding polls need messages to execute and a pass option among their options")]
+    InvalidExecution {},
+
+    #[error("Poll carries no messages to execute")]
+    NotBinding {},
+
+    #[error("Poll did not pass")]
+    PollNotPassed {},
+
+    #[error("Poll messages were already executed")]
+    PollExecuted {},
+
+    #[error("Template name is empty or too long, or the export is malformed")]
+    InvalidTemplate {},
+
+    #[error("Template not found")]
+    TemplateNotFound {},
+
+    #[error("Draft not found")]
+    DraftNotFound {},
+
+    #[error("Too many drafts, at most {max} are allowed per creator")]
+    TooManyDrafts { max: usize },
+
+    #[error("Space info is empty or exceeds its size limits")]
+    InvalidSpaceInfo {},
+
+    #[error("Quadratic polls need a positive credit budget")]
+    InvalidCredits {},
+
+    #[error("Vote amounts must be positive, one per selected option, and only in quadratic polls")]
+    InvalidVoteAmounts {},
+
+    #[error("Ballot costs more than the {credits} credits available")]
+    InsufficientCredits { credits: u64 },
+
+    #[error("Every remaining option must be scored from 0 to {max}")]
+    InvalidScores { max: u64 },
+
+    #[error("Poll requires committing to a ballot and revealing it")]
+    CommitRevealRequired {},
+
+    #[error("Poll does not use commit-reveal voting")]
+    NotCommitReveal {},
+
+    #[error("Commit phase is over")]
+    CommitPhaseOver {},
+
+    #[error("Ballots can't be revealed before the commit phase is over")]
+    CommitPhaseActive {},
+
+    #[error("No commitment found")]
+    CommitmentNotFound {},
+
+    #[error("Revealed ballot does not match the commitment")]
+    InvalidReveal {},
+
+    #[error("Quorum must be at most 100% and needs an allowlist or registry poll")]
+    InvalidQuorum {},
+
+    #[error("Message kind is not permitted in polls")]
+    MsgNotPermitted {},
+
+    #[error("Poll creation requires a deposit of {deposit}")]
+    InsufficientDeposit { deposit: Coin },
+
+    #[error("A deposit of {deposit} can't be paid through a token transfer")]
+    DepositNotPayable { deposit: Coin },
+
+    #[error(
+        "Veto option must be one of the poll's options, with a threshold above 0% and at most 100%"
+    )]
+    InvalidVeto {},
+
+    #[error("Poll has no deposit to settle")]
+    NoDeposit {},
+
+    #[error("At least one option must be selected")]
+    EmptySelection {},
+
+    #[error("Option {option} is selected more than once")]
+    DuplicateSelection { option: String },
+
+    #[error("At most {max} options may be selected")]
+    TooManySelections { max: u32 },
+
+    #[error("Max selections must be between 1 and the number of options")]
+    InvalidMaxSelections {},
+
+    #[error("Max ballots must be greater than zero")]
+    InvalidMaxBallots {},
+
+    #[error("Ballot can no longer be changed")]
+    BallotLocked {},
+
+    #[error("Poll does not weigh votes by token balance")]
+    VoteTokenNotEnabled {},
+
+    #[error("Token-weighted polls need a vote token")]
+    MissingVoteToken {},
+
+    #[error("Poll does not take its voters from a group")]
+    GroupNotEnabled {},
+
+    #[error("Group polls need a cw4 group")]
+    MissingGroup {},
+
+    #[error("Denom {denom} is not accepted by this poll")]
+    DenomNotAccepted { denom: String },
+
+    #[error("Threshold must be between 0 and 1")]
+    InvalidThreshold {},
+
+    #[error("No admin transfer is pending")]
+    NoPendingAdmin {},
+
+    #[error("Signed poll creation is not enabled")]
+    SignedCreationDisabled {},
+
+    #[error("Invalid signature")]
+    InvalidSignature {},
+
+    #[error("Nonce {nonce} has already been used")]
+    NonceUsed { nonce: u64 },
+
+    #[error("Not eligible to vote on this poll")]
+    NotEligible {},
+
+    #[error("Vote weight must be greater than zero")]
+    InvalidWeight {},
+
+    #[error("Memo exceeds {max} bytes")]
+    MemoTooLong { max: usize },
+
+    #[error("Description exceeds {max} bytes")]
+    DescriptionTooLong { max: usize },
+
+    #[error("Image URL exceeds {max} bytes")]
+    ImageUrlTooLong { max: usize },
+
+    #[error("Metadata exceeds {max} bytes")]
+    MetadataTooLong { max: usize },
+
+    #[error("Invalid details for option {option}")]
+    InvalidOptionDetails { option: String },
+
+    #[error("Batches create at most {max} polls")]
+    BatchTooLarge { max: usize },
+
+    #[error("Poll {index} of the batch failed: {error}")]
+    BatchItemFailed {
+        index: usize,
+        error: Box<ContractError>,
+    },
+
+    #[error("Invalid data capture {name}")]
+    InvalidCapture { name: String },
+
+    #[error("Polls capture at most {max} data points")]
+    TooManyCaptures { max: usize },
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
+    #[error("No voters left to credit on this poll")]
+    NothingToCredit {},
+
+    #[error("Nothing left to prune for this poll")]
+    NothingToPrune {},
+
+    #[error("Cannot migrate from contract {previous_contract}")]
+    CannotMigrate { previous_contract: String },
+
+    #[error("Cannot migrate from version {previous_version} to {new_version}")]
+    CannotMigrateVersion {
+        previous_version: String,
+        new_version: String,
+    },
+}
+
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        Self::SemVer(err.to_string())
+    }
 }
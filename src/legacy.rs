@@ -0,0 +1,245 @@
+//! Storage layouts of earlier releases, read when migrating to the current one.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Addr, BlockInfo, Empty, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map};
+use cw_utils::Expiration;
+
+use crate::contract::save_poll;
+use crate::state::{
+    Ballot, Chamber, Config, OptionDetails, Poll, PollOption, PollStatus, Privacy, StoredPoll,
+    TieBreak, VotingMethod, VotingStrategy, WeightCurve, BALLOTS, CONFIG, DEFAULT_MAX_OPTIONS,
+    DEFAULT_MAX_OPTION_LEN, DEFAULT_MAX_QUESTION_LEN, DEFAULT_MIN_OPTIONS, POLLS, POLL_SEQUENCE,
+    POLL_TOTALS, POLL_VOTERS, TALLIES,
+};
+
+/// Config as stored by 0.1 releases, before fees, subjects and the treasury.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigV1 {
+    pub admin: Addr,
+}
+
+/// Poll as stored by 0.1 releases: an open-ended, one address one vote poll.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollV1 {
+    pub creator: Addr,
+    pub question: String,
+    pub options: Vec<(String, u64)>,
+}
+
+/// Ballot as stored by 0.1 releases, a single option counted once.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BallotV1 {
+    pub option: String,
+}
+
+/// Counters stored inside each poll before 0.3.0. From 0.2.0 on only the
+/// score and chamber counts are live here, the rest being kept apart and
+/// left zeroed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollCountersV2 {
+    pub options: Vec<(String, Uint128)>,
+    pub participation: Uint128,
+    pub abstain: Uint128,
+    #[serde(default)]
+    pub score_counts: Vec<(String, Uint128)>,
+    #[serde(default)]
+    pub chambers: Vec<Chamber>,
+}
+
+/// A poll holding its counters, or any other entry, whether in the 0.1
+/// layout or one whose counters were already moved out.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(untagged)]
+pub enum StoredCounters {
+    Inside(PollCountersV2),
+    Apart(Empty),
+}
+
+/// An entry in either layout. 0.1 releases share their version with states
+/// already in the current layout, so each entry is told apart on its own.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(untagged)]
+pub enum Stored<T, V1> {
+    Current(T),
+    V1(V1),
+}
+
+const STORED_CONFIG: Item<Stored<Config, ConfigV1>> = Item::new("config");
+const STORED_POLLS: Map<&str, Stored<StoredPoll, PollV1>> = Map::new("polls");
+const STORED_COUNTERS: Map<&str, StoredCounters> = Map::new("polls");
+const STORED_BALLOTS: Map<(Addr, &str), Stored<Ballot, BallotV1>> = Map::new("ballots");
+
+/// Rewrites the config, polls and ballots still in the 0.1 layout into the
+/// current one. Returns the number of polls rewritten.
+pub fn upgrade_v1(storage: &mut dyn Storage, block: &BlockInfo) -> StdResult<u64> {
+    if let Stored::V1(config) = STORED_CONFIG.load(storage)? {
+        CONFIG.save(
+            storage,
+            &Config {
+                treasury: config.admin.clone(),
+                admin: config.admin,
+                unique_subjects: true,
+                admin_pubkey: None,
+                poll_creation_fee: None,
+                poll_deposit: None,
+                cw20_poll_creation_fee: None,
+                max_polls_per_creator: None,
+                poll_creation_cooldown: None,
+                min_options: DEFAULT_MIN_OPTIONS,
+                max_options: DEFAULT_MAX_OPTIONS,
+                max_question_len: DEFAULT_MAX_QUESTION_LEN,
+                max_option_len: DEFAULT_MAX_OPTION_LEN,
+            },
+        )?;
+    }
+
+    let polls = STORED_POLLS
+        .range(storage, None, None, Order::Ascending)
+        .filter_map(|item| match item {
+            Ok((poll_id, Stored::V1(poll))) => Some(Ok((poll_id, poll))),
+            Ok((_, Stored::Current(_))) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut sequence = POLL_SEQUENCE.may_load(storage)?.unwrap_or_default();
+    for (poll_id, poll) in polls.iter() {
+        sequence += 1;
+        save_poll(storage, poll_id, &upgrade_poll(poll.clone(), sequence))?;
+    }
+    POLL_SEQUENCE.save(storage, &sequence)?;
+
+    // 0.1 releases kept no voter index, every ballot is indexed again
+    let ballots = STORED_BALLOTS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for ((voter, poll_id), ballot) in ballots {
+        if let Stored::V1(ballot) = ballot {
+            BALLOTS.save(
+                storage,
+                (voter.clone(), &poll_id),
+                &upgrade_ballot(ballot, block),
+            )?;
+        }
+        POLL_VOTERS.save(storage, (&poll_id, &voter), &Empty {})?;
+    }
+
+    Ok(polls.len() as u64)
+}
+
+/// Moves the counters of polls stored with them inside, the tallies and
+/// totals too if `tallies` is set, into `TALLIES` and `POLL_TOTALS`, and
+/// drops them from `POLLS`. Returns the number of polls rewritten.
+pub fn split_counters(storage: &mut dyn Storage, tallies: bool) -> StdResult<u64> {
+    let polls = STORED_COUNTERS
+        .range(storage, None, None, Order::Ascending)
+        .filter_map(|item| match item {
+            Ok((poll_id, StoredCounters::Inside(counters))) => Some(Ok((poll_id, counters))),
+            Ok((_, StoredCounters::Apart(_))) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    for (poll_id, counters) in polls.iter() {
+        let mut totals = POLL_TOTALS.may_load(storage, poll_id)?.unwrap_or_default();
+        if tallies {
+            for (idx, (_, tally)) in counters.options.iter().enumerate() {
+                TALLIES.save(storage, (poll_id, idx as u32), tally)?;
+            }
+            totals.participation = counters.participation;
+            totals.abstain = counters.abstain;
+        }
+        totals.score_counts = counters.score_counts.iter().map(|count| count.1).collect();
+        totals.chambers = counters
+            .chambers
+            .iter()
+            .map(|chamber| chamber.options.iter().map(|option| option.1).collect())
+            .collect();
+        POLL_TOTALS.save(storage, poll_id, &totals)?;
+        // The stored layout reads the options pairs and skips the counters
+        let poll = POLLS.load(storage, poll_id)?;
+        POLLS.save(storage, poll_id, &poll)?;
+    }
+    Ok(polls.len() as u64)
+}
+
+fn upgrade_poll(poll: PollV1, sequence: u64) -> Poll {
+    let options: Vec<PollOption> = poll
+        .options
+        .into_iter()
+        .map(|(name, tally)| PollOption {
+            name,
+            tally: Uint128::from(tally),
+            details: OptionDetails::default(),
+        })
+        .collect();
+    let participation = options.iter().map(|option| option.tally).sum();
+    Poll {
+        creator: poll.creator,
+        question: poll.question,
+        options,
+        series_id: None,
+        strategy: VotingStrategy::OneAddressOneVote {},
+        retired_options: vec![],
+        end_time: Expiration::Never {},
+        status: PollStatus::Open,
+        annul_reason: None,
+        subject: None,
+        chambers: vec![],
+        weight_curve: WeightCurve::Linear,
+        weight_cap: None,
+        basket: vec![],
+        resolution: None,
+        finalizer: None,
+        vote_token: None,
+        correction_window: None,
+        min_support: None,
+        group: None,
+        max_selections: 1,
+        upgrade: None,
+        execution: None,
+        template: None,
+        lottery: false,
+        tie_break: TieBreak::None,
+        result: None,
+        method: VotingMethod::Choice {},
+        score_counts: vec![],
+        privacy: Privacy::Public,
+        commit_deadline: None,
+        quorum: None,
+        threshold: None,
+        veto: None,
+        deposit: None,
+        participation,
+        abstain: Uint128::zero(),
+        sequence,
+        labels: vec![],
+        frozen: false,
+        max_ballots: None,
+        description: None,
+        image_url: None,
+        metadata: None,
+        captures: vec![],
+        tags: vec![],
+        created_at: None,
+    }
+}
+
+/// Ballots carried no block, they are stamped with the migration's.
+fn upgrade_ballot(ballot: BallotV1, block: &BlockInfo) -> Ballot {
+    Ballot {
+        options: vec![ballot.option],
+        amounts: vec![],
+        credits_spent: 0,
+        weight: Uint128::new(1),
+        height: block.height,
+        time: block.time,
+        memo: None,
+        chambers: vec![],
+        option_retired: false,
+        escrow: vec![],
+        gateway: None,
+        locks_at: None,
+    }
+}
@@ -1,7 +1,15 @@
+#[cfg(any(feature = "library", test))]
+pub mod ballots;
 pub mod contract;
 mod error;
 pub mod helpers;
+mod legacy;
 pub mod msg;
+#[cfg(any(feature = "scenarios", test))]
+pub mod scenarios;
 pub mod state;
+mod tally;
 
 pub use crate::error::ContractError;
+#[cfg(any(feature = "library", test))]
+pub use crate::tally::{compute_tally, TallyResult};
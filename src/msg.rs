@@ -1,4 +1,12 @@
-use crate::state::{Ballot, Config, Poll};
+use crate::state::{
+    ArchivedPoll, Ballot, BasketDenom, Config, DataCapture, DepositPricing, Label, OptionDetails,
+    PermittedMsg, Poll, PollRules, PollStatus, Privacy, Reputation, ReputationParams,
+    ScheduledConfig, SpaceInfo, Stats, StorageStats, Streak, TieBreak, Veto, VotingMethod,
+    VotingStrategy, WeightCap, WeightCurve,
+};
+use cosmwasm_std::{Addr, Binary, Coin, CosmosMsg, Decimal, Timestamp, Uint128};
+use cw20::{Cw20Coin, Cw20ReceiveMsg};
+use cw_utils::{Duration, Expiration, Scheduled};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -6,49 +14,884 @@ use serde::{Deserialize, Serialize};
 #[serde(rename_all = "snake_case")]
 pub struct InstantiateMsg {
     pub admin: Option<String>,
+    /// Allow only one open poll per subject at a time, defaults to true
+    pub unique_subjects: Option<bool>,
+    /// secp256k1 public key whose signatures authorize `CreatePollSigned`
+    pub admin_pubkey: Option<Binary>,
+    /// Fee charged for each `CreatePoll`
+    pub poll_creation_fee: Option<Coin>,
+    /// Deposit held from each `CreatePoll` until the poll ends
+    pub poll_deposit: Option<Coin>,
+    /// Receives withdrawn fees, defaults to the admin
+    pub treasury: Option<String>,
+    /// Fee for polls created by sending cw20 tokens
+    pub cw20_poll_creation_fee: Option<Cw20Coin>,
+    /// Open polls an address may hold at once, unlimited by default
+    pub max_polls_per_creator: Option<u64>,
+    /// Seconds an address must wait between creating polls, none by default
+    pub poll_creation_cooldown: Option<u64>,
+    /// Fewest options a poll may have, defaults to 2
+    pub min_options: Option<u32>,
+    /// Most options a poll may have, defaults to 10
+    pub max_options: Option<u32>,
+    /// Longest question in bytes, defaults to 512
+    pub max_question_len: Option<u32>,
+    /// Longest option in bytes, defaults to 128
+    pub max_option_len: Option<u32>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Member {
+    pub address: String,
+    pub weight: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChamberInput {
+    pub name: String,
+    /// Share of the chamber's counted weight an option needs to pass
+    pub threshold: Decimal,
+    pub members: Vec<Member>,
+}
+
+/// Everything needed to create a poll, as carried by `CreatePoll`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollInput {
+    pub poll_id: Option<String>,
+    pub question: String,
+    pub options: Vec<String>,
+    pub series_id: Option<String>,
+    pub strategy: Option<VotingStrategy>,
+    #[serde(default)]
+    pub allowlist: Vec<Member>,
+    pub end_time: Option<Expiration>,
+    pub subject: Option<String>,
+    #[serde(default)]
+    pub chambers: Vec<ChamberInput>,
+    pub weight_curve: Option<WeightCurve>,
+    pub weight_cap: Option<WeightCap>,
+    #[serde(default)]
+    pub basket: Vec<BasketDenom>,
+    pub resolver: Option<ResolverInput>,
+    pub vote_token: Option<String>,
+    pub correction_window: Option<Duration>,
+    pub min_support: Option<Decimal>,
+    pub group: Option<String>,
+    pub max_selections: Option<u32>,
+    pub upgrade: Option<UpgradeInput>,
+    pub method: Option<VotingMethod>,
+    pub privacy: Option<Privacy>,
+    pub commit_deadline: Option<Expiration>,
+    pub quorum: Option<Decimal>,
+    pub veto: Option<Veto>,
+    pub finalizer: Option<FinalizerInput>,
+    pub execution: Option<ExecutionInput>,
+    pub template: Option<String>,
+    pub threshold: Option<Decimal>,
+    #[serde(default)]
+    pub lottery: bool,
+    pub tie_break: Option<TieBreak>,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    pub max_ballots: Option<u64>,
+    pub description: Option<String>,
+    pub image_url: Option<String>,
+    pub metadata: Option<String>,
+    #[serde(default)]
+    pub option_details: Vec<OptionDetails>,
+    #[serde(default)]
+    pub captures: Vec<DataCapture>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UpgradeInput {
+    pub code_id: u64,
+    pub msg: Binary,
+    /// Delay after the poll's end time before the upgrade can be executed
+    pub timelock: Duration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ResolverInput {
+    pub resolver: String,
+    /// Resolves the poll if the resolver hasn't by `deadline`, defaults to the admin
+    pub arbiter: Option<String>,
+    pub deadline: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExecutionInput {
+    /// Option that has to win for `msgs` to be dispatched
+    pub pass_option: String,
+    pub msgs: Vec<CosmosMsg>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FinalizerInput {
+    pub address: String,
+    /// Delay after the poll's end time before the finalizer may step in
+    pub timeout: Duration,
+}
+
+/// Canonical form in which templates travel between deployments: the JSON of
+/// this struct, fields in declaration order.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TemplateExport {
+    /// Contract that exported the template, imports only accept their own.
+    pub contract: String,
+    /// Version of the export format.
+    pub format: u32,
+    pub name: String,
+    pub rules: PollRules,
+}
+
+/// Poll creation pre-authorized off-chain by the admin, submitted by anyone
+/// through `CreatePollSigned`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignedPollPayload {
+    /// Address recorded as the poll's creator
+    pub creator: String,
+    /// Single-use value preventing the payload from being replayed
+    pub nonce: u64,
+    pub poll: PollInput,
+}
+
+/// Randomness delivered by the nois proxy, as in nois' `NoisCallback`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NoisCallback {
+    /// The id the randomness was requested with, the poll's id
+    pub job_id: String,
+    /// Publication time of the beacon round the randomness comes from
+    pub published: Timestamp,
+    /// 32 random bytes, hex encoded
+    pub randomness: String,
+}
+
+/// Messages this contract sends to the nois proxy.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NoisProxyExecuteMsg {
+    /// Requests the randomness of the next beacon round, delivered through
+    /// `ExecuteMsg::NoisReceive`
+    GetNextRandomness { job_id: String },
+}
+
+/// Messages embedded in cw20 `Send`s to this contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    /// Same fields as `ExecuteMsg::CreatePoll`, the poll being created on
+    /// behalf of the token sender
+    CreatePoll(PollInput),
+}
+
+#[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     CreatePoll {
-        poll_id: String,
+        /// Leave unset to have the contract assign the next numeric id
+        poll_id: Option<String>,
         question: String,
         options: Vec<String>,
+        /// Links the poll into a series (runoff, stage, recurring poll...)
+        series_id: Option<String>,
+        /// How eligibility and vote weight are decided, one address one vote by default
+        strategy: Option<VotingStrategy>,
+        /// Initial allowlist for `VotingStrategy::Allowlist` polls
+        #[serde(default)]
+        allowlist: Vec<Member>,
+        /// When voting closes, the poll stays open forever if unset
+        end_time: Option<Expiration>,
+        /// Topic key used to prevent concurrent polls on the same subject
+        subject: Option<String>,
+        /// Chambers for `VotingStrategy::Chambers` polls
+        #[serde(default)]
+        chambers: Vec<ChamberInput>,
+        /// Curve applied to raw voter weight, linear by default
+        weight_curve: Option<WeightCurve>,
+        /// Limit on any single voter's raw weight, applied before the curve
+        weight_cap: Option<WeightCap>,
+        /// Denoms accepted for `VotingStrategy::Coins` polls
+        #[serde(default)]
+        basket: Vec<BasketDenom>,
+        /// Oracle reporting the real-world outcome (prediction-market mode)
+        resolver: Option<ResolverInput>,
+        /// cw20 token whose balance weighs votes in `VotingStrategy::Cw20Balance` polls
+        vote_token: Option<String>,
+        /// How long voters may change a ballot after first casting it, forever if unset
+        correction_window: Option<Duration>,
+        /// Share of the counted weight an option needs to be viable, e.g. 10%
+        min_support: Option<Decimal>,
+        /// cw4-group contract whose members vote in `VotingStrategy::Cw4Group` polls
+        group: Option<String>,
+        /// How many options voters may select, one by default
+        max_selections: Option<u32>,
+        /// Migrates this contract if voters approve, only callable by the admin.
+        /// The contract must be its own admin on chain
+        upgrade: Option<UpgradeInput>,
+        /// How voters express their preference, `Choice` by default
+        method: Option<VotingMethod>,
+        /// What ballot queries reveal, `Public` by default
+        privacy: Option<Privacy>,
+        /// Makes voting two-phase: ballots are committed as hashes until this
+        /// expires, then revealed before the poll ends
+        commit_deadline: Option<Expiration>,
+        /// Share of the total power that must take part, abstentions included,
+        /// for the result to be valid. Allowlist and registry polls only
+        quorum: Option<Decimal>,
+        /// Option vetoing the poll, whatever the other tallies, once its share
+        /// of the participation exceeds the threshold
+        veto: Option<Veto>,
+        /// Backup that may archive or resolve the poll once the creator and
+        /// resolver have left it untouched for the timeout after it ends
+        finalizer: Option<FinalizerInput>,
+        /// Messages dispatched through `Execute` if the pass option wins.
+        /// Creators other than the admin may only use permitted message kinds
+        execution: Option<ExecutionInput>,
+        /// Stored template whose rules fill in the settings left unset here
+        template: Option<String>,
+        /// Share of the non-abstaining weight the leading option needs to
+        /// win, a plain plurality if unset
+        threshold: Option<Decimal>,
+        /// Draws the winner at finalization from the randomness proxy's next
+        /// beacon round, each option with a chance proportional to its tally
+        #[serde(default)]
+        lottery: bool,
+        /// How a tie for the lead is settled, leaving the poll undecided by
+        /// default. Random tie-breaks need a randomness proxy to be set
+        tie_break: Option<TieBreak>,
+        /// Key/value pairs such as `team=infra` the poll can be filtered by,
+        /// one value per key
+        #[serde(default)]
+        labels: Vec<Label>,
+        /// Number of ballots accepted, first come first served, unlimited
+        /// if unset. Revoked ballots free their slot
+        max_ballots: Option<u64>,
+        /// Longer explanation shown with the question
+        description: Option<String>,
+        image_url: Option<String>,
+        /// Free-form data for frontends, stored as given
+        metadata: Option<String>,
+        /// Label, image and link of each option, in the order of `options`.
+        /// Either one entry per option or none
+        #[serde(default)]
+        option_details: Vec<OptionDetails>,
+        /// External data, such as a token's supply, queried and stored on
+        /// the result when the poll is finalized
+        #[serde(default)]
+        captures: Vec<DataCapture>,
+        /// Categories the poll is listed under, case-insensitive
+        #[serde(default)]
+        tags: Vec<String>,
+    },
+    /// Creates a poll on behalf of `payload.creator`, free of charge, given
+    /// the admin's signature over the sha256 of the JSON `SignedPollPayload`.
+    /// Any deposit required is paid by the sender and returned to the creator
+    CreatePollSigned {
+        payload: Binary,
+        signature: Binary,
+    },
+    /// Saves a poll in preparation under the sender's `draft_id`, replacing
+    /// any earlier version. Drafts can't be voted on, and beyond the size
+    /// limits are only checked when published
+    SaveDraft {
+        draft_id: String,
+        poll: PollInput,
+    },
+    DeleteDraft {
+        draft_id: String,
+    },
+    /// Creates several polls at once, all or none, each paying the deposit
+    /// and fee `CreatePoll` would out of the funds sent
+    CreatePolls {
+        polls: Vec<PollInput>,
+    },
+    /// Creates the poll from the sender's draft, taking the creation fee and
+    /// deposit as `CreatePoll` does
+    PublishPoll {
+        draft_id: String,
     },
     Vote {
         poll_id: String,
-        vote: String,
+        /// Options selected, at most the poll's `max_selections`
+        vote: Vec<String>,
+        /// Votes placed on each selected option in quadratic polls, or the
+        /// score given to it in score polls
+        #[serde(default)]
+        amounts: Vec<u64>,
+        /// Optional short justification stored alongside the ballot
+        memo: Option<String>,
+    },
+    /// Casts a vote on behalf of `voter`, only callable by registered gateway
+    /// contracts. `proof` is opaque context from the gateway (signature,
+    /// session...) emitted with the vote for auditing
+    VoteFor {
+        voter: String,
+        poll_id: String,
+        vote: Vec<String>,
+        #[serde(default)]
+        amounts: Vec<u64>,
+        memo: Option<String>,
+        proof: Option<Binary>,
+    },
+    /// Trusts a contract to vote on behalf of its users, only callable by the admin
+    AddGateway {
+        address: String,
+    },
+    /// Removes a trusted gateway, only callable by the admin
+    RemoveGateway {
+        address: String,
+    },
+    /// Sets the branding of the space hosted by this contract, only callable by
+    /// the admin
+    SetSpaceInfo {
+        info: SpaceInfo,
+    },
+    /// Replaces the message kinds executable polls may carry when created by
+    /// anyone but the admin, only callable by the admin
+    SetPermittedMsgs {
+        permitted: Vec<PermittedMsg>,
+    },
+    /// Stores poll rules under `name` for `CreatePoll` to reuse, or removes
+    /// them if `rules` is unset, only callable by the admin
+    SetTemplate {
+        name: String,
+        rules: Option<PollRules>,
+    },
+    /// Stores a template exported by another deployment of this contract,
+    /// only callable by the admin
+    ImportTemplate {
+        export: Binary,
+    },
+    /// Replaces how reputation is scored, only callable by the admin
+    SetReputationParams {
+        params: ReputationParams,
+    },
+    /// Makes the poll deposit grow with recent creation volume, or keeps it
+    /// flat when unset, only callable by the admin
+    SetDepositPricing {
+        pricing: Option<DepositPricing>,
+    },
+    /// Uploads a batch of allowlist changes while the poll is open, only
+    /// callable by the poll creator
+    UpdateAllowlist {
+        poll_id: String,
+        add: Vec<Member>,
+        remove: Vec<String>,
+    },
+    /// Verifies the sender's eligibility on a poll whose strategy reads their
+    /// weight from another contract, caching the weight so their votes skip
+    /// the query
+    PrecheckEligibility {
+        poll_id: String,
+    },
+    /// Subscribes the sender to notifications about a poll
+    WatchPoll {
+        poll_id: String,
+    },
+    UnwatchPoll {
+        poll_id: String,
+    },
+    /// Hides the creator's polls from the sender's `Feed` and `FollowedPolls`
+    MuteCreator {
+        creator: String,
+    },
+    UnmuteCreator {
+        creator: String,
+    },
+    /// Adds the creator's polls to the sender's `FollowedPolls`
+    FollowCreator {
+        creator: String,
+    },
+    UnfollowCreator {
+        creator: String,
+    },
+    /// Changes the contract configuration, only callable by the admin. Fields
+    /// left unset keep their current value. The admin itself is handed over
+    /// with `ProposeAdmin` / `AcceptAdmin`
+    UpdateConfig {
+        unique_subjects: Option<bool>,
+        admin_pubkey: Option<Binary>,
+        poll_creation_fee: Option<Coin>,
+        poll_deposit: Option<Coin>,
+        treasury: Option<String>,
+        cw20_poll_creation_fee: Option<Cw20Coin>,
+        max_polls_per_creator: Option<u64>,
+        poll_creation_cooldown: Option<u64>,
+        min_options: Option<u32>,
+        max_options: Option<u32>,
+        max_question_len: Option<u32>,
+        max_option_len: Option<u32>,
+    },
+    /// Announces a config change that takes effect at `activate_at`, replacing
+    /// any change still pending, only callable by the admin
+    ScheduleConfig {
+        unique_subjects: Option<bool>,
+        admin_pubkey: Option<Binary>,
+        poll_creation_fee: Option<Coin>,
+        poll_deposit: Option<Coin>,
+        treasury: Option<String>,
+        cw20_poll_creation_fee: Option<Cw20Coin>,
+        max_polls_per_creator: Option<u64>,
+        poll_creation_cooldown: Option<u64>,
+        min_options: Option<u32>,
+        max_options: Option<u32>,
+        max_question_len: Option<u32>,
+        max_option_len: Option<u32>,
+        activate_at: Scheduled,
+    },
+    /// Nominates a new admin, who must accept before the role changes hands,
+    /// only callable by the admin
+    ProposeAdmin {
+        new_admin: String,
+    },
+    /// Claims the admin role, only callable by the proposed admin
+    AcceptAdmin {},
+    /// Appoints the guardian, or removes it if unset, only callable by the admin
+    SetGuardian {
+        guardian: Option<String>,
+    },
+    /// Switches read-only mode, in which every execute but `Pause` and
+    /// `Unpause` is rejected while queries keep working. Only callable by the
+    /// admin or guardian
+    SetReadOnly {
+        read_only: bool,
+    },
+    /// Stops every execute but `SetReadOnly` until `Unpause`, only callable by
+    /// the admin
+    Pause {},
+    Unpause {},
+    /// Adds, re-weights or removes members of the contract-wide voter registry,
+    /// only callable by the admin
+    UpdateMembers {
+        add: Vec<Member>,
+        remove: Vec<String>,
+    },
+    /// Grants an address the moderator role, only callable by the admin
+    AddModerator {
+        address: String,
+    },
+    /// Revokes the moderator role, only callable by the admin
+    RemoveModerator {
+        address: String,
+    },
+    /// Stops a poll from accepting votes, only callable by the creator, admin
+    /// or a moderator
+    ClosePoll {
+        poll_id: String,
+    },
+    /// Withdraws a poll without a result, only callable by the creator or admin
+    CancelPoll {
+        poll_id: String,
+    },
+    /// Suspends voting and revocations on a poll pending investigation, only
+    /// callable by the admin
+    FreezePoll {
+        poll_id: String,
+    },
+    UnfreezePoll {
+        poll_id: String,
+    },
+    /// Cancels a poll at any stage and unwinds its side effects, only callable by the admin
+    AnnulPoll {
+        poll_id: String,
+        reason: String,
+    },
+    /// Moves a finished poll's result into the compact archive and reclaims its
+    /// active storage, only callable by the creator or admin, or the poll's
+    /// backup finalizer once its timeout has passed
+    ArchivePoll {
+        poll_id: String,
+    },
+    /// Creates a poll paid for with the configured cw20 fee token, the
+    /// embedded message being a `ReceiveMsg`. Unavailable while a deposit is
+    /// required, as it can't be sent along
+    Receive(Cw20ReceiveMsg),
+    /// Sends collected creation fees to the treasury, all of them (including
+    /// cw20 fees) when `amount` is unset, only callable by the admin
+    WithdrawFees {
+        amount: Option<Coin>,
     },
-    /*  DeletePoll {
+    /// Withdraws everything owed to the sender (refunds, rewards, fee shares...)
+    Claim {},
+    /// Removes a poll and all of its ballots, only callable by the creator,
+    /// admin or a moderator. Ballots beyond the first batch are left for
+    /// `PrunePoll`
+    DeletePoll {
         poll_id: String,
     },
+    /// Stops an option from accepting votes (e.g. a candidate withdraws),
+    /// only callable by the poll creator
+    RetireOption {
+        poll_id: String,
+        option: String,
+        reason: Option<String>,
+    },
+    /// Withdraws the sender's ballot from a poll
     RevokeVote {
         poll_id: String,
-    }, */
+    },
+    /// Records the real-world outcome of a finished poll, only callable by its
+    /// resolver, or its arbiter once the resolution deadline has passed. The
+    /// poll's backup finalizer may also step in once its timeout has passed
+    ResolveOutcome {
+        poll_id: String,
+        outcome: String,
+    },
+    /// Takes part in a poll without supporting any option, counting towards
+    /// its quorum only
+    Abstain {
+        poll_id: String,
+        memo: Option<String>,
+    },
+    /// Commits to a ballot in a commit-reveal poll, as the sha256 of the
+    /// comma-joined options followed by a secret salt
+    CommitVote {
+        poll_id: String,
+        commitment: Binary,
+    },
+    /// Casts a committed ballot once the commit phase is over. Only revealed
+    /// ballots are counted
+    RevealVote {
+        poll_id: String,
+        vote: Vec<String>,
+        #[serde(default)]
+        amounts: Vec<u64>,
+        salt: String,
+    },
+    /// Fixes the result of an ended poll so later changes, such as to its
+    /// allowlist or the registry, can't alter it, and settles the creator's
+    /// deposit. Callable by anyone. Lottery polls, and ties settled at random,
+    /// are only finalized once the randomness proxy answers with the next
+    /// beacon round; any funds sent along pay for its request
+    FinalizePoll {
+        poll_id: String,
+    },
+    /// Returns the creator's deposit once the poll has ended, or forfeits it
+    /// to the collected fees if the poll was vetoed. Callable by anyone
+    SettleDeposit {
+        poll_id: String,
+    },
+    /// Moves the coins locked with the sender's ballot to their claims once
+    /// the poll is no longer open
+    ReleaseEscrow {
+        poll_id: String,
+    },
+    /// Credits the next batch of a finalized poll's voters with reputation,
+    /// for polls with more voters than finalizing credits at once. Callable
+    /// by anyone
+    CreditReputation {
+        poll_id: String,
+    },
+    /// Removes the next batch of ballots and other entries left behind by a
+    /// deleted or archived poll, callable by anyone
+    PrunePoll {
+        poll_id: String,
+    },
+    /// Migrates the contract as approved by an upgrade poll, callable by
+    /// anyone once the poll has been finalized and its timelock has passed
+    ExecuteUpgrade {
+        poll_id: String,
+    },
+    /// Dispatches the messages of a binding poll, callable by anyone once the
+    /// poll has been finalized with its pass option ahead of the others and
+    /// its quorum reached
+    Execute {
+        poll_id: String,
+    },
+    /// Sets the nois proxy random draws are requested from, or removes it if
+    /// unset, only callable by the admin. Lottery and random tie-break polls
+    /// can only be created while one is set
+    SetRandomnessProxy {
+        proxy: Option<String>,
+    },
+    /// Delivers the randomness requested by `FinalizePoll` and finalizes the
+    /// poll with it, only callable by the randomness proxy
+    NoisReceive {
+        callback: NoisCallback,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    AllPolls {},
-    Poll { poll_id: String },
-    Vote { poll_id: String, address: String },
+    AllPolls {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Polls from the most recently created on, continuing after the poll
+    /// whose `sequence` is `start_after`, deleted or not
+    LatestPolls {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Id, question, status, votes and deadline of each poll, by poll id,
+    /// for list views that don't need the options
+    PollSummaries {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Pseudo-random sample of open polls, fixed for a given seed and block.
+    /// Only a bounded number of polls are looked at, so the sample may fall
+    /// short of `limit` when most polls are closed
+    RandomPolls {
+        seed: u64,
+        limit: Option<u32>,
+    },
+    /// The poll with `option_limit` of its options from position
+    /// `option_offset` on, every option when no limit is given.
+    Poll {
+        poll_id: String,
+        option_offset: Option<u32>,
+        option_limit: Option<u32>,
+    },
+    /// Drafts saved by `creator`, by draft id.
+    Drafts {
+        creator: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    Vote {
+        poll_id: String,
+        address: String,
+    },
     GetConfig {},
-    //AllVotesForAUser { user_address: String },
+    Claims {
+        address: String,
+    },
+    ArchivedPolls {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    ArchivedResult {
+        poll_id: String,
+    },
+    Members {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    Moderators {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    Gateways {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    Watchers {
+        poll_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Polls as listed by `AllPolls`, leaving out those of creators `address`
+    /// muted. A page looks at a bounded number of polls, so it may come up
+    /// short of `limit`; keep paging from `next_start_after` while it is set.
+    Feed {
+        address: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Polls by creators `address` follows and hasn't muted, paged like `Feed`.
+    FollowedPolls {
+        address: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    MutedCreators {
+        address: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    ChamberResults {
+        poll_id: String,
+    },
+    PollSeries {
+        series_id: String,
+    },
+    /// Polls carrying the label `key=value`, by poll id.
+    PollsByLabel {
+        key: String,
+        value: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Polls created by `creator`, by poll id.
+    PollsByCreator {
+        creator: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Polls listed under `tag`, by poll id.
+    PollsByTag {
+        tag: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Weight `address` would vote with on the poll right now, zero when not
+    /// eligible. Coin polls weigh the funds sent with the vote, see `SimulateVote`
+    VotingPower {
+        poll_id: String,
+        address: String,
+    },
+    SimulateVote {
+        poll_id: String,
+        address: String,
+        vote: Vec<String>,
+        #[serde(default)]
+        amounts: Vec<u64>,
+        /// Funds the vote would be sent with
+        #[serde(default)]
+        funds: Vec<Coin>,
+    },
+    AllVotesForAUser {
+        user_address: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Entry counts and approximate sizes of the contract's storage sections.
+    StorageStats {},
+    /// Polls created, ballots cast and distinct voters since deployment
+    Stats {},
+    /// Checks (address, option) claims against the stored ballots, one
+    /// result per claim in order
+    VerifyBallots {
+        poll_id: String,
+        claims: Vec<(String, String)>,
+    },
+    /// Proof of `voter`'s ballot on a finalized poll, for other contracts
+    /// rewarding voters to check through `VerifyVoteProof`
+    VoteProof {
+        poll_id: String,
+        voter: String,
+    },
+    VerifyVoteProof {
+        poll_id: String,
+        voter: String,
+        proof: Binary,
+    },
+    SpaceInfo {},
+    /// Message kinds executable polls may carry
+    PermittedMsgs {},
+    /// Tallies of a poll along with its participation and whether it reached
+    /// quorum, paged through like the options of `Poll`
+    Results {
+        poll_id: String,
+        option_offset: Option<u32>,
+        option_limit: Option<u32>,
+    },
+    /// Total raw voting power of an allowlist or registry poll as of the start
+    /// of `height`, the current block by default
+    TotalPowerAtHeight {
+        poll_id: String,
+        height: Option<u64>,
+    },
+    /// Credit budget of `address` in a quadratic poll and how much of it the
+    /// current ballot uses
+    Credits {
+        poll_id: String,
+        address: String,
+    },
+    /// Participation streak of `address`
+    Streak {
+        address: String,
+    },
+    /// Voters with the longest current streaks, ties broken by their longest
+    /// streak and then by address
+    Leaderboard {
+        limit: Option<u32>,
+    },
+    /// Addresses holding the most ballots, revoked ones not counted
+    TopVoters {
+        limit: Option<u32>,
+    },
+    /// Poll templates stored in this deployment
+    Templates {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Template `name` serialized for `ImportTemplate` on another deployment
+    ExportTemplate {
+        name: String,
+    },
+    /// Reputation of `address` and the weight it gives in reputation polls
+    Reputation {
+        address: String,
+    },
+    /// Current reputation scoring
+    ReputationParams {},
+    /// Deposit the next `CreatePoll` has to hold back and how it is priced
+    PollDeposit {},
+    /// Leading option of a poll and whether it has won
+    Winner {
+        poll_id: String,
+    },
+    /// Whether the contract is in read-only mode, and its guardian
+    ReadOnly {},
+    Paused {},
+    /// JSON schema of one of the contract's messages, as published with
+    /// this release
+    Schema {
+        kind: SchemaKind,
+    },
+    /// Ballots cast on a poll, unless its privacy level hides them
+    AllBallotsForPoll {
+        poll_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Who voted on a poll and for what, unless its privacy level hides it
+    PollVoters {
+        poll_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
+/// Messages the chain itself may send, e.g. through governance.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub enum MigrateMsg {}
+pub enum SudoMsg {
+    SetReadOnly { read_only: bool },
+}
+
+/// Returned as the data of a `CreatePoll` response.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CreatePollResponse {
+    pub poll_id: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CreatePollsResponse {
+    /// Ids of the polls created, in the order they were given.
+    pub poll_ids: Vec<String>,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct AllPollsResponse {
-    pub polls: Vec<Poll>,
+    pub polls: Vec<PollInfo>,
 }
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PollResponse {
     pub poll: Option<Poll>,
+    /// Status of the poll, accounting for an `end_time` that has passed.
+    pub status: Option<PollStatus>,
+    /// Whether the poll currently accepts votes.
+    pub is_open: bool,
+    /// Options meeting the poll's minimum support, all of them if it sets none.
+    pub viable_options: Vec<String>,
+    /// Weighted average score of each option in score polls.
+    pub average_scores: Vec<(String, Decimal)>,
+    /// Number of options of the poll, of which only the requested page is
+    /// returned.
+    pub option_count: u32,
+    /// Ballots still accepted by a poll capping their number.
+    pub remaining_ballots: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -56,7 +899,350 @@ pub struct VoteResponse {
     pub vote: Option<Ballot>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllVotesForAUserResponse {
+    /// (poll_id, ballot) pairs ordered by poll id, leaving out polls whose
+    /// ballots are private.
+    pub votes: Vec<(String, Ballot)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllBallotsForPollResponse {
+    /// (voter, ballot) pairs ordered by voter.
+    pub ballots: Vec<(Addr, Ballot)>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
     pub config: Config,
+    /// Proposed admin that has yet to accept the role.
+    pub pending_admin: Option<Addr>,
+    /// Config change that has not taken effect yet.
+    pub scheduled: Option<ScheduledConfig>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChamberResult {
+    pub name: String,
+    pub threshold: Decimal,
+    pub options: Vec<(String, u64)>,
+    /// Total weight counted in the chamber.
+    pub total_weight: u64,
+    /// Options whose share of the chamber's weight meets its threshold.
+    pub passing_options: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChamberResultsResponse {
+    pub chambers: Vec<ChamberResult>,
+    /// Options meeting the threshold in every chamber.
+    pub passing_options: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VotingPowerResponse {
+    pub weight: Uint128,
+    /// (chamber, weight) pairs in chamber polls.
+    pub chambers: Vec<(String, u64)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimsResponse {
+    pub claims: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ArchivedPollsResponse {
+    /// (poll_id, archived result) pairs ordered by poll id.
+    pub polls: Vec<(String, ArchivedPoll)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ArchivedResultResponse {
+    pub result: Option<ArchivedPoll>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MembersResponse {
+    pub members: Vec<Member>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GatewaysResponse {
+    pub gateways: Vec<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ModeratorsResponse {
+    pub moderators: Vec<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeedResponse {
+    pub polls: Vec<PollInfo>,
+    /// Last poll looked at, set when polls past it remain to be paged.
+    pub next_start_after: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MutedCreatorsResponse {
+    pub creators: Vec<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WatchersResponse {
+    pub watchers: Vec<Addr>,
+    /// Total number of watchers, regardless of pagination.
+    pub count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollInfo {
+    pub poll_id: String,
+    pub poll: Poll,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollSummary {
+    pub poll_id: String,
+    pub question: String,
+    /// Status of the poll, accounting for an `end_time` that has passed.
+    pub status: PollStatus,
+    /// Weight of every counted ballot, abstentions included.
+    pub total_votes: Uint128,
+    pub end_time: Expiration,
+    /// Ballots still accepted by a poll capping their number.
+    pub remaining_ballots: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollSummariesResponse {
+    pub polls: Vec<PollSummary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DraftInfo {
+    pub draft_id: String,
+    pub poll: PollInput,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DraftsResponse {
+    pub drafts: Vec<DraftInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollSeriesResponse {
+    pub series_id: String,
+    pub polls: Vec<PollInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateVoteResponse {
+    /// Whether the vote would be accepted if sent now.
+    pub accepted: bool,
+    /// The error the vote would fail with, if any.
+    pub error: Option<String>,
+    /// The weight the vote would be counted with.
+    pub weight: Uint128,
+    /// Funds the vote must be sent with and locks until the poll ends. Only
+    /// coin polls take any, and only an accepted vote reports them.
+    pub required_funds: Vec<Coin>,
+    /// The poll's tallies after the vote is applied. Empty while the poll's
+    /// ballots are hidden.
+    pub options: Vec<(String, Uint128)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StorageStatsResponse {
+    pub stats: StorageStats,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatsResponse {
+    pub stats: Stats,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VerifyBallotsResponse {
+    /// Whether each claimed address holds a ballot for the claimed option.
+    pub results: Vec<bool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VoteProofResponse {
+    pub contract: Addr,
+    pub poll_id: String,
+    pub voter: Addr,
+    pub weight: Uint128,
+    /// Options of the ballot, none for an abstention.
+    pub options: Vec<String>,
+    /// sha256 of the JSON `PollResult` the poll was finalized with.
+    pub result_hash: Binary,
+    /// sha256 of the JSON array of the fields above, in order.
+    pub proof: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VerifyVoteProofResponse {
+    pub valid: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpaceInfoResponse {
+    /// Unset until the admin brands the space.
+    pub info: Option<SpaceInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CreditsResponse {
+    pub budget: u64,
+    pub spent: u64,
+    pub remaining: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TotalPowerAtHeightResponse {
+    pub power: Uint128,
+    pub height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ResultsResponse {
+    pub options: Vec<(String, Uint128)>,
+    /// Number of options of the poll, of which only the requested page is
+    /// returned.
+    pub option_count: u32,
+    /// Weight of the abstaining ballots.
+    pub abstain: Uint128,
+    /// Weight of every counted ballot, abstentions included.
+    pub participation: Uint128,
+    /// Total power the quorum is measured against, if the poll sets one.
+    pub total_power: Option<Uint128>,
+    /// False when participation fell short of the poll's quorum.
+    pub valid: bool,
+    /// Leading option, if it leads outright, the result is valid and it
+    /// clears the poll's threshold.
+    pub winner: Option<String>,
+    /// Height at which the result was finalized, unset while it is still
+    /// computed from the live tallies.
+    pub finalized_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PermittedMsgsResponse {
+    pub permitted: Vec<PermittedMsg>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StreakResponse {
+    /// Zeroed for an address that never voted.
+    pub streak: Streak,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LeaderboardEntry {
+    pub address: String,
+    pub streak: Streak,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LeaderboardResponse {
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TopVotersResponse {
+    /// (address, ballots) pairs, most ballots first and ties in reverse
+    /// address order.
+    pub voters: Vec<(Addr, u64)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TemplatesResponse {
+    pub templates: Vec<(String, PollRules)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExportTemplateResponse {
+    /// JSON encoded `TemplateExport`.
+    pub export: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WinnerResponse {
+    /// The winner once decided, otherwise the first option sharing the lead,
+    /// unset while nobody has voted.
+    pub option: Option<String>,
+    pub count: Uint128,
+    /// Whether the poll has closed with `option` as its winner.
+    pub decided: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReputationResponse {
+    pub reputation: Reputation,
+    pub score: u64,
+    /// Raw weight in `VotingStrategy::Reputation` polls, the scale plus the score.
+    pub weight: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReputationParamsResponse {
+    pub params: ReputationParams,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollDepositResponse {
+    /// Deposit currently required, none if the config sets no base deposit.
+    pub deposit: Option<Coin>,
+    pub pricing: Option<DepositPricing>,
+    /// Recent creations the deposit is priced on, blending in the previous
+    /// window as the current one fills.
+    pub recent_polls: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollVoter {
+    pub address: String,
+    /// Options selected, none for an abstention.
+    pub options: Vec<String>,
+    pub weight: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollVotersResponse {
+    /// Ordered by address.
+    pub voters: Vec<PollVoter>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReadOnlyResponse {
+    pub read_only: bool,
+    pub guardian: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PausedResponse {
+    pub paused: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaKind {
+    Instantiate,
+    Execute,
+    Query,
+    Migrate,
+    Sudo,
+    Receive,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SchemaResponse {
+    pub kind: SchemaKind,
+    /// Contract version the schema describes.
+    pub version: String,
+    /// The JSON schema, stripped of insignificant whitespace.
+    pub schema: Binary,
 }
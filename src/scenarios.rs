@@ -0,0 +1,473 @@
+//! End-to-end flows built on cw-multi-test, shipped behind the `scenarios`
+//! feature so forks and auditors can replay them against a modified build.
+//!
+//! Every scenario takes the `App` and the code id of the build under test,
+//! drives it through a complete poll lifecycle and panics as soon as the
+//! contract diverges from the reference behavior. Parameters default to a
+//! small representative setup and can be changed to cover edge cases.
+//!
+//! Upgrades are not covered here: cw-multi-test only runs native contracts,
+//! so a previous release's wasm cannot be instantiated to migrate from. The
+//! migration tests in `contract` exercise the stored layouts instead.
+
+use cosmwasm_std::{coins, Addr, Coin, Empty, Uint128};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use cw_utils::Expiration;
+
+use crate::contract::{ballot_commitment, execute, instantiate, migrate, query};
+use crate::msg::{
+    ClaimsResponse, ExecuteMsg, InstantiateMsg, Member, PollResponse, QueryMsg, ResolverInput,
+};
+use crate::state::{BasketDenom, VotingStrategy};
+
+/// This crate's entry points, ready to be stored with `App::store_code`.
+pub fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query).with_migrate(migrate))
+}
+
+/// Instantiates `code_id` with `admin` and no fees.
+pub fn instantiate_polls(app: &mut App, code_id: u64, admin: &str) -> Addr {
+    let msg = InstantiateMsg {
+        admin: None,
+        unique_subjects: None,
+        admin_pubkey: None,
+        poll_creation_fee: None,
+        poll_deposit: None,
+        treasury: None,
+        cw20_poll_creation_fee: None,
+        max_polls_per_creator: None,
+        poll_creation_cooldown: None,
+        min_options: None,
+        max_options: None,
+        max_question_len: None,
+        max_option_len: None,
+    };
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(admin),
+        &msg,
+        &[],
+        "polls",
+        Some(admin.to_string()),
+    )
+    .unwrap()
+}
+
+/// An allowlist poll where each voter's ballot counts with their weight.
+pub struct WeightedElection {
+    pub admin: String,
+    pub options: Vec<String>,
+    /// Voter address, weight and chosen option
+    pub voters: Vec<(String, u64, String)>,
+}
+
+impl Default for WeightedElection {
+    fn default() -> Self {
+        WeightedElection {
+            admin: "admin".to_string(),
+            options: vec!["Juno".to_string(), "Osmosis".to_string()],
+            voters: vec![
+                ("alice".to_string(), 5, "Juno".to_string()),
+                ("bob".to_string(), 2, "Osmosis".to_string()),
+                ("carol".to_string(), 1, "Osmosis".to_string()),
+            ],
+        }
+    }
+}
+
+/// Runs a weighted election to the end and returns the final tallies, which
+/// must add up to the weight behind each option.
+pub fn weighted_election(
+    app: &mut App,
+    code_id: u64,
+    params: &WeightedElection,
+) -> Vec<(String, Uint128)> {
+    let polls = instantiate_polls(app, code_id, &params.admin);
+    let admin = Addr::unchecked(&params.admin);
+
+    let mut msg = create_poll_msg("election", &params.options);
+    if let ExecuteMsg::CreatePoll {
+        strategy,
+        allowlist,
+        ..
+    } = &mut msg
+    {
+        *strategy = Some(VotingStrategy::Allowlist {});
+        *allowlist = params
+            .voters
+            .iter()
+            .map(|(address, weight, _)| Member {
+                address: address.clone(),
+                weight: *weight,
+            })
+            .collect();
+    }
+    app.execute_contract(admin.clone(), polls.clone(), &msg, &[])
+        .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked("outsider"),
+        polls.clone(),
+        &vote_msg("election", &params.options[0]),
+        &[],
+    )
+    .expect_err("addresses off the allowlist must not vote");
+    for (address, _, option) in params.voters.iter() {
+        app.execute_contract(
+            Addr::unchecked(address),
+            polls.clone(),
+            &vote_msg("election", option),
+            &[],
+        )
+        .unwrap();
+    }
+
+    let close = ExecuteMsg::ClosePoll {
+        poll_id: "election".to_string(),
+    };
+    app.execute_contract(admin, polls.clone(), &close, &[])
+        .unwrap();
+    if let Some((address, _, option)) = params.voters.first() {
+        app.execute_contract(
+            Addr::unchecked(address),
+            polls.clone(),
+            &vote_msg("election", option),
+            &[],
+        )
+        .expect_err("closed polls must not accept votes");
+    }
+
+    let options = query_poll(app, &polls, "election").poll.unwrap().tallies();
+    for (option, tally) in options.iter() {
+        let expected: u64 = params
+            .voters
+            .iter()
+            .filter(|voter| &voter.2 == option)
+            .map(|voter| voter.1)
+            .sum();
+        assert_eq!(*tally, Uint128::from(expected), "tally of {}", option);
+    }
+    options
+}
+
+/// A commit-reveal poll where some voters never reveal their ballot.
+pub struct CommitRevealVote {
+    pub admin: String,
+    pub options: Vec<String>,
+    /// Voter address, chosen option, salt and whether they reveal
+    pub voters: Vec<(String, String, String, bool)>,
+}
+
+impl Default for CommitRevealVote {
+    fn default() -> Self {
+        CommitRevealVote {
+            admin: "admin".to_string(),
+            options: vec!["Juno".to_string(), "Osmosis".to_string()],
+            voters: vec![
+                (
+                    "alice".to_string(),
+                    "Juno".to_string(),
+                    "pepper".to_string(),
+                    true,
+                ),
+                (
+                    "bob".to_string(),
+                    "Osmosis".to_string(),
+                    "sea salt".to_string(),
+                    true,
+                ),
+                (
+                    "carol".to_string(),
+                    "Osmosis".to_string(),
+                    "rock salt".to_string(),
+                    false,
+                ),
+            ],
+        }
+    }
+}
+
+/// Runs a commit-reveal poll through both phases and checks that only
+/// revealed ballots are counted. Returns the final tallies.
+pub fn commit_reveal_vote(
+    app: &mut App,
+    code_id: u64,
+    params: &CommitRevealVote,
+) -> Vec<(String, Uint128)> {
+    let polls = instantiate_polls(app, code_id, &params.admin);
+    let commit_height = app.block_info().height + 5;
+    let mut msg = create_poll_msg("secret", &params.options);
+    if let ExecuteMsg::CreatePoll {
+        end_time,
+        commit_deadline,
+        ..
+    } = &mut msg
+    {
+        *end_time = Some(Expiration::AtHeight(commit_height + 5));
+        *commit_deadline = Some(Expiration::AtHeight(commit_height));
+    }
+    app.execute_contract(Addr::unchecked(&params.admin), polls.clone(), &msg, &[])
+        .unwrap();
+
+    let reveal = |option: &str, salt: &str| ExecuteMsg::RevealVote {
+        poll_id: "secret".to_string(),
+        vote: vec![option.to_string()],
+        amounts: vec![],
+        salt: salt.to_string(),
+    };
+    for (address, option, salt, _) in params.voters.iter() {
+        let voter = Addr::unchecked(address);
+        app.execute_contract(
+            voter.clone(),
+            polls.clone(),
+            &vote_msg("secret", option),
+            &[],
+        )
+        .expect_err("commit-reveal polls must not take plain votes");
+        let commit = ExecuteMsg::CommitVote {
+            poll_id: "secret".to_string(),
+            commitment: ballot_commitment(std::slice::from_ref(option), salt),
+        };
+        app.execute_contract(voter.clone(), polls.clone(), &commit, &[])
+            .unwrap();
+        app.execute_contract(voter, polls.clone(), &reveal(option, salt), &[])
+            .expect_err("ballots must not be revealed during the commit phase");
+    }
+
+    app.update_block(|block| block.height = commit_height);
+    for (address, option, salt, reveals) in params.voters.iter() {
+        let voter = Addr::unchecked(address);
+        let wrong_salt = format!("{}!", salt);
+        app.execute_contract(
+            voter.clone(),
+            polls.clone(),
+            &reveal(option, &wrong_salt),
+            &[],
+        )
+        .expect_err("reveals must match the commitment");
+        if *reveals {
+            app.execute_contract(voter, polls.clone(), &reveal(option, salt), &[])
+                .unwrap();
+        }
+    }
+
+    let options = query_poll(app, &polls, "secret").poll.unwrap().tallies();
+    for (option, tally) in options.iter() {
+        let expected = params
+            .voters
+            .iter()
+            .filter(|voter| &voter.1 == option && voter.3)
+            .count();
+        assert_eq!(
+            *tally,
+            Uint128::from(expected as u128),
+            "tally of {}",
+            option
+        );
+    }
+    options
+}
+
+/// A prediction poll where voters stake coins on an outcome, the resolver
+/// reports the real one and every stake is handed back through `Claim`.
+pub struct PrizePoll {
+    pub admin: String,
+    pub resolver: String,
+    pub denom: String,
+    pub options: Vec<String>,
+    /// Voter address, staked amount and chosen option
+    pub stakes: Vec<(String, u128, String)>,
+    pub outcome: String,
+}
+
+impl Default for PrizePoll {
+    fn default() -> Self {
+        PrizePoll {
+            admin: "admin".to_string(),
+            resolver: "oracle".to_string(),
+            denom: "ujuno".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            stakes: vec![
+                ("alice".to_string(), 300, "Yes".to_string()),
+                ("bob".to_string(), 100, "No".to_string()),
+            ],
+            outcome: "Yes".to_string(),
+        }
+    }
+}
+
+/// Runs a prize poll from staking to withdrawal and checks that the outcome
+/// is recorded and every voter ends with the balance they started with.
+pub fn prize_poll(app: &mut App, code_id: u64, params: &PrizePoll) {
+    for (address, amount, _) in params.stakes.iter() {
+        app.init_modules(|router, _, storage| {
+            router.bank.init_balance(
+                storage,
+                &Addr::unchecked(address),
+                coins(*amount, &params.denom),
+            )
+        })
+        .unwrap();
+    }
+
+    let polls = instantiate_polls(app, code_id, &params.admin);
+    let end_height = app.block_info().height + 10;
+    let mut msg = create_poll_msg("prize", &params.options);
+    if let ExecuteMsg::CreatePoll {
+        strategy,
+        basket,
+        end_time,
+        resolver,
+        ..
+    } = &mut msg
+    {
+        *strategy = Some(VotingStrategy::Coins {});
+        *basket = vec![BasketDenom {
+            denom: params.denom.clone(),
+            multiplier: 1,
+        }];
+        *end_time = Some(Expiration::AtHeight(end_height));
+        *resolver = Some(ResolverInput {
+            resolver: params.resolver.clone(),
+            arbiter: None,
+            deadline: Expiration::AtHeight(end_height + 10),
+        });
+    }
+    app.execute_contract(Addr::unchecked(&params.admin), polls.clone(), &msg, &[])
+        .unwrap();
+
+    for (address, amount, option) in params.stakes.iter() {
+        app.execute_contract(
+            Addr::unchecked(address),
+            polls.clone(),
+            &vote_msg("prize", option),
+            &coins(*amount, &params.denom),
+        )
+        .unwrap();
+        assert_eq!(
+            balance(app, address, &params.denom),
+            0,
+            "stake of {}",
+            address
+        );
+    }
+
+    let resolve = ExecuteMsg::ResolveOutcome {
+        poll_id: "prize".to_string(),
+        outcome: params.outcome.clone(),
+    };
+    app.execute_contract(
+        Addr::unchecked(&params.resolver),
+        polls.clone(),
+        &resolve,
+        &[],
+    )
+    .expect_err("outcomes must not be resolved while the poll is open");
+    app.update_block(|block| block.height = end_height);
+    app.execute_contract(
+        Addr::unchecked(&params.resolver),
+        polls.clone(),
+        &resolve,
+        &[],
+    )
+    .unwrap();
+    let resolution = query_poll(app, &polls, "prize")
+        .poll
+        .unwrap()
+        .resolution
+        .unwrap();
+    assert_eq!(resolution.outcome, Some(params.outcome.clone()));
+
+    for (address, amount, _) in params.stakes.iter() {
+        let voter = Addr::unchecked(address);
+        let release = ExecuteMsg::ReleaseEscrow {
+            poll_id: "prize".to_string(),
+        };
+        app.execute_contract(voter.clone(), polls.clone(), &release, &[])
+            .unwrap();
+        let claims: ClaimsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &polls,
+                &QueryMsg::Claims {
+                    address: address.clone(),
+                },
+            )
+            .unwrap();
+        assert_eq!(claims.claims, coins(*amount, &params.denom));
+        app.execute_contract(voter, polls.clone(), &ExecuteMsg::Claim {}, &[])
+            .unwrap();
+        assert_eq!(
+            balance(app, address, &params.denom),
+            *amount,
+            "refund of {}",
+            address
+        );
+    }
+}
+
+fn create_poll_msg(poll_id: &str, options: &[String]) -> ExecuteMsg {
+    ExecuteMsg::CreatePoll {
+        poll_id: Some(poll_id.to_string()),
+        question: format!("Scenario {}", poll_id),
+        options: options.to_vec(),
+        series_id: None,
+        strategy: None,
+        allowlist: vec![],
+        end_time: None,
+        subject: None,
+        chambers: vec![],
+        weight_curve: None,
+        weight_cap: None,
+        basket: vec![],
+        resolver: None,
+        vote_token: None,
+        correction_window: None,
+        min_support: None,
+        group: None,
+        max_selections: None,
+        upgrade: None,
+        method: None,
+        privacy: None,
+        commit_deadline: None,
+        quorum: None,
+        veto: None,
+        finalizer: None,
+        execution: None,
+        template: None,
+        threshold: None,
+        lottery: false,
+        tie_break: None,
+        labels: vec![],
+        max_ballots: None,
+        description: None,
+        image_url: None,
+        metadata: None,
+        option_details: vec![],
+        captures: vec![],
+        tags: vec![],
+    }
+}
+
+fn vote_msg(poll_id: &str, option: &str) -> ExecuteMsg {
+    ExecuteMsg::Vote {
+        poll_id: poll_id.to_string(),
+        vote: vec![option.to_string()],
+        amounts: vec![],
+        memo: None,
+    }
+}
+
+fn query_poll(app: &App, polls: &Addr, poll_id: &str) -> PollResponse {
+    let msg = QueryMsg::Poll {
+        poll_id: poll_id.to_string(),
+        option_offset: None,
+        option_limit: None,
+    };
+    app.wrap().query_wasm_smart(polls, &msg).unwrap()
+}
+
+fn balance(app: &App, address: &str, denom: &str) -> u128 {
+    let coin: Coin = app.wrap().query_balance(address, denom).unwrap();
+    coin.amount.u128()
+}
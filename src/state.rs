@@ -1,28 +1,925 @@
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Deserializer, Serialize};
 
-use cosmwasm_std::Addr;
-use cw_storage_plus::{Item, Map};
+use crate::msg::PollInput;
+use cosmwasm_std::{Addr, Binary, Coin, CosmosMsg, Decimal, Empty, Timestamp, Uint128};
+use cw20::{Cw20Coin, Cw20CoinVerified};
+use cw_storage_plus::{Item, Map, SnapshotItem, SnapshotMap, Strategy};
+use cw_utils::{Duration, Expiration, Scheduled};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub admin: Addr,
+    /// Whether at most one poll per subject may be open at a time.
+    pub unique_subjects: bool,
+    /// Key authorizing gasless poll creation, disabled when unset.
+    pub admin_pubkey: Option<Binary>,
+    /// Charged on every `CreatePoll`, none by default.
+    pub poll_creation_fee: Option<Coin>,
+    /// Held back from every `CreatePoll` and returned once the poll ends,
+    /// unless it was vetoed.
+    pub poll_deposit: Option<Coin>,
+    /// Destination of withdrawn fees.
+    pub treasury: Addr,
+    /// Alternative fee payable in a cw20 token through `Receive`.
+    pub cw20_poll_creation_fee: Option<Cw20CoinVerified>,
+    /// Open polls an address may hold at once, unlimited when unset.
+    #[serde(default)]
+    pub max_polls_per_creator: Option<u64>,
+    /// Seconds an address must wait between creating polls, none by default.
+    #[serde(default)]
+    pub poll_creation_cooldown: Option<u64>,
+    /// Fewest options a poll may be created with.
+    #[serde(default = "default_min_options")]
+    pub min_options: u32,
+    /// Most options a poll may be created with.
+    #[serde(default = "default_max_options")]
+    pub max_options: u32,
+    /// Longest question in bytes a poll may be created with.
+    #[serde(default = "default_max_question_len")]
+    pub max_question_len: u32,
+    /// Longest option in bytes a poll may be created with.
+    #[serde(default = "default_max_option_len")]
+    pub max_option_len: u32,
+}
+
+pub const DEFAULT_MIN_OPTIONS: u32 = 2;
+pub const DEFAULT_MAX_OPTIONS: u32 = 10;
+pub const DEFAULT_MAX_QUESTION_LEN: u32 = 512;
+pub const DEFAULT_MAX_OPTION_LEN: u32 = 128;
+
+fn default_min_options() -> u32 {
+    DEFAULT_MIN_OPTIONS
+}
+
+fn default_max_options() -> u32 {
+    DEFAULT_MAX_OPTIONS
+}
+
+fn default_max_question_len() -> u32 {
+    DEFAULT_MAX_QUESTION_LEN
+}
+
+fn default_max_option_len() -> u32 {
+    DEFAULT_MAX_OPTION_LEN
+}
+
+/// Config fields to change; unset fields keep their current value.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigChanges {
+    pub unique_subjects: Option<bool>,
+    pub admin_pubkey: Option<Binary>,
+    pub poll_creation_fee: Option<Coin>,
+    pub poll_deposit: Option<Coin>,
+    /// Validated when the change is submitted.
+    pub treasury: Option<String>,
+    /// Token address validated when the change is submitted.
+    pub cw20_poll_creation_fee: Option<Cw20Coin>,
+    pub max_polls_per_creator: Option<u64>,
+    pub poll_creation_cooldown: Option<u64>,
+    pub min_options: Option<u32>,
+    pub max_options: Option<u32>,
+    pub max_question_len: Option<u32>,
+    pub max_option_len: Option<u32>,
+}
+
+/// Config change announced ahead of time, taking effect at `activate_at`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScheduledConfig {
+    pub changes: ConfigChanges,
+    pub activate_at: Scheduled,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Poll {
     pub creator: Addr,
     pub question: String,
+    pub options: Vec<PollOption>,
+    pub series_id: Option<String>,
+    pub strategy: VotingStrategy,
+    /// Options that stopped accepting votes while the poll was running.
+    pub retired_options: Vec<RetiredOption>,
+    /// Voting closes once this expires.
+    pub end_time: Expiration,
+    pub status: PollStatus,
+    /// Why the poll was annulled, if it was.
+    pub annul_reason: Option<String>,
+    pub subject: Option<String>,
+    /// Groups of voters whose tallies are evaluated separately.
+    pub chambers: Vec<Chamber>,
+    /// Curve applied to voters' raw weight.
+    pub weight_curve: WeightCurve,
+    /// Limit on any single voter's raw weight, applied before the curve.
+    pub weight_cap: Option<WeightCap>,
+    /// Denoms accepted by `VotingStrategy::Coins` polls and what they're worth.
+    pub basket: Vec<BasketDenom>,
+    /// External party reporting the real-world outcome of the poll.
+    pub resolution: Option<Resolution>,
+    /// Backup able to finalize the poll if it is left stuck.
+    pub finalizer: Option<Finalizer>,
+    /// cw20 token whose balance weighs votes in `VotingStrategy::Cw20Balance` polls.
+    pub vote_token: Option<Addr>,
+    /// How long after first being cast a ballot may still be changed.
+    pub correction_window: Option<Duration>,
+    /// Share of the counted weight an option needs to be considered viable.
+    pub min_support: Option<Decimal>,
+    /// cw4-group contract deciding membership in `VotingStrategy::Cw4Group` polls.
+    pub group: Option<Addr>,
+    /// How many options a voter may select, each counted with their full weight.
+    pub max_selections: u32,
+    /// Migration of this contract carried out if the poll approves it.
+    pub upgrade: Option<Upgrade>,
+    /// Messages dispatched if the poll passes.
+    pub execution: Option<Execution>,
+    /// Template the poll's rules were filled in from.
+    pub template: Option<String>,
+    /// Whether the winner is drawn at random, weighted by the tallies.
+    pub lottery: bool,
+    pub tie_break: TieBreak,
+    /// Result fixed by `FinalizePoll`.
+    pub result: Option<PollResult>,
+    pub method: VotingMethod,
+    /// Weight of the ballots scoring each option in `VotingMethod::Score`
+    /// polls, whose tallies hold the weighted score sums.
+    pub score_counts: Vec<(String, Uint128)>,
+    /// What ballot queries reveal about individual voters.
+    pub privacy: Privacy,
+    /// Until this expires voters only commit to hashed ballots, revealing
+    /// them afterwards.
+    pub commit_deadline: Option<Expiration>,
+    /// Share of the total power that must take part for the result to be valid.
+    pub quorum: Option<Decimal>,
+    /// Share of the non-abstaining weight the leading option needs to win.
+    pub threshold: Option<Decimal>,
+    pub veto: Option<Veto>,
+    /// Deposit held from the creator until the poll ends, unless already settled.
+    pub deposit: Option<Coin>,
+    /// Weight of every counted ballot, abstentions included.
+    pub participation: Uint128,
+    /// Weight of the ballots abstaining.
+    pub abstain: Uint128,
+    /// Position of the poll among every poll created in the space, from 1.
+    pub sequence: u64,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    /// Set by the admin to suspend voting and revocations.
+    #[serde(default)]
+    pub frozen: bool,
+    /// Number of ballots after which the poll turns further voters away.
+    #[serde(default)]
+    pub max_ballots: Option<u64>,
+    /// Longer explanation of what the poll decides.
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub image_url: Option<String>,
+    /// Free-form data for frontends, typically JSON, never interpreted.
+    #[serde(default)]
+    pub metadata: Option<String>,
+    /// External data recorded on the result when the poll is finalized.
+    #[serde(default)]
+    pub captures: Vec<DataCapture>,
+    /// Lowercase categories such as `governance` the poll is listed under.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Block the poll was created in, unknown for polls predating 0.3.0.
+    #[serde(default)]
+    pub created_at: Option<BlockStamp>,
+}
+
+impl Poll {
+    /// Each option with its tally, in option order.
+    pub fn tallies(&self) -> Vec<(String, Uint128)> {
+        self.options
+            .iter()
+            .map(|option| (option.name.clone(), option.tally))
+            .collect()
+    }
+}
+
+/// An option voters may pick, with the weight counted for it.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct PollOption {
+    /// What voters select, unique within the poll.
+    pub name: String,
+    pub tally: Uint128,
+    #[serde(default)]
+    pub details: OptionDetails,
+}
+
+/// `Poll` as kept in `POLLS`, without any of the counters ballots move.
+/// Those are stored apart in `TALLIES` and `POLL_TOTALS`, so a vote only
+/// rewrites what it changes rather than the whole poll.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StoredPoll {
+    pub creator: Addr,
+    pub question: String,
+    pub options: Vec<StoredOption>,
+    pub series_id: Option<String>,
+    pub strategy: VotingStrategy,
+    pub retired_options: Vec<RetiredOption>,
+    pub end_time: Expiration,
+    pub status: PollStatus,
+    pub annul_reason: Option<String>,
+    pub subject: Option<String>,
+    pub chambers: Vec<StoredChamber>,
+    pub weight_curve: WeightCurve,
+    pub weight_cap: Option<WeightCap>,
+    pub basket: Vec<BasketDenom>,
+    pub resolution: Option<Resolution>,
+    pub finalizer: Option<Finalizer>,
+    pub vote_token: Option<Addr>,
+    pub correction_window: Option<Duration>,
+    pub min_support: Option<Decimal>,
+    pub group: Option<Addr>,
+    pub max_selections: u32,
+    pub upgrade: Option<Upgrade>,
+    pub execution: Option<Execution>,
+    pub template: Option<String>,
+    pub lottery: bool,
+    pub tie_break: TieBreak,
+    pub result: Option<PollResult>,
+    pub method: VotingMethod,
+    pub privacy: Privacy,
+    pub commit_deadline: Option<Expiration>,
+    pub quorum: Option<Decimal>,
+    pub threshold: Option<Decimal>,
+    pub veto: Option<Veto>,
+    pub deposit: Option<Coin>,
+    pub sequence: u64,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    #[serde(default)]
+    pub frozen: bool,
+    #[serde(default)]
+    pub max_ballots: Option<u64>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub image_url: Option<String>,
+    #[serde(default)]
+    pub metadata: Option<String>,
+    #[serde(default)]
+    pub captures: Vec<DataCapture>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub created_at: Option<BlockStamp>,
+}
+
+/// `PollOption` without its tally.
+#[derive(Serialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StoredOption {
+    pub name: String,
+    #[serde(default)]
+    pub details: OptionDetails,
+}
+
+impl<'de> Deserialize<'de> for StoredOption {
+    /// Reads options stored before 0.3.0 as (name, tally) pairs as well, the
+    /// tally having moved to `TALLIES` since 0.2.0.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Layout {
+            Current {
+                name: String,
+                #[serde(default)]
+                details: OptionDetails,
+            },
+            Pair(String, IgnoredAny),
+        }
+
+        Ok(match Layout::deserialize(deserializer)? {
+            Layout::Current { name, details } => StoredOption { name, details },
+            Layout::Pair(name, _) => StoredOption {
+                name,
+                details: OptionDetails::default(),
+            },
+        })
+    }
+}
+
+/// `Chamber` without its counts.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StoredChamber {
+    pub name: String,
+    pub threshold: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BlockStamp {
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+/// How a frontend may present an option, beyond the option string voters
+/// pick.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct OptionDetails {
+    /// Display name, the option string itself if unset.
+    pub label: Option<String>,
+    pub image_url: Option<String>,
+    /// Page describing the option.
+    pub link: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Label {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Privacy {
+    /// Anyone can look up who voted for what.
+    Public,
+    /// Only tallies are disclosed, individual ballots never are.
+    Anonymous,
+    /// Individual ballots are disclosed once voting has ended.
+    PostCloseReveal,
+}
+
+/// How a tie between the leading options of a poll is settled.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TieBreak {
+    /// The tied option listed first wins.
+    FirstListed,
+    /// Nobody wins.
+    None,
+    /// One of the tied options is drawn when the poll is finalized, from the
+    /// randomness proxy's next beacon round.
+    Random,
+}
+
+/// How voters express their preference between options.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VotingMethod {
+    /// Voters choose up to the poll's `max_selections` options.
+    Choice {},
+    /// Voters approve any subset of the options, the most approved one wins.
+    Approval {},
+    /// Voters spread votes over the options, `n` votes on one costing `n²` of
+    /// their `credits`.
+    Quadratic { credits: u64 },
+    /// Voters score every option from zero to five, the best average wins.
+    Score {},
+}
+
+/// "No with veto" option of a poll, vetoing it once its share of the
+/// participation exceeds `threshold`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Veto {
+    pub option: String,
+    pub threshold: Decimal,
+}
+
+/// Kind of message polls created by anyone but the admin may execute.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PermittedMsg {
+    /// `BankMsg::Send` of at most `max` of each listed denom.
+    BankSend { max: Vec<Coin> },
+    /// `WasmMsg::Execute` on one of `contracts`.
+    WasmExecute { contracts: Vec<Addr> },
+    /// Migration of this contract through an upgrade poll.
+    Migrate {},
+}
+
+/// Self-upgrade proposed by a poll: the first option approves it, the second
+/// rejects it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Upgrade {
+    pub code_id: u64,
+    /// Message passed to the new code's `migrate` entry point.
+    pub msg: Binary,
+    /// The poll's end time plus its timelock.
+    pub executable_at: Expiration,
+    pub executed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Resolution {
+    pub resolver: Addr,
+    /// Takes over from the resolver once `deadline` has passed.
+    pub arbiter: Addr,
+    pub deadline: Expiration,
+    /// Winning option, once resolved.
+    pub outcome: Option<String>,
+    pub resolved_by: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Execution {
+    pub pass_option: String,
+    pub msgs: Vec<CosmosMsg>,
+    pub executed: bool,
+}
+
+/// Result of a poll as fixed when it was finalized.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollResult {
+    pub status: PollStatus,
+    pub options: Vec<(String, Uint128)>,
+    pub abstain: Uint128,
+    pub participation: Uint128,
+    pub total_power: Option<Uint128>,
+    /// Whether participation reached the poll's quorum.
+    pub valid: bool,
+    pub winner: Option<String>,
+    pub finalized_at: u64,
+    /// How the winner of a lottery poll was drawn.
+    pub draw: Option<LotteryDraw>,
+    /// Values of the poll's data captures at finalization.
+    #[serde(default)]
+    pub captured: Vec<CapturedData>,
+}
+
+/// External data point a poll records when it is finalized, preserving the
+/// context the decision was taken in.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DataCapture {
+    pub name: String,
+    pub source: DataSource,
+}
+
+/// Query answering a `DataCapture`. Addresses are validated when the poll is
+/// created.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DataSource {
+    /// `TokenInfo` of a cw20 token, including its total supply.
+    TokenInfo { token: String },
+    /// Total weight of a cw4 group.
+    GroupWeight { group: String },
+    /// Any smart query, `msg` being its JSON.
+    Smart { contract: String, msg: Binary },
+}
+
+/// Answer to a `DataCapture` at finalization: the raw JSON response, or why
+/// the query failed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CapturedData {
+    pub name: String,
+    pub value: Option<Binary>,
+    pub error: Option<String>,
+}
+
+/// Proof of a random draw made at finalization. `randomness` is the value of
+/// the nois beacon round published at `published`, requested only once the
+/// poll had ended, and `point` its first 16 bytes modulo the range drawn
+/// from. In lottery polls the range is the total tally and the winner the
+/// option whose cumulative tally range, in option order, contains `point`;
+/// for random tie-breaks it is the number of tied options and `point` the
+/// position of the winner among them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LotteryDraw {
+    pub randomness: Binary,
+    pub point: Uint128,
+    /// Unset for draws taken from the finalizing block before the beacon
+    #[serde(default)]
+    pub published: Option<Timestamp>,
+}
+
+/// Backup for a poll's creator and resolver, allowed to archive the poll or
+/// resolve its outcome once `active_at` has passed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Finalizer {
+    pub address: Addr,
+    /// The poll's end time plus the finalizer's timeout.
+    pub active_at: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BasketDenom {
+    pub denom: String,
+    /// Vote weight granted per unit of `denom`.
+    pub multiplier: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Chamber {
+    pub name: String,
+    /// Share of the chamber's counted weight an option needs to pass.
+    pub threshold: Decimal,
+    /// Weight counted for each option within this chamber.
     pub options: Vec<(String, u64)>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct Ballot {
+#[serde(rename_all = "snake_case")]
+pub enum PollStatus {
+    /// Accepting votes until `end_time`.
+    Open,
+    /// No longer accepting votes, either closed early or past `end_time`.
+    Closed,
+    /// Withdrawn by its creator or the admin without a result.
+    Cancelled,
+    /// Cancelled by the admin; the poll's outcome must be disregarded.
+    Annulled,
+    /// Ended with its veto option above the veto threshold; the outcome is
+    /// rejected whatever the other tallies and the creator's deposit forfeited.
+    Vetoed,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RetiredOption {
     pub option: String,
+    pub reason: Option<String>,
+    /// Block height at which the option was retired.
+    pub height: u64,
+    /// Weight the option held when it was retired.
+    pub tally_at_retirement: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VotingStrategy {
+    /// Any address may vote, each with a weight of one.
+    OneAddressOneVote {},
+    /// Only addresses on the poll's allowlist may vote, with their listed weight.
+    Allowlist {},
+    /// Only members of the contract-wide registry may vote, with their registry weight.
+    Registry {},
+    /// Voters sit in one or more chambers, each with its own members, weights
+    /// and passing threshold. Counted once in the overall tally.
+    Chambers {},
+    /// Voters lock coins from the poll's basket with their vote, weighing the
+    /// amount of each denom by its multiplier.
+    Coins {},
+    /// Voters weigh in with their balance of the poll's vote token, read when
+    /// the vote is cast.
+    Cw20Balance {},
+    /// Voters weigh in with the amount they have bonded to validators in the
+    /// chain's staking denom, read when the vote is cast.
+    Staked {},
+    /// Only members of the poll's cw4 group may vote, with their group weight.
+    Cw4Group {},
+    /// Any address may vote, weighing in with the reputation scale plus the
+    /// reputation score it earned in past polls.
+    Reputation {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Ballot {
+    /// Options selected, a single one unless the poll allows several, none
+    /// for an abstention.
+    pub options: Vec<String>,
+    /// Votes placed on each of `options` in quadratic polls, or the score
+    /// given to each in score polls, empty when each selection counts once.
+    pub amounts: Vec<u64>,
+    /// Credits the ballot costs in quadratic polls.
+    pub credits_spent: u64,
+    /// Weight the ballot is counted with in the poll's tallies.
+    pub weight: Uint128,
+    /// Block height at which the ballot was last cast or changed.
+    pub height: u64,
+    /// Block time at which the ballot was last cast or changed.
+    pub time: Timestamp,
+    /// Optional justification supplied by the voter.
+    pub memo: Option<String>,
+    /// (chamber, weight) pairs the ballot is counted with in chamber polls.
+    pub chambers: Vec<(String, u64)>,
+    /// Set when the chosen option was retired after the ballot was cast;
+    /// the voter may re-vote for a remaining option.
+    pub option_retired: bool,
+    /// Coins locked with the ballot in `VotingStrategy::Coins` polls.
+    pub escrow: Vec<Coin>,
+    /// Gateway contract that cast the ballot on the voter's behalf.
+    pub gateway: Option<Addr>,
+    /// When the ballot stops accepting changes, per the poll's correction window.
+    pub locks_at: Option<Expiration>,
+}
+
+/// Compact, read-only record of a finished poll kept after its active
+/// storage has been reclaimed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ArchivedPoll {
+    pub creator: Addr,
+    pub question: String,
+    pub options: Vec<(String, Uint128)>,
+    pub status: PollStatus,
+    /// Number of ballots the poll held, counted as they are pruned.
+    pub ballots: u64,
+    /// Block height at which the poll was archived.
+    pub archived_at: u64,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
+/// Addresses allowed to close or delete any poll, managed by the admin.
+pub const MODERATORS: Map<&Addr, Empty> = Map::new("moderators");
+/// Contracts trusted to cast votes on behalf of their users.
+pub const GATEWAYS: Map<&Addr, Empty> = Map::new("gateways");
+/// Config change waiting for its activation point.
+pub const SCHEDULED_CONFIG: Item<ScheduledConfig> = Item::new("scheduled_config");
+/// Admin proposed through `ProposeAdmin`, awaiting acceptance.
+pub const PENDING_ADMIN: Item<Addr> = Item::new("pending_admin");
+/// Address allowed to switch read-only mode alongside the admin.
+pub const GUARDIAN: Item<Addr> = Item::new("guardian");
+/// nois proxy draws are requested from.
+pub const RANDOMNESS_PROXY: Item<Addr> = Item::new("randomness_proxy");
+/// Polls whose finalization waits for the randomness proxy's callback.
+pub const PENDING_DRAWS: Map<&str, Empty> = Map::new("pending_draws");
+/// While set, every execute but `Pause`, `Unpause` and `SetReadOnly` is
+/// rejected; queries still work.
+pub const READ_ONLY: Item<bool> = Item::new("read_only");
+/// Circuit breaker set by the admin; every execute but `Pause`, `Unpause` and
+/// `SetReadOnly` is rejected.
+pub const PAUSED: Item<bool> = Item::new("paused");
+
+pub const POLLS: Map<&str, StoredPoll> = Map::new("polls");
+
+/// Polls being prepared, keyed (creator, draft_id). They only become polls,
+/// validated and charged for, once published.
+pub const DRAFTS: Map<(&Addr, &str), PollInput> = Map::new("drafts");
+
+/// Weight counted for each option of a poll, keyed (poll_id, option index).
+pub const TALLIES: Map<(&str, u32), Uint128> = Map::new("tallies");
+
+/// Counters of a poll beyond its option tallies, stored next to `TALLIES`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct PollTotals {
+    pub participation: Uint128,
+    pub abstain: Uint128,
+    /// Weight of the ballots scoring each option, in option order, in
+    /// `VotingMethod::Score` polls.
+    #[serde(default)]
+    pub score_counts: Vec<Uint128>,
+    /// Weight counted for each option within each chamber, in chamber and
+    /// option order.
+    #[serde(default)]
+    pub chambers: Vec<Vec<u64>>,
+}
 
-pub const POLLS: Map<&str, Poll> = Map::new("polls");
+pub const POLL_TOTALS: Map<&str, PollTotals> = Map::new("poll_totals");
+
+/// Last numeric id assigned to a poll created without an explicit id.
+pub const POLL_COUNT: Item<u64> = Item::new("poll_count");
+/// Sequence number of the last poll created, whatever its id.
+pub const POLL_SEQUENCE: Item<u64> = Item::new("poll_sequence");
+/// Poll ids by sequence number, listing polls in creation order.
+pub const SEQUENCE_POLLS: Map<u64, String> = Map::new("sequence_polls");
 
 pub const BALLOTS: Map<(Addr, &str), Ballot> = Map::new("ballots");
+
+/// Reverse index of `BALLOTS` keyed (poll_id, voter), so a poll's ballots can be found.
+pub const POLL_VOTERS: Map<(&str, &Addr), Empty> = Map::new("poll_voters");
+
+/// Hashed ballots awaiting their reveal in commit-reveal polls, keyed (poll_id, voter).
+pub const COMMITMENTS: Map<(&str, &Addr), Binary> = Map::new("commitments");
+
+/// Polls carrying each label, keyed (key, value, poll_id).
+pub const LABELS: Map<(&str, &str, &str), Empty> = Map::new("labels");
+
+/// Polls listed under each tag, keyed (tag, poll_id).
+pub const TAGS: Map<(&str, &str), Empty> = Map::new("tags");
+
+/// Polls created by each address, keyed (creator, poll_id).
+pub const CREATOR_POLLS: Map<(&Addr, &str), Empty> = Map::new("creator_polls");
+
+/// Open polls held by each creator. Polls also close by expiring, so this
+/// may run ahead of the real count until the creator's polls are recounted.
+pub const OPEN_POLLS: Map<&Addr, u64> = Map::new("open_polls");
+
+/// When each address last created a poll.
+pub const LAST_CREATED: Map<&Addr, Timestamp> = Map::new("last_created");
+
+/// Polls linked into a series, keyed by (series_id, position) in creation order.
+pub const SERIES: Map<(&str, u64), String> = Map::new("series");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WeightCurve {
+    /// Weight counts as is.
+    Linear,
+    /// Weight counts as its integer square root, dampening large holders.
+    Sqrt,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WeightCap {
+    /// No voter counts with more than `max`.
+    Absolute { max: u64 },
+    /// No voter counts with more than this share of the poll's total power.
+    /// Only allowlist and registry polls track a total to take it of.
+    Percent { max: Decimal },
+}
+
+/// Poll creation fees held by the contract.
+pub const COLLECTED_FEES: Item<Vec<Coin>> = Item::new("collected_fees");
+/// cw20 creation fees held by the contract, by token.
+pub const COLLECTED_CW20_FEES: Map<&Addr, Uint128> = Map::new("collected_cw20_fees");
+
+/// Funds owed to each address, withdrawn through `ExecuteMsg::Claim`.
+pub const CLAIMS: Map<&Addr, Vec<Coin>> = Map::new("claims");
+
+/// Per-poll allowlist of eligible voters and their weights.
+pub const ALLOWLIST: Map<(&str, &Addr), u64> = Map::new("allowlist");
+/// Sum of each poll's allowlist weights, checkpointed at every height it changes.
+pub const ALLOWLIST_TOTALS: SnapshotMap<&str, u64> = SnapshotMap::new(
+    "allowlist_totals",
+    "allowlist_totals__checkpoints",
+    "allowlist_totals__changelog",
+    Strategy::EveryBlock,
+);
+
+/// Addresses subscribed to updates about a poll.
+pub const WATCHERS: Map<(&str, &Addr), Empty> = Map::new("watchers");
+
+pub const WATCHER_COUNTS: Map<&str, u64> = Map::new("watcher_counts");
+
+/// Creators each address muted, keyed (address, creator).
+pub const MUTED: Map<(&Addr, &Addr), Empty> = Map::new("muted");
+/// Creators each address follows, keyed (address, creator).
+pub const FOLLOWED: Map<(&Addr, &Addr), Empty> = Map::new("followed");
+
+/// Raw weights verified through `PrecheckEligibility`, used in place of the
+/// cross-contract query when the address votes.
+pub const ELIGIBILITY: Map<(&str, &Addr), Uint128> = Map::new("eligibility");
+
+/// Contract-wide registry of members and their weights, maintained by the admin.
+pub const MEMBERS: Map<&Addr, u64> = Map::new("members");
+/// Sum of the registry weights, checkpointed at every height it changes.
+pub const MEMBERS_TOTAL: SnapshotItem<u64> = SnapshotItem::new(
+    "members_total",
+    "members_total__checkpoints",
+    "members_total__changelog",
+    Strategy::EveryBlock,
+);
+
+/// The most recent poll opened for each subject.
+pub const SUBJECTS: Map<&str, String> = Map::new("subjects");
+
+pub const ARCHIVE: Map<&str, ArchivedPoll> = Map::new("archive");
+
+/// (chamber, weight) memberships of each voter in a chamber poll.
+pub const CHAMBER_MEMBERS: Map<(&str, &Addr), Vec<(String, u64)>> = Map::new("chamber_members");
+
+/// Nonces of signed creation payloads that have already been used.
+pub const SIGNED_NONCES: Map<u64, Empty> = Map::new("signed_nonces");
+
+/// Number of entries and approximate bytes held by one storage section.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct SectionStats {
+    pub count: u64,
+    pub bytes: u64,
+}
+
+/// Running storage footprint of the contract. Entries are measured (key plus
+/// serialized value) when written and removed; in-place updates such as tally
+/// changes are not re-measured, so byte figures are approximate.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct StorageStats {
+    pub polls: SectionStats,
+    pub ballots: SectionStats,
+    /// Voter and series indexes.
+    pub indexes: SectionStats,
+    pub archive: SectionStats,
+}
+
+pub const STORAGE_STATS: Item<StorageStats> = Item::new("storage_stats");
+
+/// Run of consecutive polls, by sequence number, an address has voted on.
+/// A ballot on a later poll than the next one in line starts a new run.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct Streak {
+    pub current: u64,
+    pub longest: u64,
+    /// Sequence number of the latest poll the address voted on.
+    pub last_poll: u64,
+    /// Number of polls the address has voted on.
+    pub polls_voted: u64,
+}
+
+pub const STREAKS: Map<&Addr, Streak> = Map::new("streaks");
+/// `STREAKS` keyed (u64::MAX - current, u64::MAX - longest, address), so
+/// ascending order ranks the longest streaks first with ties in address order.
+pub const STREAK_RANKING: Map<(u64, u64, &Addr), Empty> = Map::new("streak_ranking");
+
+/// Activity totals since deployment, counted as it happens.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct Stats {
+    pub polls_created: u64,
+    /// Ballots cast, each counted once however often it is changed.
+    pub votes_cast: u64,
+    /// Addresses that have cast at least one ballot.
+    pub unique_voters: u64,
+}
+
+pub const STATS: Item<Stats> = Item::new("stats");
+
+/// Ballots each address currently holds, revoked or removed ones taken off.
+pub const VOTE_COUNTS: Map<&Addr, u64> = Map::new("vote_counts");
+/// `VOTE_COUNTS` keyed (count, address), ranking voters by activity.
+pub const VOTE_RANKING: Map<(u64, &Addr), Empty> = Map::new("vote_ranking");
+
+/// Branding of the space this contract hosts, used by frontends to theme
+/// themselves.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpaceInfo {
+    pub name: String,
+    pub logo_uri: Option<String>,
+    /// Color hints such as `#1a2b3c`, in order of prominence.
+    pub colors: Vec<String>,
+    pub links: Vec<SpaceLink>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpaceLink {
+    pub label: String,
+    pub url: String,
+}
+
+pub const SPACE_INFO: Item<SpaceInfo> = Item::new("space_info");
+
+/// Governance format shared between polls: the rules a `CreatePoll` using
+/// the template inherits wherever it leaves its own settings unset.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct PollRules {
+    pub strategy: Option<VotingStrategy>,
+    pub weight_curve: Option<WeightCurve>,
+    pub weight_cap: Option<WeightCap>,
+    pub correction_window: Option<Duration>,
+    pub min_support: Option<Decimal>,
+    pub max_selections: Option<u32>,
+    pub method: Option<VotingMethod>,
+    pub privacy: Option<Privacy>,
+    pub quorum: Option<Decimal>,
+    pub threshold: Option<Decimal>,
+    pub tie_break: Option<TieBreak>,
+}
+
+pub const TEMPLATES: Map<&str, PollRules> = Map::new("templates");
+
+/// Message kinds executable polls may carry, managed by the admin.
+pub const PERMITTED_MSGS: Item<Vec<PermittedMsg>> = Item::new("permitted_msgs");
+
+/// Scoring of voter reputation, earned in polls as they are finalized.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReputationParams {
+    /// Points per finalized poll voted on.
+    pub participation: u64,
+    /// Extra points per finalized poll where the ballot backed the winner.
+    pub accuracy: u64,
+    /// Weight of an address without reputation in `VotingStrategy::Reputation`
+    /// polls; a score of `scale` doubles it.
+    pub scale: u64,
+}
+
+impl Default for ReputationParams {
+    fn default() -> Self {
+        ReputationParams {
+            participation: 1,
+            accuracy: 2,
+            scale: 10,
+        }
+    }
+}
+
+pub const REPUTATION_PARAMS: Item<ReputationParams> = Item::new("reputation_params");
+
+/// Congestion pricing of the poll deposit, raised for every poll created
+/// recently beyond what the space normally sees.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositPricing {
+    /// Length in seconds of the windows creations are counted over.
+    pub window: u64,
+    /// Creations per window charged no more than the base deposit.
+    pub target: u64,
+    /// Share of the base deposit added for each creation past the target.
+    pub step: Decimal,
+}
+
+pub const DEPOSIT_PRICING: Item<DepositPricing> = Item::new("deposit_pricing");
+
+/// Polls created in the current pricing window and the one before it.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct CreationVolume {
+    /// Index of the current window, counted in windows since the epoch.
+    pub window: u64,
+    pub current: u64,
+    pub previous: u64,
+}
+
+pub const CREATION_VOLUME: Item<CreationVolume> = Item::new("creation_volume");
+
+/// Track record of an address over finalized polls.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct Reputation {
+    pub polls_voted: u64,
+    /// Polls where the address's ballot included the winning option.
+    pub polls_won: u64,
+}
+
+pub const REPUTATION: Map<&Addr, Reputation> = Map::new("reputation");
+/// Finalized polls with voters still to be credited reputation, and the last
+/// voter credited so far.
+pub const REPUTATION_CURSORS: Map<&str, Addr> = Map::new("reputation_cursors");
+/// Deleted or archived polls whose entries keyed by voter or address are
+/// still being removed in batches.
+pub const PRUNING: Map<&str, Empty> = Map::new("pruning");
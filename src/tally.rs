@@ -0,0 +1,94 @@
+use cosmwasm_std::{Decimal, Uint128};
+#[cfg(any(feature = "library", test))]
+use schemars::JsonSchema;
+#[cfg(any(feature = "library", test))]
+use serde::{Deserialize, Serialize};
+
+#[cfg(any(feature = "library", test))]
+use crate::state::{Ballot, VotingStrategy};
+
+/// Outcome of counting a set of ballots, independent of contract storage.
+#[cfg(any(feature = "library", test))]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TallyResult {
+    /// Weight counted for each option, in the poll's option order.
+    pub options: Vec<(String, Uint128)>,
+    /// Sum of the weight of every counted ballot.
+    pub total_weight: Uint128,
+    /// Number of ballots counted.
+    pub ballots: u64,
+}
+
+/// Recomputes a poll's tallies from its ballots without touching storage, so
+/// off-chain verifiers and embedding contracts (via the `library` feature) can
+/// reproduce a result from exported ballots and compare it with the stored one.
+///
+/// Every option a ballot selects receives its weight times the votes placed
+/// on it. Ballots selecting none of `options` are ignored.
+#[cfg(any(feature = "library", test))]
+pub fn compute_tally(
+    options: &[String],
+    ballots: &[Ballot],
+    strategy: &VotingStrategy,
+) -> TallyResult {
+    let mut result = TallyResult {
+        options: options
+            .iter()
+            .map(|option| (option.clone(), Uint128::zero()))
+            .collect(),
+        total_weight: Uint128::zero(),
+        ballots: 0,
+    };
+
+    for ballot in ballots {
+        let weight = match strategy {
+            VotingStrategy::OneAddressOneVote {} | VotingStrategy::Chambers {} => Uint128::new(1),
+            VotingStrategy::Allowlist {}
+            | VotingStrategy::Registry {}
+            | VotingStrategy::Coins {}
+            | VotingStrategy::Cw20Balance {}
+            | VotingStrategy::Staked {}
+            | VotingStrategy::Cw4Group {}
+            | VotingStrategy::Reputation {} => ballot.weight,
+        };
+
+        let mut counted = false;
+        for option in result.options.iter_mut() {
+            let votes = votes_on(&ballot.options, &ballot.amounts, &option.0);
+            if votes > 0 {
+                option.1 += weight * Uint128::from(votes);
+                counted = true;
+            }
+        }
+        if counted {
+            result.total_weight += weight;
+            result.ballots += 1;
+        }
+    }
+
+    result
+}
+
+/// Votes a ballot selecting `options` with `amounts` places on `option`: its
+/// quadratic allocation, one when amounts aren't used, zero if not selected.
+pub fn votes_on(options: &[String], amounts: &[u64], option: &str) -> u64 {
+    match options.iter().position(|selected| selected == option) {
+        Some(i) => amounts.get(i).copied().unwrap_or(1),
+        None => 0,
+    }
+}
+
+/// Options whose share of the total counted weight reaches `min_support`.
+/// Every option is viable when no minimum is set.
+pub fn viable_options(options: &[(String, Uint128)], min_support: Option<Decimal>) -> Vec<String> {
+    let total: Uint128 = options.iter().map(|option| option.1).sum();
+    options
+        .iter()
+        .filter(|option| match min_support {
+            None => true,
+            Some(min_support) if total.is_zero() => min_support.is_zero(),
+            Some(min_support) => Decimal::from_ratio(option.1, total) >= min_support,
+        })
+        .map(|option| option.0.clone())
+        .collect()
+}